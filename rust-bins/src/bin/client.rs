@@ -7,6 +7,7 @@ use crypto_common::{
 use dialoguer::{Input, MultiSelect, Select};
 use dodis_yampolskiy_prf as prf;
 use ed25519_dalek as ed25519;
+use eddsa_ed25519::keypair_from_seed;
 use either::Either::{Left, Right};
 use elgamal::{PublicKey, SecretKey};
 use id::{
@@ -21,6 +22,7 @@ use key_derivation::{words_to_seed, ConcordiumHdWallet, Net};
 use pairing::bls12_381::{Bls12, G1};
 use rand::*;
 use serde_json::{json, to_value};
+use sha2::{Digest, Sha256};
 use std::{
     cmp::max,
     collections::btree_map::BTreeMap,
@@ -44,6 +46,14 @@ fn mk_ip_filename(path: &Path, n: usize) -> (PathBuf, PathBuf) {
     (public, private)
 }
 
+// Generate the name of the file with the anonymity revokers used by the n-th
+// identity provider.
+fn mk_ip_ars_filename(path: &Path, n: usize) -> PathBuf {
+    let mut ars = path.to_path_buf();
+    ars.push(format!("{}{}.ars.json", IP_NAME_PREFIX, n));
+    ars
+}
+
 fn mk_ip_description(n: usize) -> Description {
     let mut s = IP_NAME_PREFIX.to_string();
     s.push_str(&n.to_string());
@@ -283,6 +293,19 @@ struct GenerateIps {
         env = "NUM_ARS"
     )]
     num_ars:      u32,
+    #[structopt(
+        long = "ars",
+        help = "File with existing anonymity revokers. If given no new anonymity revokers are \
+                generated.",
+        conflicts_with = "num-ars"
+    )]
+    ars:          Option<PathBuf>,
+    #[structopt(
+        long = "ar-ids",
+        help = "Identities of the anonymity revokers the identity providers use. If none are \
+                given all anonymity revokers are used."
+    )]
+    ar_ids:       Vec<u32>,
     #[structopt(
         long = "global",
         help = "File with global parameters.",
@@ -307,6 +330,38 @@ struct GenerateIps {
     output_dir:   PathBuf,
 }
 
+#[derive(StructOpt)]
+struct GenerateArs {
+    #[structopt(
+        long = "num",
+        help = "Number of anonymity revokers to generate.",
+        default_value = "5",
+        env = "NUM_ARS"
+    )]
+    num:        u32,
+    #[structopt(
+        long = "first-identity",
+        help = "Identity of the first generated anonymity revoker. The remaining ones get \
+                consecutive identities.",
+        default_value = "1"
+    )]
+    first:      u32,
+    #[structopt(
+        long = "global",
+        help = "File with global parameters.",
+        default_value = "database/global.json",
+        env = "GLOBAL_FILE"
+    )]
+    global:     PathBuf,
+    #[structopt(
+        long = "out-dir",
+        help = "Directory to write the generated anonymity revokers to.",
+        default_value = "database",
+        env = "OUT_DIR"
+    )]
+    output_dir: PathBuf,
+}
+
 #[derive(StructOpt)]
 struct GenerateGlobal {
     #[structopt(
@@ -325,7 +380,9 @@ struct GenerateGlobal {
     genesis_string: String,
     #[structopt(
         long = "seed",
-        help = "Seed file to use when generating group generators.",
+        help = "Seed file to use when generating group generators. The generators are obtained by \
+                hashing the seed to the curve, so the same seed always gives the same global \
+                parameters.",
         env = "SEED_FILE"
     )]
     seed_file:      Option<PathBuf>,
@@ -383,6 +440,13 @@ struct IpSignPio {
         help = "Do not select any attributes to reveal."
     )]
     no_attributes:      bool,
+    #[structopt(
+        long = "attributes",
+        help = "File with a JSON object mapping attribute names to their values. If not given an \
+                interactive choice will be provided.",
+        conflicts_with = "no-attributes"
+    )]
+    attributes:         Option<PathBuf>,
 }
 
 #[derive(StructOpt)]
@@ -421,6 +485,13 @@ struct IpSignPioV1 {
         help = "Do not select any attributes to reveal."
     )]
     no_attributes:      bool,
+    #[structopt(
+        long = "attributes",
+        help = "File with a JSON object mapping attribute names to their values. If not given an \
+                interactive choice will be provided.",
+        conflicts_with = "no-attributes"
+    )]
+    attributes:         Option<PathBuf>,
 }
 
 #[derive(StructOpt)]
@@ -497,6 +568,13 @@ struct CreateCredential {
         help = "Index of the account/credential to be created."
     )]
     index:              Option<u8>,
+    #[structopt(
+        long = "seed",
+        help = "Hex encoded 32 byte seed from which to derive the account keys. If not given \
+                fresh keys are generated.",
+        conflicts_with = "hd-wallet"
+    )]
+    seed:               Option<String>,
 }
 
 #[derive(StructOpt)]
@@ -514,9 +592,17 @@ struct VerifyCredential {
     global:             PathBuf,
     #[structopt(
         long = "ip-info",
-        help = "File with the JSON encoded information about the identity provider."
+        help = "File with the JSON encoded information about the identity provider.",
+        required_unless = "ips",
+        conflicts_with = "ips"
     )]
-    ip_info:            PathBuf,
+    ip_info:            Option<PathBuf>,
+    #[structopt(
+        long = "ips",
+        help = "File with the JSON encoded database of identity providers. The identity provider \
+                of the credential is looked up in it."
+    )]
+    ips:                Option<PathBuf>,
     #[structopt(
         long = "ars",
         help = "File with a list of anonymity revokers.",
@@ -657,6 +743,11 @@ enum IdClient {
                  and private keys."
     )]
     GenerateIps(GenerateIps),
+    #[structopt(
+        name = "generate-ars",
+        about = "Generate given number of anonymity revokers. With public and private keys."
+    )]
+    GenerateArs(GenerateArs),
     #[structopt(name = "generate-global")]
     GenerateGlobal(GenerateGlobal),
     #[structopt(
@@ -712,6 +803,7 @@ fn main() {
         StartIp(ip) => handle_start_ip(ip),
         StartIpV1(ip) => handle_start_ip_v1(ip),
         GenerateIps(ips) => handle_generate_ips(ips),
+        GenerateArs(ars) => handle_generate_ars(ars),
         GenerateGlobal(gl) => handle_generate_global(gl),
         IpSignPio(isp) => handle_act_as_ip(isp),
         IpSignPioV1(isp) => handle_act_as_ip_v1(isp),
@@ -787,14 +879,47 @@ fn handle_make_account(macc: MakeAccount) {
 }
 
 fn handle_verify_credential(vcred: VerifyCredential) {
-    let ip_info = match read_ip_info(vcred.ip_info) {
+    let credential = match read_credential(vcred.credential) {
         Ok(v) => v,
-        Err(err) => {
-            eprintln!("Could not read identity provider info because {}", err);
+        Err(e) => {
+            eprintln!("Error reading credential: {}", e);
             return;
         }
     };
 
+    let ip_info = match (vcred.ip_info, vcred.ips) {
+        (Some(ip_info), _) => match read_ip_info(ip_info) {
+            Ok(v) => v,
+            Err(err) => {
+                eprintln!("Could not read identity provider info because {}", err);
+                return;
+            }
+        },
+        (None, Some(ips)) => {
+            let mut ips = match read_identity_providers(ips) {
+                Ok(v) => v,
+                Err(err) => {
+                    eprintln!("Could not read identity providers because {}", err);
+                    return;
+                }
+            };
+            match ips
+                .identity_providers
+                .remove(&credential.values.ip_identity)
+            {
+                Some(v) => v,
+                None => {
+                    eprintln!(
+                        "Identity provider {} of the credential is not in the database.",
+                        credential.values.ip_identity
+                    );
+                    return;
+                }
+            }
+        }
+        (None, None) => panic!("One of (ip-info, ips) is required."),
+    };
+
     // we also read the global context from another json file (called
     // global.context). We need commitment keys and other data in there.
     let global_ctx = {
@@ -814,14 +939,6 @@ fn handle_verify_credential(vcred: VerifyCredential) {
         }
     };
 
-    let credential = match read_credential(vcred.credential) {
-        Ok(v) => v,
-        Err(e) => {
-            eprintln!("Error reading credential: {}", e);
-            return;
-        }
-    };
-
     let new_or_existing = match (vcred.expiry, vcred.account) {
         (None, None) => panic!("One of (expiry, address) is required."),
         (None, Some(addr)) => Right(addr),
@@ -829,16 +946,80 @@ fn handle_verify_credential(vcred: VerifyCredential) {
         (Some(_), Some(_)) => panic!("Exactly one of (expiry, address) is required."),
     };
 
-    if let Err(e) = id::chain::verify_cdi(
+    // The sigma proofs (including the openings of the commitments to the revealed
+    // attributes) are combined into a single proof, so they can only be checked
+    // together. The remaining checks, including the policy and the account
+    // signatures that verify_cdi checks last, are done independently so that all
+    // failures are reported, not only the first one.
+    let ip_check = if ip_info.ip_identity == credential.values.ip_identity {
+        Ok(())
+    } else {
+        Err(format!(
+            "The credential is for identity provider {}, but the given identity provider is {}.",
+            credential.values.ip_identity, ip_info.ip_identity
+        ))
+    };
+
+    let proofs_check = match id::chain::verify_cdi(
         &global_ctx,
         &ip_info,
         &all_ars_infos.anonymity_revokers,
         &credential,
         &new_or_existing,
     ) {
-        eprintln!("Credential verification failed due to {}", e)
+        // These are only checked after all the proofs have been verified, and are
+        // reported as separate checks below.
+        Ok(())
+        | Err(id::chain::CdiVerificationError::AccountOwnership)
+        | Err(id::chain::CdiVerificationError::Policy) => Ok(()),
+        Err(e) => Err(e.to_string()),
+    };
+
+    let policy_check = if id::chain::verify_policy(
+        &global_ctx.on_chain_commitment_key,
+        &credential.proofs.id_proofs.commitments,
+        &credential.values.policy,
+    ) {
+        Ok(())
+    } else {
+        Err(id::chain::CdiVerificationError::Policy.to_string())
+    };
+
+    let signed = id::utils::credential_hash_to_sign(
+        &credential.values,
+        &credential.proofs.id_proofs,
+        &new_or_existing,
+    );
+    let account_check = if id::utils::verify_account_ownership_proof(
+        &credential.values.cred_key_info.keys,
+        credential.values.cred_key_info.threshold,
+        &credential.proofs.proof_acc_sk,
+        &signed,
+    ) {
+        Ok(())
     } else {
+        Err("The account signatures do not verify.".to_owned())
+    };
+
+    let checks = [
+        ("identityProvider", ip_check),
+        ("proofs", proofs_check),
+        ("policy", policy_check),
+        ("accountOwnership", account_check),
+    ];
+    let verifies = checks.iter().all(|(_, result)| result.is_ok());
+    let report = checks
+        .iter()
+        .map(|(name, result)| match result {
+            Ok(()) => json!({ "check": name, "passed": true }),
+            Err(reason) => json!({ "check": name, "passed": false, "reason": reason }),
+        })
+        .collect::<Vec<_>>();
+    output_json(&report);
+    if verifies {
         eprintln!("Credential verifies.")
+    } else {
+        eprintln!("Credential verification failed.")
     }
 }
 
@@ -954,6 +1135,22 @@ impl<P: Pairing, C: Curve<Scalar = P::ScalarField>, AttributeType: Attribute<C::
     }
 }
 
+/// Parse a hex encoded 32 byte seed.
+fn parse_seed(s: &str) -> Result<[u8; 32], String> {
+    let bytes = hex::decode(s).map_err(|e| e.to_string())?;
+    <[u8; 32]>::try_from(bytes.as_slice())
+        .map_err(|_| format!("The seed must be exactly 32 bytes, not {}.", bytes.len()))
+}
+
+/// Derive the seed for the account key with the given index, so that each key
+/// of the account is derived from a different seed.
+fn key_seed(seed: &[u8; 32], index: u8) -> [u8; 32] {
+    let mut h = Sha256::new();
+    h.update(seed);
+    h.update([index]);
+    h.finalize().into()
+}
+
 /// Read the identity object, select attributes to reveal and create a
 /// transaction.
 fn handle_create_credential(cc: CreateCredential) {
@@ -964,7 +1161,7 @@ fn handle_create_credential(cc: CreateCredential) {
                 Ok(v) => SomeIdentityObject::IdoV1(v),
                 Err(x) => {
                     eprintln!("Could not read identity object because {}", x);
-                    return;
+                    std::process::exit(1);
                 }
             },
         }
@@ -974,7 +1171,7 @@ fn handle_create_credential(cc: CreateCredential) {
         Ok(v) => v,
         Err(err) => {
             eprintln!("Could not read identity provider info because {}", err);
-            return;
+            std::process::exit(1);
         }
     };
 
@@ -985,7 +1182,7 @@ fn handle_create_credential(cc: CreateCredential) {
             gc
         } else {
             eprintln!("Cannot read global context information database. Terminating.");
-            return;
+            std::process::exit(1);
         }
     };
 
@@ -1009,7 +1206,7 @@ fn handle_create_credential(cc: CreateCredential) {
             Ok(idxs) => idxs,
             Err(x) => {
                 eprintln!("You need to select which attributes you want. {}", x);
-                return;
+                std::process::exit(1);
             }
         }
     };
@@ -1022,12 +1219,12 @@ fn handle_create_credential(cc: CreateCredential) {
             Some(elem) => {
                 if revealed_attributes.insert(*tag, elem.clone()).is_some() {
                     eprintln!("Duplicate attribute idx.");
-                    return;
+                    std::process::exit(1);
                 }
             }
             None => {
                 eprintln!("Selected an attribute which does not exist. Aborting.");
-                return;
+                std::process::exit(1);
             }
         }
     }
@@ -1059,15 +1256,26 @@ fn handle_create_credential(cc: CreateCredential) {
                 Ok(v) => v,
                 Err(x) => {
                     eprintln!("Could not read ID use data object because: {}", x);
-                    return;
+                    std::process::exit(1);
+                }
+            };
+            let seed = match cc.seed.as_deref().map(parse_seed).transpose() {
+                Ok(seed) => seed,
+                Err(e) => {
+                    eprintln!("Could not parse the seed because: {}", e);
+                    std::process::exit(1);
                 }
             };
             let acc_data = {
                 let mut csprng = thread_rng();
                 let mut keys = BTreeMap::new();
-                keys.insert(KeyIndex(0), KeyPair::generate(&mut csprng));
-                keys.insert(KeyIndex(1), KeyPair::generate(&mut csprng));
-                keys.insert(KeyIndex(2), KeyPair::generate(&mut csprng));
+                for i in 0..3 {
+                    let key = match seed {
+                        Some(ref seed) => KeyPair::from(keypair_from_seed(&key_seed(seed, i))),
+                        None => KeyPair::generate(&mut csprng),
+                    };
+                    keys.insert(KeyIndex(i), key);
+                }
 
                 CredentialData {
                     keys,
@@ -1081,7 +1289,7 @@ fn handle_create_credential(cc: CreateCredential) {
                 Ok(w) => w,
                 Err(e) => {
                     eprintln!("Could not read file because {}", e);
-                    return;
+                    std::process::exit(1);
                 }
             };
             let identity_provider_index = ip_info.ip_identity.0;
@@ -1097,7 +1305,7 @@ fn handle_create_credential(cc: CreateCredential) {
                     Ok(prf) => prf,
                     Err(e) => {
                         eprintln!("Could not get prf key because {}", e);
-                        return;
+                        std::process::exit(1);
                     }
                 };
 
@@ -1106,7 +1314,7 @@ fn handle_create_credential(cc: CreateCredential) {
                     Ok(scalar) => scalar,
                     Err(e) => {
                         eprintln!("Could not get idCredSec because {}", e);
-                        return;
+                        std::process::exit(1);
                     }
                 };
 
@@ -1115,7 +1323,7 @@ fn handle_create_credential(cc: CreateCredential) {
                     Ok(scalar) => scalar,
                     Err(e) => {
                         eprintln!("Could not get blinding randomness because {}", e);
-                        return;
+                        std::process::exit(1);
                     }
                 };
 
@@ -1136,7 +1344,7 @@ fn handle_create_credential(cc: CreateCredential) {
                 Ok(scalar) => scalar,
                 Err(e) => {
                     eprintln!("Could not get account signing key because {}", e);
-                    return;
+                    std::process::exit(1);
                 }
             };
             let cred_data = {
@@ -1159,6 +1367,16 @@ fn handle_create_credential(cc: CreateCredential) {
         }
     };
 
+    // Check that the identity object was in fact signed by the identity provider
+    // before doing any work with it.
+    if !verify_identity_object_signature(&ip_info, &id_object, &id_use_data) {
+        eprintln!(
+            "The identity provider's signature on the identity object is not valid. Make sure the \
+             identity object, identity provider, and private identity object data match."
+        );
+        std::process::exit(1);
+    }
+
     // Now we have have everything we need to generate the proofs
     // we have
     // - chi
@@ -1174,7 +1392,7 @@ fn handle_create_credential(cc: CreateCredential) {
             ars.anonymity_revokers
         } else {
             eprintln!("Cannot read anonymity revokers from the database. Terminating.");
-            return;
+            std::process::exit(1);
         }
     };
 
@@ -1218,10 +1436,19 @@ fn handle_create_credential(cc: CreateCredential) {
         Ok(cdi) => cdi,
         Err(x) => {
             eprintln!("Could not generate the credential because {}", x);
-            return;
+            std::process::exit(1);
         }
     };
 
+    // Double check that the generated CDI is going to be successfully validated.
+    if let Err(e) = id::chain::verify_cdi(&global_ctx, &ip_info, &ars, &cdi, &new_or_existing) {
+        eprintln!(
+            "Something went terribly wrong and the generated CDI is not valid because {}",
+            e
+        );
+        std::process::exit(1);
+    }
+
     let address = account_address_from_registration_id(&cdi.values.cred_id);
 
     let cdi_no_proofs = AccountCredentialWithoutProofs::Normal {
@@ -1259,7 +1486,10 @@ fn handle_create_credential(cc: CreateCredential) {
             "credentials": versioned_credentials,
             "commitmentsRandomness": randomness_map,
         });
-        output_possibly_encrypted(&cc.keys_out, &js).ok();
+        if let Err(e) = output_possibly_encrypted(&cc.keys_out, &js) {
+            eprintln!("Could not write the account keys because {}", e);
+            std::process::exit(1);
+        }
     } else {
         let account_data_json = json!({
             "address": address,
@@ -1275,40 +1505,35 @@ fn handle_create_credential(cc: CreateCredential) {
              written to file {}.",
             cc.keys_out.to_string_lossy()
         );
-        output_possibly_encrypted(&cc.keys_out, &account_data_json).ok();
+        if let Err(e) = output_possibly_encrypted(&cc.keys_out, &account_data_json) {
+            eprintln!("Could not write the account keys because {}", e);
+            std::process::exit(1);
+        }
     }
 
-    // Double check that the generated CDI is going to be successfully validated.
-    // let checked = verify_cdi(&global_ctx, &ip_info, &cdi);
-    // if let Err(e) = checked {
-    //     eprintln!(
-    //         "Something went terribly wrong and the generated CDI is not valid
-    // because {}",         e
-    //     );
-    //     return;
-    // };
-
     // Now simply output the credential object in the transaction format
     // accepted by the simple-client for sending transactions.
-
+    // If it is an existing account then just write the credential.
+    // Otherwise write the credential message that can be sent to the chain.
+    let cdi_json_value = match new_or_existing {
+        Left(tt) => to_value(&Versioned::new(VERSION_0, AccountCredentialMessage {
+            message_expiry: tt,
+            credential:     cdi,
+        }))
+        .expect("Cannot fail."),
+        Right(_) => to_value(&Versioned::new(VERSION_0, cdi)).expect("Cannot fail"),
+    };
     if let Some(json_file) = cc.out {
-        // if it is an existing account then just write the credential.
-        // otherwise write the credential message that can be sent to the chain.
-        let cdi_json_value = match new_or_existing {
-            Left(tt) => to_value(&Versioned::new(VERSION_0, AccountCredentialMessage {
-                message_expiry: tt,
-                credential:     cdi,
-            }))
-            .expect("Cannot fail."),
-            Right(_) => to_value(&Versioned::new(VERSION_0, cdi)).expect("Cannot fail"),
-        };
         match write_json_to_file(json_file, &cdi_json_value) {
             Ok(_) => println!("Wrote transaction payload to JSON file."),
             Err(e) => {
                 eprintln!("Could not JSON write to file because {}", e);
                 output_json(&cdi_json_value);
+                std::process::exit(1);
             }
         }
+    } else {
+        output_json(&cdi_json_value);
     }
 }
 
@@ -1536,25 +1761,33 @@ fn handle_act_as_ip(aai: IpSignPio) {
 
     let created_at = YearMonth::now();
 
-    let tags = {
-        if !aai.no_attributes {
-            match MultiSelect::new()
-                .with_prompt("Select attributes:")
-                .items(&ATTRIBUTE_NAMES)
-                .interact()
-            {
-                Ok(idxs) => idxs,
-                Err(x) => {
-                    eprintln!("You have to choose some attributes. Terminating. {}", x);
-                    return;
-                }
+    let alist = if let Some(attributes_file) = aai.attributes {
+        match read_json_from_file::<_, BTreeMap<AttributeTag, ExampleAttribute>>(attributes_file) {
+            Ok(alist) => alist,
+            Err(e) => {
+                eprintln!("Could not read the attribute list because: {}", e);
+                return;
             }
-        } else {
-            Vec::new()
         }
-    };
+    } else {
+        let tags = {
+            if !aai.no_attributes {
+                match MultiSelect::new()
+                    .with_prompt("Select attributes:")
+                    .items(&ATTRIBUTE_NAMES)
+                    .interact()
+                {
+                    Ok(idxs) => idxs,
+                    Err(x) => {
+                        eprintln!("You have to choose some attributes. Terminating. {}", x);
+                        return;
+                    }
+                }
+            } else {
+                Vec::new()
+            }
+        };
 
-    let alist = {
         let mut alist: BTreeMap<AttributeTag, ExampleAttribute> = BTreeMap::new();
         for idx in tags {
             match Input::new().with_prompt(ATTRIBUTE_NAMES[idx]).interact() {
@@ -1700,25 +1933,33 @@ fn handle_act_as_ip_v1(aai: IpSignPioV1) {
 
     let created_at = YearMonth::now();
 
-    let tags = {
-        if !aai.no_attributes {
-            match MultiSelect::new()
-                .with_prompt("Select attributes:")
-                .items(&ATTRIBUTE_NAMES)
-                .interact()
-            {
-                Ok(idxs) => idxs,
-                Err(x) => {
-                    eprintln!("You have to choose some attributes. Terminating. {}", x);
-                    return;
-                }
+    let alist = if let Some(attributes_file) = aai.attributes {
+        match read_json_from_file::<_, BTreeMap<AttributeTag, ExampleAttribute>>(attributes_file) {
+            Ok(alist) => alist,
+            Err(e) => {
+                eprintln!("Could not read the attribute list because: {}", e);
+                return;
             }
-        } else {
-            Vec::new()
         }
-    };
+    } else {
+        let tags = {
+            if !aai.no_attributes {
+                match MultiSelect::new()
+                    .with_prompt("Select attributes:")
+                    .items(&ATTRIBUTE_NAMES)
+                    .interact()
+                {
+                    Ok(idxs) => idxs,
+                    Err(x) => {
+                        eprintln!("You have to choose some attributes. Terminating. {}", x);
+                        return;
+                    }
+                }
+            } else {
+                Vec::new()
+            }
+        };
 
-    let alist = {
         let mut alist: BTreeMap<AttributeTag, ExampleAttribute> = BTreeMap::new();
         for idx in tags {
             match Input::new().with_prompt(ATTRIBUTE_NAMES[idx]).interact() {
@@ -1924,7 +2165,6 @@ fn handle_start_ip(sip: StartIp) {
         }
     };
 
-    let context = IpContext::new(&ip_info, &choice_ars, &global_ctx);
     // and finally generate the pre-identity object
     // we also retrieve the randomness which we must keep private.
     // This randomness must be used
@@ -1940,8 +2180,15 @@ fn handle_start_ip(sip: StartIp) {
     };
     let randomness = ps_sig::SigRetrievalRandomness::generate_non_zero(&mut csprng);
     let id_use_data = IdObjectUseData { aci, randomness };
-    let (pio, _) = generate_pio(&context, threshold, &id_use_data, &initial_acc_data)
-        .expect("Generating the pre-identity object should succeed.");
+    let pio = create_pre_identity_object(
+        &ip_info,
+        &choice_ars,
+        threshold,
+        &global_ctx,
+        &id_use_data,
+        &initial_acc_data,
+    )
+    .expect("Generating the pre-identity object should succeed.");
 
     // the only thing left is to output all the information
 
@@ -2109,10 +2356,10 @@ fn handle_start_ip_v1(sip: StartIpV1) {
         }
     };
 
-    let context = IpContext::new(&ip_info, &choice_ars, &global_ctx);
     // and finally generate the pre-identity object
-    let (pio, _) = generate_pio_v1(&context, threshold, &id_use_data)
-        .expect("Generating the pre-identity object should succeed.");
+    let pio =
+        create_pre_identity_object_v1(&ip_info, &choice_ars, threshold, &global_ctx, &id_use_data)
+            .expect("Generating the pre-identity object should succeed.");
 
     // the only thing left is to output all the information
 
@@ -2129,6 +2376,83 @@ fn handle_start_ip_v1(sip: StartIpV1) {
     }
 }
 
+/// Generate anonymity revokers with the given identities, and write each of
+/// them, as well as the list of all of them, to the output directory.
+fn generate_ars<R: Rng>(
+    global_ctx: &GlobalContext<G1>,
+    ar_identities: impl IntoIterator<Item = ArIdentity>,
+    output_dir: &Path,
+    csprng: &mut R,
+) -> io::Result<ArInfos<G1>> {
+    let ar_base = global_ctx.on_chain_commitment_key.g;
+    let mut all_ars = ArInfos {
+        anonymity_revokers: BTreeMap::new(),
+    };
+
+    for ar_identity in ar_identities {
+        let i = u32::from(ar_identity);
+        let ar_secret_key = SecretKey::generate(&ar_base, csprng);
+        let ar_public_key = PublicKey::from(&ar_secret_key);
+        let public_ar_info = ArInfo {
+            ar_identity,
+            ar_description: mk_ar_description(i),
+            ar_public_key,
+        };
+        let (ar_pub_fname, ar_fname) = mk_ar_filename(output_dir, i);
+        let ar_data = ArData {
+            public_ar_info,
+            ar_secret_key,
+        };
+        println!("writing AR({}) in file {:?}", i, ar_fname);
+        write_json_to_file(&ar_fname, &ar_data)?;
+        println!("writing public AR({}) in file {:?}", i, ar_pub_fname);
+        let ver_public_ar_info = Versioned::new(VERSION_0, ar_data.public_ar_info.clone());
+        write_json_to_file(&ar_pub_fname, &ver_public_ar_info)?;
+        let _ = all_ars
+            .anonymity_revokers
+            .insert(ar_identity, ar_data.public_ar_info);
+    }
+
+    let mut ars_path = output_dir.to_path_buf();
+    ars_path.push("anonymity_revokers.json");
+    let ver_all_ars = Versioned::new(VERSION_0, &all_ars);
+    write_json_to_file(&ars_path, &ver_all_ars)?;
+    println!("Wrote out anonymity revokers to {}", ars_path.display());
+    Ok(all_ars)
+}
+
+/// Generate standalone anonymity revokers.
+fn handle_generate_ars(gar: GenerateArs) {
+    let mut csprng = thread_rng();
+
+    let global_ctx = {
+        if let Some(gc) = read_global_context(gar.global) {
+            gc
+        } else {
+            eprintln!("Cannot read global context information database. Terminating.");
+            return;
+        }
+    };
+
+    let ar_identities = match (gar.first..gar.first.saturating_add(gar.num))
+        .map(ArIdentity::try_from)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Invalid anonymity revoker identity: {}", e);
+            return;
+        }
+    };
+
+    println!("Generating {} anonymity revokers.", gar.num);
+    if let Err(err) = generate_ars(&global_ctx, ar_identities, &gar.output_dir, &mut csprng) {
+        eprintln!("Could not write out anonymity revokers: {}", err);
+        return;
+    }
+    println!("Done.");
+}
+
 /// Generate identity providers with public and private information as well as
 /// anonymity revokers. For now we generate identity providers with names
 /// IP_PREFIX-i.json and its associated anonymity revoker has name
@@ -2136,11 +2460,6 @@ fn handle_start_ip_v1(sip: StartIpV1) {
 fn handle_generate_ips(gip: GenerateIps) {
     let mut csprng = thread_rng();
     let num: usize = gip.num;
-    let num_ars: u32 = gip.num_ars;
-
-    // First generate anonymity revokers with ids 1..num-ars.
-    println!("Generating {} anonymity revokers.", num_ars);
-    let mut ar_identities = Vec::with_capacity(num_ars as usize);
 
     // we also read the global context from another json file (called
     // global.context). We need the generator from there.
@@ -2152,69 +2471,120 @@ fn handle_generate_ips(gip: GenerateIps) {
             return;
         }
     };
-    {
-        let ar_base = global_ctx.on_chain_commitment_key.g;
-        let mut all_ars = ArInfos {
-            anonymity_revokers: BTreeMap::new(),
-        };
 
-        for i in 1..=num_ars {
-            let ar_secret_key = SecretKey::generate(&ar_base, &mut csprng);
-            let ar_public_key = PublicKey::from(&ar_secret_key);
-            let ar_identity = ArIdentity::try_from(i).unwrap();
-            let public_ar_info = ArInfo {
-                ar_identity,
-                ar_description: mk_ar_description(i),
-                ar_public_key,
-            };
-            ar_identities.push(ar_identity);
-            let (ar_pub_fname, ar_fname) = mk_ar_filename(&gip.output_dir, i);
-            let ar_data = ArData {
-                public_ar_info,
-                ar_secret_key,
-            };
-            println!("writing AR({}) in file {:?}", i, ar_fname);
-            if let Err(err) = write_json_to_file(&ar_fname, &ar_data) {
-                eprintln!("Could not write anonymity revoker {}: {}", i, err);
+    // Either use the existing anonymity revokers, or generate new ones with ids
+    // 1..num-ars.
+    let ars = if let Some(ars) = gip.ars {
+        match read_anonymity_revokers(&ars) {
+            Ok(ars) => {
+                println!(
+                    "Using existing anonymity revokers {:?}.",
+                    ars.anonymity_revokers.keys().collect::<Vec<_>>()
+                );
+                ars
+            }
+            Err(err) => {
+                eprintln!("Could not read anonymity revokers: {}", err);
                 return;
             }
-            println!("writing public AR({}) in file {:?}", i, ar_fname);
-            let ver_public_ar_info = Versioned::new(VERSION_0, ar_data.public_ar_info.clone());
-            if let Err(err) = write_json_to_file(&ar_pub_fname, &ver_public_ar_info) {
-                eprintln!("Could not write anonymity revoker {}: {}", i, err);
+        }
+    } else {
+        println!("Generating {} anonymity revokers.", gip.num_ars);
+        let ar_identities = (1..=gip.num_ars).map(|i| ArIdentity::try_from(i).unwrap());
+        match generate_ars(&global_ctx, ar_identities, &gip.output_dir, &mut csprng) {
+            Ok(ars) => ars,
+            Err(err) => {
+                eprintln!("Could not write out anonymity revokers: {}", err);
                 return;
             }
-            let _ = all_ars
-                .anonymity_revokers
-                .insert(ar_identity, ar_data.public_ar_info);
         }
+    };
 
-        let mut ars_path = gip.output_dir.clone();
-        ars_path.push("anonymity_revokers.json");
-        let ver_all_ars = Versioned::new(VERSION_0, all_ars);
-        if let Err(err) = write_json_to_file(ars_path.clone(), &ver_all_ars) {
-            eprintln!("Could not write out anonymity revokers: {}", err);
+    let ar_identities = match gip
+        .ar_ids
+        .iter()
+        .copied()
+        .map(ArIdentity::try_from)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Invalid anonymity revoker identity: {}", e);
             return;
-        } else {
-            println!("Wrote out anonymity revokers to {}", ars_path.display())
         }
-    }
+    };
+    let ars = match select_ars(&ars, &ar_identities) {
+        Ok(ars) => ars,
+        Err(ar_identity) => {
+            eprintln!("Anonymity revoker {} does not exist.", ar_identity);
+            return;
+        }
+    };
 
     println!("Generating {} identity providers.", num);
+    let all_idps = match generate_ips(num, gip.key_capacity, &ars, &gip.output_dir, &mut csprng) {
+        Ok(all_idps) => all_idps,
+        Err(err) => {
+            eprintln!("Could not write out identity providers: {}", err);
+            return;
+        }
+    };
+    let mut ips_path = gip.output_dir;
+    ips_path.push("identity_providers.json");
+    let ver_all_idps = Versioned::new(VERSION_0, all_idps);
+    if let Err(err) = write_json_to_file(ips_path, &ver_all_idps) {
+        eprintln!("Could not write out list of identity providers: {}", err);
+        return;
+    }
+    println!("Done.");
+}
+
+/// Restrict the anonymity revokers to the ones with the given identities. If no
+/// identities are given all anonymity revokers are kept. If one of the
+/// identities does not exist it is returned as the error.
+fn select_ars(ars: &ArInfos<G1>, ar_identities: &[ArIdentity]) -> Result<ArInfos<G1>, ArIdentity> {
+    if ar_identities.is_empty() {
+        return Ok(ArInfos {
+            anonymity_revokers: ars.anonymity_revokers.clone(),
+        });
+    }
+    let mut selected = ArInfos {
+        anonymity_revokers: BTreeMap::new(),
+    };
+    for ar_identity in ar_identities {
+        let ar_info = ars
+            .anonymity_revokers
+            .get(ar_identity)
+            .ok_or(*ar_identity)?;
+        selected
+            .anonymity_revokers
+            .insert(*ar_identity, ar_info.clone());
+    }
+    Ok(selected)
+}
+
+/// Generate `num` identity providers whose keys can sign `key_capacity`
+/// attributes. For each identity provider its public and private data, and the
+/// anonymity revokers it uses, are written to files in `output_dir`. The
+/// public information of all the generated identity providers is returned.
+fn generate_ips<R: Rng + CryptoRng>(
+    num: usize,
+    key_capacity: usize,
+    ars: &ArInfos<G1>,
+    output_dir: &Path,
+    csprng: &mut R,
+) -> io::Result<IpInfos<Bls12>> {
     let mut all_idps = IpInfos {
         identity_providers: BTreeMap::new(),
     };
+    let ver_ars = Versioned::new(VERSION_0, ars);
     for id in 0..num {
-        // generate an identity provider and for each
-        // identity provider three anonymity revokers
-        let (ip_fname_pub, ip_fname) = mk_ip_filename(&gip.output_dir, id);
+        let (ip_fname_pub, ip_fname) = mk_ip_filename(output_dir, id);
 
-        // TODO: hard-coded length of the key for now, but should be changed
-        // based on the maximum length of the attribute list
-        let id_secret_key = ps_sig::SecretKey::<Bls12>::generate(gip.key_capacity, &mut csprng);
+        let id_secret_key = ps_sig::SecretKey::<Bls12>::generate(key_capacity, csprng);
         let id_public_key = ps_sig::PublicKey::from(&id_secret_key);
 
-        let keypair = ed25519::Keypair::generate(&mut csprng);
+        let keypair = ed25519::Keypair::generate(csprng);
         let ip_cdi_verify_key = keypair.public;
         let ip_cdi_secret_key = keypair.secret;
 
@@ -2231,32 +2601,26 @@ fn handle_generate_ips(gip: GenerateIps) {
             ip_cdi_secret_key,
         };
         println!("writing ip_{} in file {}", id, ip_fname.display());
-        if let Err(err) = write_json_to_file(&ip_fname, &full_info) {
-            eprintln!("Could not write out identity provider: {}", err);
-            return;
-        }
+        write_json_to_file(&ip_fname, &full_info)?;
         let versioned_ip_info_public = Versioned::new(VERSION_0, full_info.public_ip_info.clone());
         println!(
             "writing ip_{} public data in file {}",
             id,
             ip_fname_pub.display()
         );
-        if let Err(err) = write_json_to_file(&ip_fname_pub, &versioned_ip_info_public) {
-            eprintln!("Could not write out identity provider: {}", err);
-            return;
-        }
+        write_json_to_file(&ip_fname_pub, &versioned_ip_info_public)?;
+        let ip_ars_fname = mk_ip_ars_filename(output_dir, id);
+        println!(
+            "writing anonymity revokers of ip_{} in file {}",
+            id,
+            ip_ars_fname.display()
+        );
+        write_json_to_file(&ip_ars_fname, &ver_ars)?;
         all_idps
             .identity_providers
             .insert(ip_id, full_info.public_ip_info);
     }
-    let mut ips_path = gip.output_dir;
-    ips_path.push("identity_providers.json");
-    let ver_all_idps = Versioned::new(VERSION_0, all_idps);
-    if let Err(err) = write_json_to_file(ips_path, &ver_all_idps) {
-        eprintln!("Could not write out list of identity providers: {}", err);
-        return;
-    }
-    println!("Done.");
+    Ok(all_idps)
 }
 
 /// Generate the global context.
@@ -2349,3 +2713,323 @@ fn handle_validate_recovery(vir: ValidateIdRecoveryRequest) {
     let result = validate_id_recovery_request(&ip_info, &global_ctx, &request);
     println!("ID recovery validation result: {}", result);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate_ars() {
+        let mut csprng = thread_rng();
+        let global_ctx = GlobalContext::<G1>::generate(String::from("genesis_string"));
+        let mut output_dir = std::env::temp_dir();
+        output_dir.push(format!("generate-ars-{}", csprng.gen::<u64>()));
+        std::fs::create_dir_all(&output_dir).expect("Could not create output directory.");
+
+        let ar_identities = [3, 5, 7]
+            .iter()
+            .map(|&i| ArIdentity::try_from(i).unwrap())
+            .collect::<Vec<_>>();
+        let generated = generate_ars(
+            &global_ctx,
+            ar_identities.iter().copied(),
+            &output_dir,
+            &mut csprng,
+        )
+        .expect("Generating anonymity revokers should succeed.");
+
+        let mut ars_path = output_dir.clone();
+        ars_path.push("anonymity_revokers.json");
+        let read = read_anonymity_revokers(&ars_path).expect("Could not read anonymity revokers.");
+        assert_eq!(
+            read.anonymity_revokers.keys().copied().collect::<Vec<_>>(),
+            ar_identities
+        );
+        for ar_identity in ar_identities {
+            let (ar_pub_fname, ar_fname) = mk_ar_filename(&output_dir, u32::from(ar_identity));
+            let public: Versioned<ArInfo<G1>> =
+                read_json_from_file(&ar_pub_fname).expect("Could not read public AR data.");
+            let private: ArData<G1> =
+                read_json_from_file(&ar_fname).expect("Could not read AR data.");
+            assert_eq!(public.value.ar_identity, ar_identity);
+            assert!(
+                PublicKey::from(&private.ar_secret_key)
+                    == generated.anonymity_revokers[&ar_identity].ar_public_key,
+                "The public key should match the secret key."
+            );
+        }
+        std::fs::remove_dir_all(&output_dir).expect("Could not clean up output directory.");
+    }
+
+    #[test]
+    fn test_generate_ips_with_selected_ars() {
+        let mut csprng = thread_rng();
+        let global_ctx = GlobalContext::<G1>::generate(String::from("genesis_string"));
+        let mut output_dir = std::env::temp_dir();
+        output_dir.push(format!("generate-ips-{}", csprng.gen::<u64>()));
+        std::fs::create_dir_all(&output_dir).expect("Could not create output directory.");
+
+        let all_ars = generate_ars(
+            &global_ctx,
+            (1..=3).map(|i| ArIdentity::try_from(i).unwrap()),
+            &output_dir,
+            &mut csprng,
+        )
+        .expect("Generating anonymity revokers should succeed.");
+        let missing = ArIdentity::try_from(4).unwrap();
+        assert_eq!(
+            select_ars(&all_ars, &[missing]).err(),
+            Some(missing),
+            "Selecting an anonymity revoker that does not exist should fail."
+        );
+
+        let ar_identities = [1, 3]
+            .iter()
+            .map(|&i| ArIdentity::try_from(i).unwrap())
+            .collect::<Vec<_>>();
+        let ars = select_ars(&all_ars, &ar_identities)
+            .expect("The selected anonymity revokers should exist.");
+        let ips = generate_ips(1, 10, &ars, &output_dir, &mut csprng)
+            .expect("Generating identity providers should succeed.");
+        assert_eq!(
+            ips.identity_providers.keys().copied().collect::<Vec<_>>(),
+            vec![IpIdentity(0)]
+        );
+
+        let ip_ars = read_anonymity_revokers(mk_ip_ars_filename(&output_dir, 0))
+            .expect("Could not read the anonymity revokers of the identity provider.");
+        assert_eq!(
+            ip_ars
+                .anonymity_revokers
+                .keys()
+                .copied()
+                .collect::<Vec<_>>(),
+            ar_identities,
+            "The identity provider should use exactly the selected anonymity revokers."
+        );
+        for ar_identity in ar_identities {
+            assert_eq!(
+                ip_ars.anonymity_revokers[&ar_identity],
+                all_ars.anonymity_revokers[&ar_identity]
+            );
+        }
+        let (_, ip_fname) = mk_ip_filename(&output_dir, 0);
+        let ip_data: IpData<Bls12> =
+            read_json_from_file(&ip_fname).expect("Could not read identity provider data.");
+        assert_eq!(ip_data.public_ip_info.ip_identity, IpIdentity(0));
+        std::fs::remove_dir_all(&output_dir).expect("Could not clean up output directory.");
+    }
+
+    /// Inputs of `ip-sign-pio` and `ip-sign-pio-v1` written to a directory.
+    struct IpSignPioInputs {
+        dir:                PathBuf,
+        ip_info:            IpInfo<Bls12>,
+        ars:                ArInfos<G1>,
+        global_ctx:         GlobalContext<G1>,
+        id_use_data:        IdObjectUseData<Bls12, ExampleCurve>,
+        ip_data:            PathBuf,
+        global:             PathBuf,
+        anonymity_revokers: PathBuf,
+        attributes:         PathBuf,
+    }
+
+    /// The attributes that are signed from the attributes file.
+    fn test_attributes() -> serde_json::Value {
+        json!({
+            "firstName": "John",
+            "countryOfResidence": "DK",
+        })
+    }
+
+    /// Generate global parameters, two anonymity revokers, an identity
+    /// provider, and an attributes file in a fresh directory.
+    fn write_ip_sign_pio_inputs(name: &str) -> IpSignPioInputs {
+        let mut csprng = thread_rng();
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("{}-{}", name, csprng.gen::<u64>()));
+        std::fs::create_dir_all(&dir).expect("Could not create output directory.");
+
+        let global_ctx = GlobalContext::<G1>::generate(String::from("genesis_string"));
+        let mut global = dir.clone();
+        global.push("global.json");
+        write_json_to_file(&global, &Versioned::new(VERSION_0, &global_ctx))
+            .expect("Could not write global parameters.");
+        let ars = generate_ars(
+            &global_ctx,
+            (1..=2).map(|i| ArIdentity::try_from(i).unwrap()),
+            &dir,
+            &mut csprng,
+        )
+        .expect("Generating anonymity revokers should succeed.");
+        let mut anonymity_revokers = dir.clone();
+        anonymity_revokers.push("anonymity_revokers.json");
+        let ips = generate_ips(1, 10, &ars, &dir, &mut csprng)
+            .expect("Generating identity providers should succeed.");
+        let ip_info = ips.identity_providers[&IpIdentity(0)].clone();
+        let (_, ip_data) = mk_ip_filename(&dir, 0);
+        let mut attributes = dir.clone();
+        attributes.push("attributes.json");
+        write_json_to_file(&attributes, &test_attributes())
+            .expect("Could not write attributes file.");
+
+        let aci = AccCredentialInfo {
+            cred_holder_info: CredentialHolderInfo {
+                id_cred: IdCredentials::generate(&mut csprng),
+            },
+            prf_key:          prf::SecretKey::generate(&mut csprng),
+        };
+        let id_use_data = IdObjectUseData {
+            aci,
+            randomness: ps_sig::SigRetrievalRandomness::generate_non_zero(&mut csprng),
+        };
+        IpSignPioInputs {
+            dir,
+            ip_info,
+            ars,
+            global_ctx,
+            id_use_data,
+            ip_data,
+            global,
+            anonymity_revokers,
+            attributes,
+        }
+    }
+
+    #[test]
+    fn test_ip_sign_pio_attributes_file() {
+        let inputs = write_ip_sign_pio_inputs("ip-sign-pio");
+        let initial_account = InitialAccountData {
+            keys:      {
+                let mut keys = BTreeMap::new();
+                keys.insert(KeyIndex(0), KeyPair::generate(&mut thread_rng()));
+                keys
+            },
+            threshold: SignatureThreshold(1),
+        };
+        let pio = create_pre_identity_object(
+            &inputs.ip_info,
+            &inputs.ars.anonymity_revokers,
+            Threshold(1),
+            &inputs.global_ctx,
+            &inputs.id_use_data,
+            &initial_account,
+        )
+        .expect("Creating the pre-identity object should succeed.");
+        let mut pio_file = inputs.dir.clone();
+        pio_file.push("pio.json");
+        write_json_to_file(&pio_file, &Versioned::new(VERSION_0, pio))
+            .expect("Could not write pre-identity object.");
+        let mut out_file = inputs.dir.clone();
+        out_file.push("id_object.json");
+
+        handle_act_as_ip(IpSignPio {
+            pio:                pio_file,
+            ip_data:            inputs.ip_data,
+            out_file:           Some(out_file.clone()),
+            bin_out:            None,
+            out_icdi:           None,
+            global:             inputs.global,
+            anonymity_revokers: inputs.anonymity_revokers,
+            expiry:             100,
+            id_expiry:          YearMonth::new(2100, 1),
+            no_attributes:      false,
+            attributes:         Some(inputs.attributes),
+        });
+
+        let id_object = read_id_object(&out_file).expect("Could not read identity object.");
+        assert_eq!(
+            to_value(&id_object.alist.alist).expect("Attributes should serialize."),
+            test_attributes(),
+            "The identity object should contain the attributes from the file."
+        );
+        std::fs::remove_dir_all(&inputs.dir).expect("Could not clean up output directory.");
+    }
+
+    #[test]
+    fn test_ip_sign_pio_v1_attributes_file() {
+        let inputs = write_ip_sign_pio_inputs("ip-sign-pio-v1");
+        let pio = create_pre_identity_object_v1(
+            &inputs.ip_info,
+            &inputs.ars.anonymity_revokers,
+            Threshold(1),
+            &inputs.global_ctx,
+            &inputs.id_use_data,
+        )
+        .expect("Creating the pre-identity object should succeed.");
+        let mut pio_file = inputs.dir.clone();
+        pio_file.push("pio.json");
+        write_json_to_file(&pio_file, &Versioned::new(VERSION_0, pio))
+            .expect("Could not write pre-identity object.");
+        let mut out_file = inputs.dir.clone();
+        out_file.push("id_object.json");
+
+        handle_act_as_ip_v1(IpSignPioV1 {
+            pio:                pio_file,
+            ip_data:            inputs.ip_data,
+            out_file:           Some(out_file.clone()),
+            global:             inputs.global,
+            anonymity_revokers: inputs.anonymity_revokers,
+            id_expiry:          YearMonth::new(2100, 1),
+            no_attributes:      false,
+            attributes:         Some(inputs.attributes),
+        });
+
+        let id_object = read_id_object_v1(&out_file).expect("Could not read identity object.");
+        assert_eq!(
+            to_value(&id_object.alist.alist).expect("Attributes should serialize."),
+            test_attributes(),
+            "The identity object should contain the attributes from the file."
+        );
+        std::fs::remove_dir_all(&inputs.dir).expect("Could not clean up output directory.");
+    }
+
+    #[test]
+    fn test_generate_global_from_seed() {
+        let mut csprng = thread_rng();
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("generate-global-{}", csprng.gen::<u64>()));
+        std::fs::create_dir_all(&dir).expect("Could not create output directory.");
+        let mut seed_file = dir.clone();
+        seed_file.push("seed");
+        std::fs::write(&seed_file, b"test network seed").expect("Could not write seed file.");
+        let mut other_seed_file = dir.clone();
+        other_seed_file.push("other-seed");
+        std::fs::write(&other_seed_file, b"another seed").expect("Could not write seed file.");
+
+        let generate = |seed: &PathBuf, name: &str| {
+            let mut output_file = dir.clone();
+            output_file.push(name);
+            handle_generate_global(GenerateGlobal {
+                output_file:    output_file.clone(),
+                genesis_string: String::from("genesis_string"),
+                seed_file:      Some(seed.clone()),
+            });
+            std::fs::read(&output_file).expect("Could not read global parameters.")
+        };
+        let first = generate(&seed_file, "global1.json");
+        let second = generate(&seed_file, "global2.json");
+        let other = generate(&other_seed_file, "global3.json");
+        assert_eq!(
+            first, second,
+            "The same seed should give identical global parameters."
+        );
+        assert_ne!(
+            first, other,
+            "Different seeds should give different global parameters."
+        );
+        std::fs::remove_dir_all(&dir).expect("Could not clean up output directory.");
+    }
+
+    #[test]
+    fn test_seed_derivation() {
+        let seed = parse_seed(&"ab".repeat(32)).expect("A 32 byte seed should parse.");
+        assert!(parse_seed(&"ab".repeat(31)).is_err());
+        assert!(parse_seed("not hex").is_err());
+        let keys = (0..3)
+            .map(|i| keypair_from_seed(&key_seed(&seed, i)).public)
+            .collect::<Vec<_>>();
+        assert_ne!(keys[0], keys[1]);
+        assert_ne!(keys[1], keys[2]);
+        assert_eq!(keys[0], keypair_from_seed(&key_seed(&seed, 0)).public);
+    }
+}
@@ -368,7 +368,6 @@ fn handle_start_ip(sip: StartIp) -> anyhow::Result<()> {
         prf_key,
     };
 
-    let context = IpContext::new(&ip_info, &choice_ars, &global_ctx);
     // and finally generate the pre-identity object
     // we also retrieve the randomness which we must keep private.
     // This randomness must be used
@@ -383,8 +382,15 @@ fn handle_start_ip(sip: StartIp) -> anyhow::Result<()> {
 
     let randomness = ps_sig::SigRetrievalRandomness::generate_non_zero(&mut csprng);
     let id_use_data = IdObjectUseData { aci, randomness };
-    let (pio, _) = generate_pio(&context, threshold, &id_use_data, &initial_acc_data)
-        .context("Failed to generate the identity object request.")?;
+    let pio = create_pre_identity_object(
+        &ip_info,
+        &choice_ars,
+        threshold,
+        &global_ctx,
+        &id_use_data,
+        &initial_acc_data,
+    )
+    .context("Failed to generate the identity object request.")?;
 
     let enc_key = id_use_data.aci.prf_key.prf_exponent(0).unwrap();
 
@@ -539,7 +545,6 @@ fn handle_start_ip_v1(sip: StartIpV1) -> anyhow::Result<()> {
                                          // selection starts at 1
     };
 
-    let context = IpContext::new(&ip_info, &choice_ars, &global_ctx);
     // and finally generate the pre-identity object
 
     let use_mainnet = Confirm::new()
@@ -655,8 +660,9 @@ fn handle_start_ip_v1(sip: StartIpV1) -> anyhow::Result<()> {
     };
     let id_use_data = IdObjectUseData { aci, randomness };
 
-    let (pio, _) = generate_pio_v1(&context, threshold, &id_use_data)
-        .context("Failed to generate the identity object request.")?;
+    let pio =
+        create_pre_identity_object_v1(&ip_info, &choice_ars, threshold, &global_ctx, &id_use_data)
+            .context("Failed to generate the identity object request.")?;
 
     // the only thing left is to output all the information
 
@@ -777,6 +783,10 @@ fn handle_create_credential_v1(cc: CreateCredentialV1) -> anyhow::Result<()> {
         prf_key,
     };
     let id_use_data = IdObjectUseData { aci, randomness };
+    anyhow::ensure!(
+        verify_identity_object_signature(&ip_info, &id_object, &id_use_data),
+        "The identity provider's signature on the identity object is not valid."
+    );
     let secret =
         match wallet.get_account_signing_key(identity_provider_index, identity_index, u32::from(x))
         {
@@ -849,6 +859,10 @@ fn handle_create_credential(cc: CreateCredential) -> anyhow::Result<()> {
     let ars = data.ars;
     let global_ctx = data.global_ctx;
     let id_use_data = data.id_use_data;
+    anyhow::ensure!(
+        verify_identity_object_signature(&ip_info, &id_object, &id_use_data),
+        "The identity provider's signature on the identity object is not valid."
+    );
 
     // Now we have have everything we need to generate the proofs
     // we have
@@ -5,19 +5,19 @@ use curve_arithmetic::*;
 use dialoguer::Input;
 use ed25519_hd_key_derivation::DeriveError;
 use hkdf::HkdfExtract;
-use id::{constants::*, types::*};
+use id::{account_holder, constants::*, identity_provider, secret_sharing::Threshold, types::*};
 use pairing::bls12_381::Bls12;
 use pedersen_scheme::Randomness as PedersenRandomness;
 use rand::Rng;
 use serde::{de::DeserializeOwned, Serialize as SerdeSerialize};
-use serde_json::{to_string_pretty, to_writer_pretty};
+use serde_json::to_writer_pretty;
 use sha2::{Digest, Sha256};
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, BTreeSet, HashMap},
     fmt::Debug,
     fs::File,
-    io::{self, BufReader},
-    path::Path,
+    io::{self, BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
     str::FromStr,
 };
 
@@ -254,29 +254,205 @@ pub fn read_credential<P: AsRef<Path> + Debug>(
     }
 }
 
+/// Create a version 0 pre-identity object to send to the given identity
+/// provider. All the given anonymity revokers are used, with the given
+/// revocation threshold. Returns `None` if the pre-identity object could not be
+/// constructed from the given data.
+pub fn create_pre_identity_object(
+    ip_info: &IpInfo<Bls12>,
+    ars: &BTreeMap<ArIdentity, ArInfo<ExampleCurve>>,
+    threshold: Threshold,
+    global_ctx: &GlobalContext<ExampleCurve>,
+    id_use_data: &IdObjectUseData<Bls12, ExampleCurve>,
+    initial_account: &impl InitialAccountDataWithSigning,
+) -> Option<PreIdentityObject<Bls12, ExampleCurve>> {
+    let context = IpContext::new(ip_info, ars, global_ctx);
+    let (pio, _) = account_holder::generate_pio(&context, threshold, id_use_data, initial_account)?;
+    Some(pio)
+}
+
+/// Create a version 1 pre-identity object to send to the given identity
+/// provider. All the given anonymity revokers are used, with the given
+/// revocation threshold. Returns `None` if the pre-identity object could not be
+/// constructed from the given data.
+pub fn create_pre_identity_object_v1(
+    ip_info: &IpInfo<Bls12>,
+    ars: &BTreeMap<ArIdentity, ArInfo<ExampleCurve>>,
+    threshold: Threshold,
+    global_ctx: &GlobalContext<ExampleCurve>,
+    id_use_data: &IdObjectUseData<Bls12, ExampleCurve>,
+) -> Option<PreIdentityObjectV1<Bls12, ExampleCurve>> {
+    let context = IpContext::new(ip_info, ars, global_ctx);
+    let (pio, _) = account_holder::generate_pio_v1(&context, threshold, id_use_data)?;
+    Some(pio)
+}
+
+/// Check that the global parameters, identity providers and anonymity
+/// revokers are consistent with each other. All problems found are returned,
+/// not only the first one.
+pub fn validate_database(
+    global_ctx: &GlobalContext<ExampleCurve>,
+    ips: &IpInfos<Bls12>,
+    ars: &ArInfos<ExampleCurve>,
+) -> Result<(), Vec<String>> {
+    let mut problems = Vec::new();
+    let num_generators = global_ctx.bulletproof_generators().G_H.len();
+    if num_generators < NUM_BULLETPROOF_GENERATORS {
+        problems.push(format!(
+            "The global parameters have {} bulletproof generators, but {} are needed.",
+            num_generators, NUM_BULLETPROOF_GENERATORS
+        ));
+    }
+    for (&ar_identity, ar_info) in ars.anonymity_revokers.iter() {
+        if ar_info.ar_identity != ar_identity {
+            problems.push(format!(
+                "Anonymity revoker {} is listed under identity {}.",
+                ar_info.ar_identity, ar_identity
+            ));
+        }
+        if ar_info.ar_public_key.generator != *global_ctx.elgamal_generator() {
+            problems.push(format!(
+                "The public key of anonymity revoker {} does not use the generator from the \
+                 global parameters.",
+                ar_identity
+            ));
+        }
+    }
+    let ar_identities = ars
+        .anonymity_revokers
+        .keys()
+        .copied()
+        .collect::<BTreeSet<_>>();
+    let min_key_len = match identity_provider::signed_message_len::<ExampleCurve>(&ar_identities, 0)
+    {
+        Some(len) => len,
+        None => {
+            problems.push("The anonymity revokers cannot be encoded.".to_owned());
+            0
+        }
+    };
+    for (&ip_identity, ip_info) in ips.identity_providers.iter() {
+        if ip_info.ip_identity != ip_identity {
+            problems.push(format!(
+                "Identity provider {} is listed under identity {}.",
+                ip_info.ip_identity, ip_identity
+            ));
+        }
+        if ip_info.ip_verify_key.len() < min_key_len {
+            problems.push(format!(
+                "The key of identity provider {} has length {}, which is too short to sign \
+                 identity objects using all {} anonymity revokers.",
+                ip_identity,
+                ip_info.ip_verify_key.len(),
+                ars.anonymity_revokers.len()
+            ));
+        }
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
 /// Parse YYYYMM as YearMonth
 pub fn parse_yearmonth(input: &str) -> Option<YearMonth> { YearMonth::from_str(input).ok() }
 
-/// Output json to a file, pretty printed.
+/// Output json to a file, pretty printed. The file is written atomically, see
+/// [write_json_to_file_atomic].
 pub fn write_json_to_file<P: AsRef<Path>, T: SerdeSerialize>(filepath: P, v: &T) -> io::Result<()> {
-    let file = File::create(filepath)?;
-    Ok(to_writer_pretty(file, v)?)
+    write_json_to_file_atomic(filepath, v)
+}
+
+/// Output json to a file, pretty printed. The data is first written to a
+/// temporary file in the same directory, which is then renamed to the target.
+/// Hence the target file is either unchanged or contains all the new data,
+/// even if writing fails midway.
+pub fn write_json_to_file_atomic<P: AsRef<Path>, T: SerdeSerialize>(
+    filepath: P,
+    v: &T,
+) -> io::Result<()> {
+    let filepath = filepath.as_ref();
+    let tmp_path = write_json_to_temp_file(filepath, v)?;
+    std::fs::rename(&tmp_path, filepath).map_err(|e| {
+        let _ = std::fs::remove_file(&tmp_path);
+        e
+    })
 }
 
-/// Output json to standard output, pretty printed.
+/// Write the value to a fresh temporary file next to `filepath`, and return
+/// the path of the temporary file. The temporary file is removed on failure.
+/// The JSON is serialized directly into a buffered writer, so no copy of the
+/// whole output is built in memory.
+fn write_json_to_temp_file<T: SerdeSerialize>(filepath: &Path, v: &T) -> io::Result<PathBuf> {
+    let file_name = filepath.file_name().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a file path.", filepath.display()),
+        )
+    })?;
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(file_name);
+    tmp_name.push(format!(".{:016x}.tmp", rand::thread_rng().gen::<u64>()));
+    let tmp_path = filepath.with_file_name(tmp_name);
+    let write = || -> io::Result<()> {
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        to_writer_pretty(&mut writer, v)?;
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()
+    };
+    match write() {
+        Ok(()) => Ok(tmp_path),
+        Err(e) => {
+            let _ = std::fs::remove_file(&tmp_path);
+            Err(e)
+        }
+    }
+}
+
+/// Output json to standard output, pretty printed. The JSON is serialized
+/// directly to standard output, without building the whole string in memory.
 pub fn output_json<T: SerdeSerialize>(v: &T) {
-    println!("{}", to_string_pretty(v).unwrap());
+    let stdout = io::stdout();
+    let mut out = BufWriter::new(stdout.lock());
+    to_writer_pretty(&mut out, v).unwrap();
+    writeln!(out).unwrap();
 }
 
 pub fn read_json_from_file<P, T>(path: P) -> io::Result<T>
 where
     P: AsRef<Path> + Debug,
     T: DeserializeOwned, {
-    let file = File::open(path)?;
+    let path = path.as_ref();
+    let file = File::open(path).map_err(|e| {
+        io::Error::new(
+            e.kind(),
+            format!("Could not open {}: {}", path.display(), e),
+        )
+    })?;
 
     let reader = BufReader::new(file);
-    let u = serde_json::from_reader(reader)?;
-    Ok(u)
+    serde_json::from_reader(reader).map_err(|e| {
+        if e.is_io() {
+            return io::Error::new(
+                io::ErrorKind::Other,
+                format!("Could not read {}: {}", path.display(), e),
+            );
+        }
+        // The error message ends with the position, which we report separately.
+        let msg = e.to_string();
+        let msg = msg.rsplitn(2, " at line ").last().unwrap_or(&msg);
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Failed to parse {} at line {}, column {}: {}",
+                path.display(),
+                e.line(),
+                e.column(),
+                msg
+            ),
+        )
+    })
 }
 
 /// Ask for a password and a confirmation
@@ -525,3 +701,253 @@ impl HasAttributeRandomness<ArCurve> for CredentialContext {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crypto_common::types::{KeyIndex, KeyPair};
+    use id::identity_provider::{validate_request, validate_request_v1};
+    use rand::thread_rng;
+    use std::convert::TryFrom;
+
+    /// Create an identity provider, two anonymity revokers and id use data.
+    #[allow(clippy::type_complexity)]
+    fn create_test_data() -> (
+        IpInfo<Bls12>,
+        BTreeMap<ArIdentity, ArInfo<ExampleCurve>>,
+        GlobalContext<ExampleCurve>,
+        IdObjectUseData<Bls12, ExampleCurve>,
+    ) {
+        let mut csprng = thread_rng();
+        let global_ctx = GlobalContext::generate(String::from("genesis_string"));
+        let ip_secret_key = ps_sig::SecretKey::<Bls12>::generate(20, &mut csprng);
+        let ip_info = IpInfo {
+            ip_identity:       IpIdentity(0),
+            ip_description:    mk_dummy_description("IP".into()),
+            ip_verify_key:     ps_sig::PublicKey::from(&ip_secret_key),
+            ip_cdi_verify_key: ed25519_dalek::Keypair::generate(&mut csprng).public,
+        };
+        let ars = (1..=2u32)
+            .map(|i| {
+                let ar_identity = ArIdentity::try_from(i).unwrap();
+                let ar_secret_key = elgamal::SecretKey::generate(
+                    &global_ctx.on_chain_commitment_key.g,
+                    &mut csprng,
+                );
+                (ar_identity, ArInfo {
+                    ar_identity,
+                    ar_description: mk_dummy_description(format!("AR-{}", i)),
+                    ar_public_key: elgamal::PublicKey::from(&ar_secret_key),
+                })
+            })
+            .collect();
+        let aci = AccCredentialInfo {
+            cred_holder_info: CredentialHolderInfo {
+                id_cred: IdCredentials::generate(&mut csprng),
+            },
+            prf_key:          dodis_yampolskiy_prf::SecretKey::generate(&mut csprng),
+        };
+        let id_use_data = IdObjectUseData {
+            aci,
+            randomness: ps_sig::SigRetrievalRandomness::generate_non_zero(&mut csprng),
+        };
+        (ip_info, ars, global_ctx, id_use_data)
+    }
+
+    #[test]
+    fn test_write_many_identity_providers() {
+        let mut csprng = thread_rng();
+        let num = 1000;
+        let mut ips = IpInfos {
+            identity_providers: BTreeMap::new(),
+        };
+        for i in 0..num {
+            let ip_secret_key = ps_sig::SecretKey::<Bls12>::generate(1, &mut csprng);
+            let ip_identity = IpIdentity(i);
+            ips.identity_providers.insert(ip_identity, IpInfo {
+                ip_identity,
+                ip_description: mk_dummy_description(format!("IP-{}", i)),
+                ip_verify_key: ps_sig::PublicKey::from(&ip_secret_key),
+                ip_cdi_verify_key: ed25519_dalek::Keypair::generate(&mut csprng).public,
+            });
+        }
+        let mut path = std::env::temp_dir();
+        path.push(format!("identity-providers-{}.json", csprng.gen::<u64>()));
+        write_json_to_file(&path, &Versioned::new(VERSION_0, ips))
+            .expect("Writing identity providers should succeed.");
+        let read = read_identity_providers(&path).expect("Could not read identity providers.");
+        assert_eq!(read.identity_providers.len(), num as usize);
+        std::fs::remove_file(&path).expect("Could not clean up output file.");
+    }
+
+    #[test]
+    fn test_validate_database() {
+        let (ip_info, anonymity_revokers, global_ctx, _) = create_test_data();
+        let mut ips = IpInfos {
+            identity_providers: BTreeMap::new(),
+        };
+        ips.identity_providers.insert(ip_info.ip_identity, ip_info);
+        let mut ars = ArInfos { anonymity_revokers };
+        assert_eq!(validate_database(&global_ctx, &ips, &ars), Ok(()));
+
+        // Break the database in three different ways.
+        let small_ctx = GlobalContext::generate_size(String::from("genesis_string"), 8);
+        let moved = ips.identity_providers.remove(&IpIdentity(0)).unwrap();
+        ips.identity_providers.insert(IpIdentity(1), moved);
+        let ar_secret_key = elgamal::SecretKey::generate_all(&mut thread_rng());
+        let ar = ars
+            .anonymity_revokers
+            .values_mut()
+            .next()
+            .expect("There are anonymity revokers.");
+        ar.ar_public_key = elgamal::PublicKey::from(&ar_secret_key);
+        let problems = validate_database(&small_ctx, &ips, &ars)
+            .expect_err("An inconsistent database should be rejected.");
+        assert_eq!(problems.len(), 3, "Unexpected problems: {:?}", problems);
+    }
+
+    #[test]
+    fn test_validate_database_many_ars() {
+        // Several anonymity revokers are encoded into each scalar of the signed
+        // message, so a short key suffices for many anonymity revokers.
+        let mut csprng = thread_rng();
+        let (mut ip_info, mut anonymity_revokers, global_ctx, _) = create_test_data();
+        for i in 3..=20u32 {
+            let ar_identity = ArIdentity::try_from(i).unwrap();
+            let ar_secret_key =
+                elgamal::SecretKey::generate(&global_ctx.on_chain_commitment_key.g, &mut csprng);
+            anonymity_revokers.insert(ar_identity, ArInfo {
+                ar_identity,
+                ar_description: mk_dummy_description(format!("AR-{}", i)),
+                ar_public_key: elgamal::PublicKey::from(&ar_secret_key),
+            });
+        }
+        let ars = ArInfos { anonymity_revokers };
+        let ar_identities = ars
+            .anonymity_revokers
+            .keys()
+            .copied()
+            .collect::<BTreeSet<_>>();
+        let min_key_len =
+            identity_provider::signed_message_len::<ExampleCurve>(&ar_identities, 0).unwrap();
+        assert!(min_key_len < 20, "The anonymity revokers should be packed.");
+
+        let mut ips = IpInfos {
+            identity_providers: BTreeMap::new(),
+        };
+        ip_info.ip_verify_key = ps_sig::PublicKey::from(&ps_sig::SecretKey::<Bls12>::generate(
+            min_key_len,
+            &mut csprng,
+        ));
+        ips.identity_providers
+            .insert(ip_info.ip_identity, ip_info.clone());
+        assert_eq!(validate_database(&global_ctx, &ips, &ars), Ok(()));
+
+        ip_info.ip_verify_key = ps_sig::PublicKey::from(&ps_sig::SecretKey::<Bls12>::generate(
+            min_key_len - 1,
+            &mut csprng,
+        ));
+        ips.identity_providers.insert(ip_info.ip_identity, ip_info);
+        let problems = validate_database(&global_ctx, &ips, &ars)
+            .expect_err("A key that is too short should be rejected.");
+        assert_eq!(problems.len(), 1, "Unexpected problems: {:?}", problems);
+    }
+
+    #[test]
+    fn test_write_json_to_file_atomic() {
+        let mut dir = std::env::temp_dir();
+        dir.push(format!("write-json-{}", thread_rng().gen::<u64>()));
+        std::fs::create_dir_all(&dir).expect("Could not create test directory.");
+        let mut path = dir.clone();
+        path.push("global.json");
+
+        write_json_to_file_atomic(&path, &vec![1u8, 2, 3]).expect("Writing should succeed.");
+        let read: Vec<u8> = read_json_from_file(&path).expect("Reading should succeed.");
+        assert_eq!(read, vec![1, 2, 3]);
+
+        // Simulate a crash after the temporary file is written, but before it is
+        // renamed.
+        let tmp_path =
+            write_json_to_temp_file(&path, &vec![4u8, 5]).expect("Writing should succeed.");
+        std::fs::remove_file(&tmp_path).expect("Could not remove temporary file.");
+        let read: Vec<u8> = read_json_from_file(&path).expect("Reading should succeed.");
+        assert_eq!(read, vec![1, 2, 3], "The original file should be intact.");
+
+        // Maps with non-string keys cannot be serialized to JSON, so writing fails
+        // midway.
+        let mut bad = BTreeMap::new();
+        bad.insert((1u8, 2u8), 3u8);
+        assert!(write_json_to_file_atomic(&path, &bad).is_err());
+        let read: Vec<u8> = read_json_from_file(&path).expect("Reading should succeed.");
+        assert_eq!(read, vec![1, 2, 3], "The original file should be intact.");
+        assert_eq!(
+            std::fs::read_dir(&dir).unwrap().count(),
+            1,
+            "No temporary files should be left behind."
+        );
+        std::fs::remove_dir_all(&dir).expect("Could not remove test directory.");
+    }
+
+    #[test]
+    fn test_read_json_from_file_error() {
+        let mut path = std::env::temp_dir();
+        path.push(format!("broken-{}.json", thread_rng().gen::<u64>()));
+        std::fs::write(
+            &path,
+            "{\n  \"v\": 0,\n  \"value\": {\n    \"genesisString\" \"x\"\n  }\n}\n",
+        )
+        .expect("Could not write test file.");
+        let res = read_json_from_file::<_, serde_json::Value>(&path);
+        std::fs::remove_file(&path).expect("Could not remove test file.");
+        let err = res.expect_err("Parsing a broken file should fail.");
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Failed to parse {} at line 4, column 21: expected `:`",
+                path.display()
+            )
+        );
+
+        let err = read_json_from_file::<_, serde_json::Value>(&path)
+            .expect_err("Reading a missing file should fail.");
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(err
+            .to_string()
+            .starts_with(&format!("Could not open {}", path.display())));
+    }
+
+    #[test]
+    fn test_create_pre_identity_object() {
+        let (ip_info, ars, global_ctx, id_use_data) = create_test_data();
+        let initial_account = InitialAccountData {
+            keys:      {
+                let mut keys = BTreeMap::new();
+                keys.insert(KeyIndex(0), KeyPair::generate(&mut thread_rng()));
+                keys
+            },
+            threshold: SignatureThreshold(1),
+        };
+        let pio = create_pre_identity_object(
+            &ip_info,
+            &ars,
+            Threshold(2),
+            &global_ctx,
+            &id_use_data,
+            &initial_account,
+        )
+        .expect("Creating the pre-identity object should succeed.");
+        let context = IpContext::new(&ip_info, &ars, &global_ctx);
+        assert_eq!(validate_request(&pio, context), Ok(()));
+    }
+
+    #[test]
+    fn test_create_pre_identity_object_v1() {
+        let (ip_info, ars, global_ctx, id_use_data) = create_test_data();
+        let pio =
+            create_pre_identity_object_v1(&ip_info, &ars, Threshold(1), &global_ctx, &id_use_data)
+                .expect("Creating the pre-identity object should succeed.");
+        let context = IpContext::new(&ip_info, &ars, &global_ctx);
+        assert_eq!(validate_request_v1(&pio, context), Ok(()));
+    }
+}
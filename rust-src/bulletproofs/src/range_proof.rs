@@ -699,6 +699,55 @@ pub fn verify_less_than_or_equal<C: Curve>(
     .is_ok()
 }
 
+/// Prove that a single committed value `v` lies in `[0, 2^n)`. This is the
+/// special case of [prove] with `m = 1`, where
+/// - `n` - the number n such that `v` is in `[0,2^n)`
+/// - `v` - the value
+/// - `gens` - generators containing vectors `G` and `H` both of length at least
+///   `n`
+/// - `v_keys` - commitment keys `B` and `B_tilde`
+/// - `v_rand` - the randomness used to commit to `v` using `v_keys`
+pub fn prove_single<C: Curve, T: Rng>(
+    transcript: &mut RandomOracle,
+    csprng: &mut T,
+    n: u8,
+    v: u64,
+    gens: &Generators<C>,
+    v_keys: &CommitmentKey<C>,
+    v_rand: &Randomness<C>,
+) -> Option<RangeProof<C>> {
+    prove(
+        transcript,
+        csprng,
+        n,
+        1,
+        &[v],
+        gens,
+        v_keys,
+        std::slice::from_ref(v_rand),
+    )
+}
+
+/// Verify a proof produced by [prove_single], i.e., that the value committed
+/// to in `commitment` lies in `[0, 2^n)`.
+pub fn verify_single<C: Curve>(
+    transcript: &mut RandomOracle,
+    n: u8,
+    commitment: &Commitment<C>,
+    proof: &RangeProof<C>,
+    gens: &Generators<C>,
+    v_keys: &CommitmentKey<C>,
+) -> Result<(), VerificationError> {
+    verify_efficient(
+        transcript,
+        n,
+        std::slice::from_ref(commitment),
+        proof,
+        gens,
+        v_keys,
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1127,4 +1176,90 @@ mod tests {
         let result = verify_efficient(&mut transcript, n, &commitments, &proof, &gens, &keys);
         assert!(result.is_ok());
     }
+
+    #[allow(non_snake_case)]
+    #[test]
+    fn test_single() {
+        let rng = &mut thread_rng();
+        let n = 8;
+        let mut G_H = Vec::with_capacity(usize::from(n));
+        for _ in 0..n {
+            G_H.push((SomeCurve::generate(rng), SomeCurve::generate(rng)));
+        }
+        let gens = Generators { G_H };
+        let keys = CommitmentKey::<SomeCurve>::generate(rng);
+        for v in 18..=255 {
+            let r = Randomness::generate(rng);
+            let com = keys.hide(&Value::new(SomeCurve::scalar_from_u64(v)), &r);
+            let mut transcript = RandomOracle::domain("Test");
+            let proof = prove_single(&mut transcript, rng, n, v, &gens, &keys, &r)
+                .expect("Proving should succeed.");
+            let proof: RangeProof<SomeCurve> =
+                serialize_deserialize(&proof).expect("Proof should deserialize.");
+            let mut transcript = RandomOracle::domain("Test");
+            assert_eq!(
+                verify_single(&mut transcript, n, &com, &proof, &gens, &keys),
+                Ok(()),
+                "Single value range proof should verify."
+            );
+        }
+    }
+
+    /// Test that an aggregated proof for several values verifies exactly when
+    /// the proofs for each of the values individually verify.
+    #[allow(non_snake_case)]
+    #[test]
+    fn test_aggregate_vs_single() {
+        let rng = &mut thread_rng();
+        let n = 8;
+        let m = 4;
+        let nm = usize::from(n) * usize::from(m);
+        let mut G_H = Vec::with_capacity(nm);
+        for _ in 0..nm {
+            G_H.push((SomeCurve::generate(rng), SomeCurve::generate(rng)));
+        }
+        let gens = Generators { G_H };
+        let keys = CommitmentKey::<SomeCurve>::generate(rng);
+        for out_of_range in &[None, Some(2usize)] {
+            let mut v_vec: Vec<u64> = (0..m).map(|_| rng.gen_range(0, 256)).collect();
+            if let Some(i) = out_of_range {
+                v_vec[*i] = 256 + rng.gen_range(0, 256);
+            }
+            let randomness: Vec<Randomness<SomeCurve>> =
+                (0..m).map(|_| Randomness::generate(rng)).collect();
+            let commitments: Vec<Commitment<SomeCurve>> = v_vec
+                .iter()
+                .zip(randomness.iter())
+                .map(|(&v, r)| keys.hide(&Value::new(SomeCurve::scalar_from_u64(v)), r))
+                .collect();
+
+            let mut transcript = RandomOracle::domain("Test");
+            let proof = prove(
+                &mut transcript,
+                rng,
+                n,
+                m,
+                &v_vec,
+                &gens,
+                &keys,
+                &randomness,
+            )
+            .expect("Proving should succeed.");
+            let mut transcript = RandomOracle::domain("Test");
+            let aggregate_ok =
+                verify_efficient(&mut transcript, n, &commitments, &proof, &gens, &keys).is_ok();
+
+            let mut individual_ok = true;
+            for ((&v, r), com) in v_vec.iter().zip(randomness.iter()).zip(commitments.iter()) {
+                let mut transcript = RandomOracle::domain("Test");
+                let proof = prove_single(&mut transcript, rng, n, v, &gens, &keys, r)
+                    .expect("Proving should succeed.");
+                let mut transcript = RandomOracle::domain("Test");
+                individual_ok &=
+                    verify_single(&mut transcript, n, com, &proof, &gens, &keys).is_ok();
+            }
+            assert_eq!(aggregate_ok, individual_ok);
+            assert_eq!(aggregate_ok, out_of_range.is_none());
+        }
+    }
 }
@@ -739,4 +739,47 @@ mod tests {
         let result = verify(&mut transcript, &the_set, &v_com, &proof, &gens, &v_keys);
         assert!(result.is_ok());
     }
+
+    /// Test that proofs for a set with a single element work
+    #[test]
+    fn test_smp_prove_singleton_set() {
+        let rng = &mut thread_rng();
+
+        let the_set = get_set_vector::<SomeCurve>(&[7]);
+        let v = SomeCurve::scalar_from_u64(7);
+        let (gens, v_keys, v_rand) = generate_helper_values(1);
+
+        let mut transcript = RandomOracle::empty();
+        let proof = prove(&mut transcript, rng, &the_set, v, &gens, &v_keys, &v_rand);
+        assert!(proof.is_ok());
+        let proof = proof.unwrap();
+
+        // verify
+        let v_com = get_v_com(v, v_keys, v_rand);
+        let mut transcript = RandomOracle::empty();
+        let result = verify(&mut transcript, &the_set, &v_com, &proof, &gens, &v_keys);
+        assert!(result.is_ok());
+    }
+
+    /// Test that proofs work if the value occurs several times in the set
+    #[test]
+    fn test_smp_prove_multiset() {
+        let rng = &mut thread_rng();
+
+        let the_set = get_set_vector::<SomeCurve>(&[3, 7, 3, 5, 3]);
+        let v = SomeCurve::scalar_from_u64(3);
+        let k = the_set.len().next_power_of_two();
+        let (gens, v_keys, v_rand) = generate_helper_values(k);
+
+        let mut transcript = RandomOracle::empty();
+        let proof = prove(&mut transcript, rng, &the_set, v, &gens, &v_keys, &v_rand);
+        assert!(proof.is_ok());
+        let proof = proof.unwrap();
+
+        // verify
+        let v_com = get_v_com(v, v_keys, v_rand);
+        let mut transcript = RandomOracle::empty();
+        let result = verify(&mut transcript, &the_set, &v_com, &proof, &gens, &v_keys);
+        assert!(result.is_ok());
+    }
 }
@@ -673,4 +673,41 @@ mod tests {
         let result = verify(&mut transcript, &the_set, &v_com, &proof, &gens, &v_keys);
         assert!(result.is_ok());
     }
+
+    /// Test that a proof still verifies after serialization and
+    /// deserialization
+    #[test]
+    fn test_snmp_serialization() {
+        let rng = &mut thread_rng();
+
+        let the_set = get_set_vector::<SomeCurve>(&[1, 7, 3, 5]);
+        let v = SomeCurve::scalar_from_u64(4);
+        let n = the_set.len();
+        let (gens, v_keys, v_rand) = generate_helper_values(n);
+
+        // prove
+        let mut transcript = RandomOracle::empty();
+        let proof = prove(&mut transcript, rng, &the_set, v, &gens, &v_keys, &v_rand);
+        assert!(proof.is_ok());
+        let proof = proof.unwrap();
+
+        // serialize and deserialize
+        let bytes = to_bytes(&proof);
+        let proof_des: SetNonMembershipProof<SomeCurve> =
+            from_bytes(&mut std::io::Cursor::new(&bytes)).expect("Proof should deserialize.");
+        assert_eq!(bytes, to_bytes(&proof_des));
+
+        // verify
+        let v_com = get_v_com(v, v_keys, v_rand);
+        let mut transcript = RandomOracle::empty();
+        let result = verify(
+            &mut transcript,
+            &the_set,
+            &v_com,
+            &proof_des,
+            &gens,
+            &v_keys,
+        );
+        assert!(result.is_ok());
+    }
 }
@@ -1,15 +1,18 @@
 use anyhow::bail;
 use byteorder::ReadBytesExt;
 
-use ff::PrimeField;
+use ff::{Field, PrimeField};
 use group::{CurveAffine, CurveProjective, EncodedPoint};
 use pairing::bls12_381::{
-    Fq12, FqRepr, Fr, FrRepr, G1Affine, G1Compressed, G2Affine, G2Compressed, G1, G2,
+    Fq, Fq12, Fq2, Fq6, FqRepr, Fr, FrRepr, G1Affine, G1Compressed, G1Uncompressed, G2Affine,
+    G2Compressed, G2Uncompressed, G1, G2,
 };
 use std::convert::TryFrom;
 
 use crate::serialize::*;
 
+/// Deserialization is strict. It only accepts the canonical, big-endian
+/// encoding of a scalar, i.e., values strictly less than the field modulus.
 impl Deserial for Fr {
     fn deserial<R: ReadBytesExt>(source: &mut R) -> ParseResult<Fr> {
         let mut frrepr: FrRepr = FrRepr([0u64; 4]);
@@ -114,6 +117,57 @@ impl Serial for G2Affine {
     }
 }
 
+/// A wrapper around a group element that uses the uncompressed encoding for
+/// serialization. This is twice the size of the default, compressed, encoding,
+/// but it is cheaper to deserialize since no square root needs to be computed.
+/// It is intended for storing large numbers of group elements locally, the
+/// compressed encoding remains the default wire format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(transparent)]
+pub struct Uncompressed<G>(pub G);
+
+impl Deserial for Uncompressed<G1> {
+    fn deserial<R: ReadBytesExt>(source: &mut R) -> ParseResult<Self> {
+        let mut g = G1Uncompressed::empty();
+        source.read_exact(g.as_mut())?;
+        Ok(Uncompressed(g.into_affine()?.into_projective()))
+    }
+}
+
+impl Serial for Uncompressed<G1> {
+    fn serial<B: Buffer>(&self, out: &mut B) {
+        let g = self.0.into_affine().into_uncompressed();
+        let g_bytes = g.as_ref();
+        if let Err(e) = out.write_all(g_bytes) {
+            panic!(
+                "Precondition violated. Buffer should be safe to write {}.",
+                e
+            );
+        }
+    }
+}
+
+impl Deserial for Uncompressed<G2> {
+    fn deserial<R: ReadBytesExt>(source: &mut R) -> ParseResult<Self> {
+        let mut g = G2Uncompressed::empty();
+        source.read_exact(g.as_mut())?;
+        Ok(Uncompressed(g.into_affine()?.into_projective()))
+    }
+}
+
+impl Serial for Uncompressed<G2> {
+    fn serial<B: Buffer>(&self, out: &mut B) {
+        let g = self.0.into_affine().into_uncompressed();
+        let g_bytes = g.as_ref();
+        if let Err(e) = out.write_all(g_bytes) {
+            panic!(
+                "Precondition violated. Buffer should be safe to write {}.",
+                e
+            );
+        }
+    }
+}
+
 /// This implementation is ad-hoc, using the fact that Fq12 is defined
 /// via that specific tower of extensions (of degrees) 2 -> 3 -> 2,
 /// and the specific representation of those fields.
@@ -142,6 +196,40 @@ impl Serial for Fq12 {
     }
 }
 
+/// Read a single `Fq` element in the layout used by the `Serial` instance for
+/// `Fq12`.
+fn deserial_fq<R: ReadBytesExt>(source: &mut R) -> ParseResult<Fq> {
+    let mut repr = FqRepr([0u64; 6]);
+    for d in repr.as_mut().iter_mut() {
+        *d = source.get()?;
+    }
+    Ok(Fq::from_repr(repr)?)
+}
+
+/// Inverse of the `Serial` instance for `Fq12`, reading coefficients in
+/// exactly the same order.
+impl Deserial for Fq12 {
+    fn deserial<R: ReadBytesExt>(source: &mut R) -> ParseResult<Fq12> {
+        let mut coeffs = [Fq2::zero(); 6];
+        for p in coeffs.iter_mut() {
+            let c1 = deserial_fq(source)?;
+            let c0 = deserial_fq(source)?;
+            *p = Fq2 { c0, c1 };
+        }
+        let c1_6 = Fq6 {
+            c2: coeffs[0],
+            c1: coeffs[1],
+            c0: coeffs[2],
+        };
+        let c0_6 = Fq6 {
+            c2: coeffs[3],
+            c1: coeffs[4],
+            c0: coeffs[5],
+        };
+        Ok(Fq12 { c0: c0_6, c1: c1_6 })
+    }
+}
+
 // Implementations for the dalek curve.
 
 use ed25519_dalek::*;
@@ -278,3 +366,134 @@ impl<T: Serial> Serial for Option<T> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::*;
+    use rand::Rng;
+
+    #[test]
+    fn test_fq12_serialization() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let x = Fq12::random(&mut rng);
+            let y = serialize_deserialize(&x);
+            assert!(y.is_ok(), "Failed to deserialize an Fq12 value.");
+            assert_eq!(x, y.unwrap());
+        }
+    }
+
+    #[test]
+    fn test_fr_non_canonical_rejected() {
+        // The modulus of Fr in big-endian.
+        let modulus: [u8; 32] = [
+            0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1,
+            0xd8, 0x05, 0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x01,
+        ];
+        let res: ParseResult<Fr> = from_bytes(&mut std::io::Cursor::new(&modulus));
+        assert!(res.is_err(), "The modulus should not be accepted.");
+        let max = [0xffu8; 32];
+        let res: ParseResult<Fr> = from_bytes(&mut std::io::Cursor::new(&max));
+        assert!(res.is_err(), "2^256 - 1 should not be accepted.");
+        // One less than the modulus is the largest canonical value.
+        let mut largest = modulus;
+        largest[31] = 0x00;
+        let res: ParseResult<Fr> = from_bytes(&mut std::io::Cursor::new(&largest));
+        let x = res.expect("The largest canonical scalar should be accepted.");
+        assert_eq!(to_bytes(&x), largest.to_vec());
+    }
+
+    #[test]
+    fn test_array_serialization() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let bytes: [u8; 32] = rng.gen();
+            let bytes_ser = to_bytes(&bytes);
+            assert_eq!(bytes_ser.len(), 32, "Arrays are serialized without length.");
+            let bytes_des = serialize_deserialize(&bytes).expect("Deserialization should succeed.");
+            assert_eq!(bytes, bytes_des);
+
+            let scalars = [
+                Fr::random(&mut rng),
+                Fr::random(&mut rng),
+                Fr::random(&mut rng),
+                Fr::random(&mut rng),
+            ];
+            let scalars_ser = to_bytes(&scalars);
+            assert_eq!(
+                scalars_ser.len(),
+                4 * 32,
+                "Arrays are serialized without length."
+            );
+            let scalars_des =
+                serialize_deserialize(&scalars).expect("Deserialization should succeed.");
+            assert_eq!(scalars, scalars_des);
+        }
+    }
+
+    #[test]
+    fn test_tuple_serialization() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let triple = (
+                G1::random(&mut rng),
+                Fr::random(&mut rng),
+                G2::random(&mut rng).into_affine(),
+            );
+            let triple_des =
+                serialize_deserialize(&triple).expect("Deserialization should succeed.");
+            assert_eq!(triple, triple_des);
+
+            let quadruple = (
+                G1::random(&mut rng),
+                Fr::random(&mut rng),
+                G2::random(&mut rng).into_affine(),
+                rng.gen::<u32>(),
+            );
+            let quadruple_des =
+                serialize_deserialize(&quadruple).expect("Deserialization should succeed.");
+            assert_eq!(quadruple, quadruple_des);
+
+            let quintuple = (
+                G1::random(&mut rng),
+                Fr::random(&mut rng),
+                G2::random(&mut rng).into_affine(),
+                rng.gen::<u32>(),
+                rng.gen::<u8>(),
+            );
+            let quintuple_des =
+                serialize_deserialize(&quintuple).expect("Deserialization should succeed.");
+            assert_eq!(quintuple, quintuple_des);
+
+            let sextuple = (
+                G1::random(&mut rng),
+                Fr::random(&mut rng),
+                G2::random(&mut rng).into_affine(),
+                rng.gen::<u32>(),
+                rng.gen::<u8>(),
+                rng.gen::<u64>(),
+            );
+            let sextuple_des =
+                serialize_deserialize(&sextuple).expect("Deserialization should succeed.");
+            assert_eq!(sextuple, sextuple_des);
+        }
+    }
+
+    #[test]
+    fn test_uncompressed_serialization() {
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let g1 = Uncompressed(G1::random(&mut rng));
+            assert_eq!(to_bytes(&g1).len(), 96);
+            let g1_des = serialize_deserialize(&g1).expect("Deserialization should succeed.");
+            assert_eq!(g1, g1_des);
+
+            let g2 = Uncompressed(G2::random(&mut rng));
+            assert_eq!(to_bytes(&g2).len(), 192);
+            let g2_des = serialize_deserialize(&g2).expect("Deserialization should succeed.");
+            assert_eq!(g2, g2_des);
+        }
+    }
+}
@@ -193,6 +193,44 @@ impl<T: Deserial, S: Deserial, U: Deserial> Deserial for (T, S, U) {
     }
 }
 
+impl<T: Deserial, S: Deserial, U: Deserial, V: Deserial> Deserial for (T, S, U, V) {
+    #[inline]
+    fn deserial<R: ReadBytesExt>(source: &mut R) -> ParseResult<Self> {
+        let x = T::deserial(source)?;
+        let y = S::deserial(source)?;
+        let z = U::deserial(source)?;
+        let u = V::deserial(source)?;
+        Ok((x, y, z, u))
+    }
+}
+
+impl<T: Deserial, S: Deserial, U: Deserial, V: Deserial, W: Deserial> Deserial for (T, S, U, V, W) {
+    #[inline]
+    fn deserial<R: ReadBytesExt>(source: &mut R) -> ParseResult<Self> {
+        let x = T::deserial(source)?;
+        let y = S::deserial(source)?;
+        let z = U::deserial(source)?;
+        let u = V::deserial(source)?;
+        let v = W::deserial(source)?;
+        Ok((x, y, z, u, v))
+    }
+}
+
+impl<T: Deserial, S: Deserial, U: Deserial, V: Deserial, W: Deserial, X: Deserial> Deserial
+    for (T, S, U, V, W, X)
+{
+    #[inline]
+    fn deserial<R: ReadBytesExt>(source: &mut R) -> ParseResult<Self> {
+        let x = T::deserial(source)?;
+        let y = S::deserial(source)?;
+        let z = U::deserial(source)?;
+        let u = V::deserial(source)?;
+        let v = W::deserial(source)?;
+        let w = X::deserial(source)?;
+        Ok((x, y, z, u, v, w))
+    }
+}
+
 /// Read a string of given size.
 /// NB: Be aware that this allocates a buffer of the given length, and so this
 /// must only be used when the size is bounded, otherwise it will lead to a
@@ -527,6 +565,41 @@ impl<T: Serial, S: Serial, U: Serial> Serial for (T, S, U) {
     }
 }
 
+impl<T: Serial, S: Serial, U: Serial, V: Serial> Serial for (T, S, U, V) {
+    #[inline]
+    fn serial<B: Buffer>(&self, out: &mut B) {
+        self.0.serial(out);
+        self.1.serial(out);
+        self.2.serial(out);
+        self.3.serial(out);
+    }
+}
+
+impl<T: Serial, S: Serial, U: Serial, V: Serial, W: Serial> Serial for (T, S, U, V, W) {
+    #[inline]
+    fn serial<B: Buffer>(&self, out: &mut B) {
+        self.0.serial(out);
+        self.1.serial(out);
+        self.2.serial(out);
+        self.3.serial(out);
+        self.4.serial(out);
+    }
+}
+
+impl<T: Serial, S: Serial, U: Serial, V: Serial, W: Serial, X: Serial> Serial
+    for (T, S, U, V, W, X)
+{
+    #[inline]
+    fn serial<B: Buffer>(&self, out: &mut B) {
+        self.0.serial(out);
+        self.1.serial(out);
+        self.2.serial(out);
+        self.3.serial(out);
+        self.4.serial(out);
+        self.5.serial(out);
+    }
+}
+
 impl<T> Serial for PhantomData<T> {
     #[inline]
     fn serial<B: Buffer>(&self, _out: &mut B) {}
@@ -728,6 +801,36 @@ impl<T: Deserial + Eq + Hash, S: BuildHasher + Default> Deserial for HashSet<T,
     }
 }
 
+/// Serialize an ordered map by encoding its length as a u32 in big endian, and
+/// then the entries in increasing order of keys.
+impl<K: Serial, V: Serial> Serial for BTreeMap<K, V> {
+    fn serial<B: Buffer>(&self, out: &mut B) {
+        (self.len() as u32).serial(out);
+        serial_map_no_length(self, out)
+    }
+}
+
+/// Dual to the [Serial] instance for maps. Deserialization is strict, the
+/// keys must be in strictly increasing order, so there is a unique encoding
+/// of each map.
+impl<K: Deserial + Ord, V: Deserial> Deserial for BTreeMap<K, V> {
+    fn deserial<R: ReadBytesExt>(source: &mut R) -> ParseResult<Self> {
+        let len: u32 = source.get()?;
+        let mut out = BTreeMap::new();
+        for _ in 0..len {
+            let k: K = source.get()?;
+            let v: V = source.get()?;
+            if let Some(last) = out.keys().next_back() {
+                if k <= *last {
+                    bail!("Keys not in order.")
+                }
+            }
+            out.insert(k, v);
+        }
+        Ok(out)
+    }
+}
+
 impl<T: Serial> Serial for &T {
     fn serial<W: Buffer + WriteBytesExt>(&self, target: &mut W) { (*self).serial(target) }
 }
@@ -815,3 +918,42 @@ pub fn base16_ignore_length_decode<'de, D: Deserializer<'de>, T: Deserial>(
     }
     des.deserialize_str(Base16IgnoreLengthVisitor(Default::default()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_map_serialization() {
+        let mut map = BTreeMap::new();
+        for i in 0..100u16 {
+            map.insert(i.wrapping_mul(7919), u64::from(i));
+        }
+        let bytes = to_bytes(&map);
+        let des: BTreeMap<u16, u64> =
+            from_bytes(&mut Cursor::new(&bytes)).expect("Deserialization should succeed.");
+        assert_eq!(map, des);
+    }
+
+    #[test]
+    fn test_map_non_canonical_rejected() {
+        // Two entries with keys out of order.
+        let mut unsorted = Vec::new();
+        unsorted.put(&2u32);
+        unsorted.put(&2u8);
+        unsorted.put(&0u8);
+        unsorted.put(&1u8);
+        unsorted.put(&0u8);
+        let res: ParseResult<BTreeMap<u8, u8>> = from_bytes(&mut Cursor::new(&unsorted));
+        assert!(res.is_err(), "Unsorted keys should be rejected.");
+        // Two entries with the same key.
+        let mut duplicate = Vec::new();
+        duplicate.put(&2u32);
+        duplicate.put(&1u8);
+        duplicate.put(&0u8);
+        duplicate.put(&1u8);
+        duplicate.put(&1u8);
+        let res: ParseResult<BTreeMap<u8, u8>> = from_bytes(&mut Cursor::new(&duplicate));
+        assert!(res.is_err(), "Duplicate keys should be rejected.");
+    }
+}
@@ -0,0 +1,37 @@
+#[macro_use]
+extern crate criterion;
+
+use criterion::Criterion;
+use curve_arithmetic::*;
+use pairing::bls12_381::G1;
+use rand::*;
+
+pub fn bench_fixed_base(c: &mut Criterion) {
+    let mut csprng = thread_rng();
+    let n = 100;
+    let base = G1::generate(&mut csprng);
+    let es: Vec<_> = (0..n).map(|_| G1::generate_scalar(&mut csprng)).collect();
+    let mut group = c.benchmark_group(format!("FixedBase({})", n));
+    group.bench_function("mul_by_scalar", |b| {
+        b.iter(|| {
+            for e in es.iter() {
+                base.mul_by_scalar(e);
+            }
+        })
+    });
+    for w in [4, 6, 8].iter() {
+        let table = FixedBaseTable::new(&base, *w);
+        group.bench_function(&format!("table({})", w), |b| {
+            b.iter(|| {
+                for e in es.iter() {
+                    table.mul(e);
+                }
+            })
+        });
+    }
+    group.bench_function("precompute", |b| b.iter(|| base.precompute()));
+    group.finish();
+}
+
+criterion_group!(fixed_base_benchmarks, bench_fixed_base);
+criterion_main!(fixed_base_benchmarks);
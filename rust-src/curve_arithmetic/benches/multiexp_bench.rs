@@ -40,5 +40,32 @@ pub fn bench_multiexp(c: &mut Criterion) {
     }
 }
 
-criterion_group!(multiexp_benchmarks, bench_multiexp);
+pub fn bench_multiexp_pippenger(c: &mut Criterion) {
+    let mut csprng = thread_rng();
+    let n = 256;
+    let gs: Vec<G1> = (0..n).map(|_| G1::generate(&mut csprng)).collect();
+    let es: Vec<_> = (0..n).map(|_| G1::generate_scalar(&mut csprng)).collect();
+    let mut group = c.benchmark_group(format!("Pippenger({})", n));
+    group.bench_function("Baseline", |b| {
+        b.iter(|| {
+            let mut a = G1::zero_point();
+            for (g, e) in gs.iter().zip(es.iter()) {
+                a = a.plus_point(&g.mul_by_scalar(e))
+            }
+        })
+    });
+    group.bench_function("multiexp", |b| b.iter(|| multiexp(&gs, &es)));
+    for w in 4..=8 {
+        group.bench_function(&format!("pippenger({})", w), |b| {
+            b.iter(|| multiexp_pippenger(&gs, &es, w))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(
+    multiexp_benchmarks,
+    bench_multiexp,
+    bench_multiexp_pippenger
+);
 criterion_main!(multiexp_benchmarks);
@@ -113,6 +113,8 @@ impl Curve for G2 {
     fn generate_scalar<T: Rng>(csprng: &mut T) -> Self::Scalar { Fr::random(csprng) }
 
     fn hash_to_group(b: &[u8]) -> Self { hash_to_curve_g2(b, HASH_TO_GROUP_G2_DST) }
+
+    fn hash_to_curve(domain: &[u8], msg: &[u8]) -> Self { hash_to_curve_g2(msg, domain) }
 }
 
 impl Curve for G1 {
@@ -197,6 +199,8 @@ impl Curve for G1 {
     fn generate_scalar<T: Rng>(csprng: &mut T) -> Self::Scalar { Fr::random(csprng) }
 
     fn hash_to_group(bytes: &[u8]) -> Self { hash_to_curve(bytes, HASH_TO_GROUP_G1_DST) }
+
+    fn hash_to_curve(domain: &[u8], msg: &[u8]) -> Self { hash_to_curve(msg, domain) }
 }
 
 impl Curve for G1Affine {
@@ -278,6 +282,8 @@ impl Curve for G1Affine {
     fn generate_scalar<T: Rng>(csprng: &mut T) -> Self::Scalar { Fr::random(csprng) }
 
     fn hash_to_group(b: &[u8]) -> Self { hash_to_curve(b, HASH_TO_GROUP_G1_DST).into_affine() }
+
+    fn hash_to_curve(domain: &[u8], msg: &[u8]) -> Self { hash_to_curve(msg, domain).into_affine() }
 }
 
 impl Curve for G2Affine {
@@ -359,6 +365,10 @@ impl Curve for G2Affine {
     fn generate_scalar<T: Rng>(csprng: &mut T) -> Self::Scalar { Fr::random(csprng) }
 
     fn hash_to_group(b: &[u8]) -> Self { hash_to_curve_g2(b, HASH_TO_GROUP_G2_DST).into_affine() }
+
+    fn hash_to_curve(domain: &[u8], msg: &[u8]) -> Self {
+        hash_to_curve_g2(msg, domain).into_affine()
+    }
 }
 
 impl Pairing for Bls12 {
@@ -418,6 +428,70 @@ mod tests {
         }
     }
 
+    // Check that hashing to the curve is deterministic, depends on the domain,
+    // agrees with hash_to_group on the fixed domain, and produces points in the
+    // prime order subgroup, i.e., points that pass the checked deserialization.
+    macro_rules! macro_test_hash_to_curve {
+        ($function_name:ident, $p:path, $dst:expr) => {
+            #[test]
+            pub fn $function_name() {
+                let msgs: [&[u8]; 3] = [b"", b"abc", b"concordium"];
+                for msg in msgs.iter() {
+                    let p = <$p>::hash_to_curve(b"test-domain", msg);
+                    assert_eq!(p, <$p>::hash_to_curve(b"test-domain", msg));
+                    assert_ne!(p, <$p>::hash_to_curve(b"other-domain", msg));
+                    assert_eq!(<$p>::hash_to_curve($dst, msg), <$p>::hash_to_group(msg));
+                    assert!(!p.is_zero_point());
+                    let q: $p = from_bytes(&mut Cursor::new(to_bytes(&p)))
+                        .expect("Hashed point should be in the group.");
+                    assert_eq!(p, q);
+                }
+            }
+        };
+    }
+
+    macro_test_hash_to_curve!(test_hash_to_curve_g1, G1, HASH_TO_GROUP_G1_DST);
+    macro_test_hash_to_curve!(test_hash_to_curve_g2, G2, HASH_TO_GROUP_G2_DST);
+    macro_test_hash_to_curve!(test_hash_to_curve_g1_affine, G1Affine, HASH_TO_GROUP_G1_DST);
+    macro_test_hash_to_curve!(test_hash_to_curve_g2_affine, G2Affine, HASH_TO_GROUP_G2_DST);
+
+    // Check that canonical scalar parsing rejects the group order, and accepts
+    // the value just below it.
+    #[test]
+    fn scalar_canonical_bounds() {
+        // The order of the group, in big-endian.
+        let mut order: [u8; 32] = [
+            0x73, 0xed, 0xa7, 0x53, 0x29, 0x9d, 0x7d, 0x48, 0x33, 0x39, 0xd8, 0x08, 0x09, 0xa1,
+            0xd8, 0x05, 0x53, 0xbd, 0xa4, 0x02, 0xff, 0xfe, 0x5b, 0xfe, 0xff, 0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00, 0x01,
+        ];
+        assert!(
+            bytes_to_scalar_canonical::<G1>(&order).is_err(),
+            "The group order is not canonical."
+        );
+        assert!(
+            bytes_to_scalar_canonical::<G1>(&[0xff; 32]).is_err(),
+            "Overflowing value."
+        );
+        assert!(
+            bytes_to_scalar_canonical::<G1>(&order[1..]).is_err(),
+            "Short input."
+        );
+        order[31] = 0;
+        let below = bytes_to_scalar_canonical::<G1>(&order).expect("Order - 1 is canonical.");
+        assert_eq!(below, {
+            let mut x = Fr::one();
+            x.negate();
+            x
+        });
+        // Canonical parsing agrees with deserialization.
+        let mut rng = rand::thread_rng();
+        for _ in 0..100 {
+            let n = Fr::random(&mut rng);
+            assert_eq!(bytes_to_scalar_canonical::<G1>(&to_bytes(&n)).unwrap(), n);
+        }
+    }
+
     macro_rules! macro_test_scalar_byte_conversion {
         ($function_name:ident, $p:path) => {
             #[test]
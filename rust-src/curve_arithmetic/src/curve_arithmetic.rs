@@ -1,6 +1,6 @@
 use byteorder::ReadBytesExt;
 use crypto_common::{Serial, Serialize};
-use ff::{Field, PrimeField};
+use ff::{Field, PrimeField, PrimeFieldRepr};
 use rand::*;
 use std::{
     borrow::Borrow,
@@ -83,6 +83,17 @@ pub trait Curve:
     fn scalar_from_bytes<A: AsRef<[u8]>>(bs: A) -> Self::Scalar;
     /// Hash to a curve point from a seed. This is deterministic function.
     fn hash_to_group(m: &[u8]) -> Self;
+    /// Hash the message to a point in the group, using the given domain
+    /// separation string. This uses the hash-to-curve suite of the group
+    /// (for BLS12-381 the SSWU suites with SHA-256 expansion), so there is
+    /// no known discrete logarithm relationship between any two outputs.
+    /// [Curve::hash_to_group] is this function with a fixed domain.
+    ///
+    /// Panics if the domain is longer than 255 bytes.
+    fn hash_to_curve(domain: &[u8], msg: &[u8]) -> Self;
+    /// Precompute a table for repeated multiplications of this point by
+    /// different public scalars. See [FixedBaseTable] for details.
+    fn precompute(&self) -> FixedBaseTable<Self> { FixedBaseTable::new(self, 8) }
 }
 
 /// A pairing friendly curve is a collection of two groups and a pairing
@@ -174,6 +185,39 @@ pub trait Pairing: Sized + 'static + Clone {
     }
 }
 
+/// Parse a scalar from its big-endian byte representation of exactly
+/// [Curve::SCALAR_LENGTH] bytes. In contrast to [Curve::scalar_from_bytes]
+/// this does not truncate or reduce the input, and fails if it represents a
+/// value that is not less than the order of the group. Every scalar thus has a
+/// unique encoding accepted by this function.
+///
+/// The comparison against the order of the group is done in constant time,
+/// i.e., it does not branch on, or index memory by, the input bytes. Only the
+/// outcome of the comparison is revealed.
+pub fn bytes_to_scalar_canonical<C: Curve>(bytes: &[u8]) -> anyhow::Result<C::Scalar> {
+    anyhow::ensure!(
+        bytes.len() == C::SCALAR_LENGTH,
+        "A scalar must be encoded in exactly {} bytes.",
+        C::SCALAR_LENGTH
+    );
+    let mut repr = <C::Scalar as PrimeField>::Repr::default();
+    repr.read_be(bytes)?;
+    // Compute the borrow of `repr - order` over all the limbs, least significant
+    // first. The final borrow is 1 if and only if `repr < order`.
+    let order = <C::Scalar as PrimeField>::char();
+    let mut borrow = 0u64;
+    for (limb, order_limb) in repr.as_ref().iter().zip(order.as_ref()) {
+        let (diff, borrow_order) = limb.overflowing_sub(*order_limb);
+        let (_, borrow_prev) = diff.overflowing_sub(borrow);
+        borrow = u64::from(borrow_order | borrow_prev);
+    }
+    anyhow::ensure!(
+        borrow == 1,
+        "The scalar is not less than the order of the group."
+    );
+    Ok(C::Scalar::from_repr(repr)?)
+}
+
 /// Like 'multiexp_worker', but computes a reasonable window size automatically.
 #[inline(always)]
 pub fn multiexp<C: Curve, X: Borrow<C>>(gs: &[X], exps: &[C::Scalar]) -> C {
@@ -278,6 +322,125 @@ pub fn multiexp_worker_given_table<C: Curve>(
     a
 }
 
+/// Extract `window_size` bits, starting at bit `offset`, of the little-endian
+/// representation of a scalar. Assumes `window_size` is at most 16.
+fn scalar_window<F: PrimeField>(repr: &F::Repr, offset: usize, window_size: usize) -> usize {
+    let limbs = repr.as_ref();
+    let (limb, shift) = (offset / 64, offset % 64);
+    let mut v = limbs[limb] >> shift;
+    if shift + window_size > 64 && limb + 1 < limbs.len() {
+        v |= limbs[limb + 1] << (64 - shift);
+    }
+    (v & ((1u64 << window_size) - 1)) as usize
+}
+
+/// A table of multiples of a fixed base, used to speed up repeated
+/// multiplications of the same point by different scalars.
+///
+/// For a window size `w` the table contains `j * 2^(w * i) * base` for every
+/// window `i` of the scalar and every `0 <= j < 2^w`. A multiplication then
+/// needs one addition per window and no doublings.
+///
+/// Multiplication is not constant time. It indexes the table by the bits of
+/// the scalar, and skips windows that are zero, so its timing and memory access
+/// pattern depend on the scalar. The table must thus not be used with secret
+/// scalars, such as `idCredSecret` or secret keys.
+#[derive(Debug, Clone)]
+pub struct FixedBaseTable<C> {
+    window_size: usize,
+    table:       Vec<Vec<C>>,
+}
+
+impl<C: Curve> FixedBaseTable<C> {
+    /// Precompute the table for the given base and window size.
+    ///
+    /// Panics if the window size is not between 1 and 16.
+    pub fn new(base: &C, window_size: usize) -> Self {
+        assert!(window_size >= 1);
+        assert!(window_size <= 16);
+        let num_windows = (C::Scalar::NUM_BITS as usize + window_size - 1) / window_size;
+        let mut table = Vec::with_capacity(num_windows);
+        // 2^(w * i) * base
+        let mut window_base = *base;
+        for _ in 0..num_windows {
+            let mut multiples = Vec::with_capacity(1 << window_size);
+            let mut tmp = C::zero_point();
+            for _ in 0..(1 << window_size) {
+                multiples.push(tmp);
+                tmp = tmp.plus_point(&window_base);
+            }
+            // After the loop tmp is 2^w * window_base.
+            window_base = tmp;
+            table.push(multiples);
+        }
+        FixedBaseTable { window_size, table }
+    }
+
+    /// Multiply the base of the table by the given scalar. The result is the
+    /// same as `base.mul_by_scalar(scalar)`.
+    ///
+    /// This is not constant time, so the scalar must not be secret.
+    pub fn mul(&self, scalar: &C::Scalar) -> C {
+        let repr = scalar.into_repr();
+        let mut a = C::zero_point();
+        for (i, multiples) in self.table.iter().enumerate() {
+            let idx = scalar_window::<C::Scalar>(&repr, i * self.window_size, self.window_size);
+            if idx != 0 {
+                a = a.plus_point(&multiples[idx]);
+            }
+        }
+        a
+    }
+}
+
+/// Compute the multiexponentiation using Pippenger's bucket method. Each scalar
+/// is split into windows of `window_size` bits. For each window the points are
+/// sorted into buckets by the value of their scalar in that window, and the
+/// buckets are then combined using running sums. This needs no precomputed
+/// table, and for large inputs it uses fewer group operations than
+/// `multiexp_worker`.
+///
+/// Panics if:
+/// - the lengths of inputs differ
+/// - window size is not between 1 and 16, since the number of buckets, and
+///   thus memory use, is exponential in the window size
+pub fn multiexp_pippenger<C: Curve, X: Borrow<C>>(
+    gs: &[X],
+    exps: &[C::Scalar],
+    window_size: usize,
+) -> C {
+    assert_eq!(gs.len(), exps.len());
+    assert!(window_size >= 1);
+    assert!(window_size <= 16);
+
+    let reprs: Vec<_> = exps.iter().map(|e| e.into_repr()).collect();
+
+    let num_windows = (C::Scalar::NUM_BITS as usize + window_size - 1) / window_size;
+    let mut a = C::zero_point();
+    let mut buckets = vec![C::zero_point(); (1 << window_size) - 1];
+    for w in (0..num_windows).rev() {
+        for _ in 0..window_size {
+            a = a.double_point();
+        }
+        for b in buckets.iter_mut() {
+            *b = C::zero_point();
+        }
+        for (g, repr) in gs.iter().zip(reprs.iter()) {
+            let idx = scalar_window::<C::Scalar>(repr, w * window_size, window_size);
+            if idx != 0 {
+                buckets[idx - 1] = buckets[idx - 1].plus_point(g.borrow());
+            }
+        }
+        // Compute sum_i (i + 1) * buckets[i] as a sum of running sums.
+        let mut running = C::zero_point();
+        for b in buckets.iter().rev() {
+            running = running.plus_point(b);
+            a = a.plus_point(&running);
+        }
+    }
+    a
+}
+
 /// Compute the table of powers that can be used `multiexp_worker_given_table`.
 pub fn multiexp_table<C: Curve, X: Borrow<C>>(gs: &[X], window_size: usize) -> Vec<Vec<C>> {
     let k = gs.len();
@@ -325,4 +488,53 @@ mod tests {
             )
         }
     }
+
+    #[test]
+    pub fn test_fixed_base_table() {
+        let mut csprng = thread_rng();
+        let base = G1::generate(&mut csprng);
+        // Window sizes that do and do not divide the number of bits.
+        for &w in [1, 4, 7, 8].iter() {
+            let table = FixedBaseTable::new(&base, w);
+            assert_eq!(table.mul(&<G1 as Curve>::Scalar::zero()), G1::zero_point());
+            for _ in 0..50 {
+                let e = G1::generate_scalar(&mut csprng);
+                assert_eq!(
+                    table.mul(&e),
+                    base.mul_by_scalar(&e),
+                    "Table multiplication with window size {} differs from mul_by_scalar.",
+                    w
+                );
+            }
+        }
+        let e = G1::generate_scalar(&mut csprng);
+        assert_eq!(base.precompute().mul(&e), base.mul_by_scalar(&e));
+    }
+
+    #[test]
+    pub fn test_multiexp_pippenger() {
+        let mut csprng = thread_rng();
+        for l in 0..50 {
+            let mut gs = Vec::with_capacity(l);
+            let mut es = Vec::with_capacity(l);
+            for _ in 0..l {
+                gs.push(G1::generate(&mut csprng));
+                es.push(G1::generate_scalar(&mut csprng));
+            }
+            let mut goal = G1::zero_point();
+            // Naive multiply + add method.
+            for (g, e) in gs.iter().zip(es.iter()) {
+                goal = goal.plus_point(&g.mul_by_scalar(e))
+            }
+            // Window sizes that do and do not divide the number of bits.
+            for &w in [1, 5, 8, 13].iter() {
+                let g = multiexp_pippenger(&gs, &es, w);
+                assert_eq!(
+                    goal, g,
+                    "Pippenger with window size {} differs from the naive method.",
+                    w
+                );
+            }
+        }
+    }
 }
@@ -122,4 +122,21 @@ mod tests {
     macro_test_value_to_byte_conversion!(value_to_byte_conversion_bls12_381_g1_affine, G1Affine);
 
     macro_test_value_to_byte_conversion!(value_to_byte_conversion_bls12_381_g2_affine, G2Affine);
+
+    #[test]
+    pub fn secret_zeroized_on_drop() {
+        let mut csprng = thread_rng();
+        for _i in 1..20 {
+            let x = <G1Affine as Curve>::generate_non_zero_scalar(&mut csprng);
+            let mut secret = std::mem::ManuallyDrop::new(Secret::new(x));
+            assert_eq!(*secret.as_ref(), x);
+            // Run the destructor, but keep the memory so it can be inspected.
+            // The field is plain data, so reading it afterwards is sound.
+            let remaining = unsafe {
+                ptr::drop_in_place(&mut *secret as *mut Secret<_>);
+                ptr::read(&secret.secret)
+            };
+            assert_eq!(remaining, <G1Affine as Curve>::Scalar::zero());
+        }
+    }
 }
@@ -87,4 +87,35 @@ mod tests {
             assert_eq!(sk2, sk);
         }
     }
+
+    #[test]
+    pub fn test_prf_evaluation() {
+        let mut csprng = thread_rng();
+        let g = G1::generate(&mut csprng);
+        for n in 0..10u8 {
+            let sk = SecretKey::<G1>::generate(&mut csprng);
+            let out = sk
+                .prf(&g, n)
+                .expect("k + n is non-zero with overwhelming probability.");
+            // The output raised to k + n is the base.
+            let mut k_plus_n = G1::scalar_from_u64(u64::from(n));
+            k_plus_n.add_assign(&sk);
+            assert_eq!(out.mul_by_scalar(&k_plus_n), g);
+            assert_eq!(g.mul_by_scalar(&sk.prf_exponent(n).unwrap()), out);
+        }
+    }
+
+    #[test]
+    pub fn test_prf_division_by_zero() {
+        let g = G1::generate(&mut thread_rng());
+        for n in 0..10u8 {
+            // The key -n makes k + n zero.
+            let mut k = G1::scalar_from_u64(u64::from(n));
+            k.negate();
+            let sk = SecretKey::<G1>::new(k);
+            assert!(sk.prf(&g, n).is_err());
+            assert!(sk.prf_exponent(n).is_err());
+            assert!(sk.prf(&g, n.wrapping_add(1)).is_ok());
+        }
+    }
 }
@@ -0,0 +1,46 @@
+//! Batch verification of ed25519 signatures.
+use ed25519_dalek::{PublicKey, Signature};
+
+/// Verify a batch of signatures, where the `i`-th signature is checked against
+/// the `i`-th message and the `i`-th public key. This is faster than verifying
+/// the signatures one at a time, but it only reports whether all of them are
+/// valid. Returns `false` if the slices have different lengths.
+pub fn verify_batch(messages: &[&[u8]], sigs: &[Signature], keys: &[PublicKey]) -> bool {
+    if messages.len() != sigs.len() || messages.len() != keys.len() {
+        return false;
+    }
+    ed25519_dalek::verify_batch(messages, sigs, keys).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Keypair, Signer};
+    use rand::thread_rng;
+
+    #[test]
+    pub fn test_verify_batch() {
+        let mut csprng = thread_rng();
+        let keypairs = (0..5)
+            .map(|_| Keypair::generate(&mut csprng))
+            .collect::<Vec<_>>();
+        let messages = (0..5u8).map(|i| vec![i; 32]).collect::<Vec<_>>();
+        let messages = messages.iter().map(|m| m.as_slice()).collect::<Vec<_>>();
+        let mut sigs = keypairs
+            .iter()
+            .zip(messages.iter())
+            .map(|(kp, m)| kp.sign(m))
+            .collect::<Vec<_>>();
+        let keys = keypairs.iter().map(|kp| kp.public).collect::<Vec<_>>();
+        assert!(verify_batch(&messages, &sigs, &keys));
+
+        // Mismatched lengths.
+        assert!(!verify_batch(&messages[1..], &sigs, &keys));
+        assert!(!verify_batch(&messages, &sigs[1..], &keys));
+        assert!(!verify_batch(&messages, &sigs, &keys[1..]));
+
+        // A single invalid signature makes the batch fail.
+        sigs[3] = keypairs[3].sign(b"another message");
+        assert!(!verify_batch(&messages, &sigs, &keys));
+    }
+}
@@ -72,6 +72,23 @@ fn point_from_public_key(public_key: &PublicKey) -> Option<EdwardsPoint> {
     CompressedEdwardsY::from_slice(&bytes).decompress()
 }
 
+/// Domain separation string used when deriving keys from a seed.
+const KEY_DERIVATION_DOMAIN: &[u8] = b"concordium_ed25519_keypair_from_seed";
+
+/// Deterministically derive a keypair from a 32 byte seed. The seed is hashed
+/// together with a domain separation string, so the secret key is never the
+/// seed itself, and the same seed used elsewhere does not give the same key.
+pub fn keypair_from_seed(seed: &[u8; 32]) -> Keypair {
+    let mut h = Sha512::new();
+    h.update(KEY_DERIVATION_DOMAIN);
+    h.update(seed);
+    let hash = h.finalize();
+    let secret = SecretKey::from_bytes(&hash[..SECRET_KEY_LENGTH])
+        .expect("Any 32 bytes are a valid secret key.");
+    let public = PublicKey::from(&secret);
+    Keypair { secret, public }
+}
+
 /// Construct a proof of knowledge of secret key.
 ///
 /// The `public_key` and `secret_key` must be the ed25519 public and secret key
@@ -184,4 +201,26 @@ mod tests {
             assert_eq!(proof, proof_des.expect("Proof did not deserialize."));
         }
     }
+
+    #[test]
+    pub fn test_keypair_from_seed() {
+        let mut seed = [0u8; 32];
+        for (i, b) in seed.iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        let kp = keypair_from_seed(&seed);
+        // Fixed test vector so that the derivation stays the same across versions.
+        let expected: [u8; 32] = [
+            114, 52, 232, 166, 57, 139, 206, 103, 8, 16, 246, 99, 186, 156, 160, 232, 56, 152, 49,
+            60, 90, 221, 94, 73, 145, 42, 116, 233, 189, 134, 7, 137,
+        ];
+        assert_eq!(kp.public.to_bytes(), expected);
+        assert_eq!(kp.public, keypair_from_seed(&seed).public);
+        assert_eq!(kp.public, PublicKey::from(&kp.secret));
+        // The seed itself is not used as the secret key.
+        assert_ne!(kp.secret.to_bytes(), seed);
+        let mut other_seed = seed;
+        other_seed[0] = 1;
+        assert_ne!(kp.public, keypair_from_seed(&other_seed).public);
+    }
 }
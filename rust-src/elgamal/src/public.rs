@@ -53,6 +53,16 @@ impl<C: Curve> PublicKey<C> {
         self.encrypt_rand(csprng, m).0
     }
 
+    /// Encrypt a sequence of messages. Each message is encrypted with fresh
+    /// randomness, so that no two ciphertexts share randomness. Use
+    /// `encrypt_rand` if the randomness is needed, e.g., for proofs.
+    pub fn encrypt_batch<'a, T, I>(&self, ms: I, csprng: &mut T) -> Vec<Cipher<C>>
+    where
+        T: Rng,
+        I: IntoIterator<Item = &'a Message<C>>, {
+        ms.into_iter().map(|m| self.encrypt(csprng, m)).collect()
+    }
+
     pub fn hide(&self, k: &C::Scalar, message: &Message<C>) -> Cipher<C> {
         let t = self.generator.mul_by_scalar(k);
         let s = self.key.mul_by_scalar(k).plus_point(&message.value);
@@ -169,4 +179,29 @@ mod tests {
 
     macro_test_key_to_byte_conversion!(key_to_byte_conversion_g1, G1);
     macro_test_key_to_byte_conversion!(key_to_byte_conversion_g2, G2);
+
+    macro_rules! macro_test_encrypt_batch {
+        ($function_name:ident, $curve_type:path) => {
+            #[test]
+            pub fn $function_name() {
+                let mut csprng = thread_rng();
+                let sk: SecretKey<$curve_type> = SecretKey::generate_all(&mut csprng);
+                let pk = PublicKey::from(&sk);
+                let m: Message<$curve_type> = Message::generate(&mut csprng);
+                let ms = [Message { value: m.value }, Message { value: m.value }];
+                let ciphers = pk.encrypt_batch(&ms, &mut csprng);
+                assert_eq!(ciphers.len(), 2);
+                assert_ne!(
+                    ciphers[0].0, ciphers[1].0,
+                    "Ciphertexts should use different randomness."
+                );
+                for c in ciphers.iter() {
+                    assert_eq!(sk.decrypt(c), m);
+                }
+            }
+        };
+    }
+
+    macro_test_encrypt_batch!(encrypt_batch_g1, G1);
+    macro_test_encrypt_batch!(encrypt_batch_g2, G2);
 }
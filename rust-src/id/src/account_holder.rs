@@ -1,6 +1,7 @@
 //! Functionality needed by the account holder, either when interacting with the
 //! identity provider, or when interacting with the chain.
 use crate::{
+    identity_provider,
     secret_sharing::*,
     sigma_protocols::{
         com_enc_eq, com_eq, com_eq_different_groups, com_eq_sig, com_mult, common::*, dlog,
@@ -638,6 +639,44 @@ pub fn commitment_to_share_and_rand<C: Curve>(
     (cmm, rnd)
 }
 
+/// Check that the identity provider's signature on the identity object is
+/// valid. The signature is on a blinded message, so the account holder needs
+/// their secret data ([IdObjectUseData]) to reconstruct the signed message and
+/// retrieve the signature. This should be checked before any credentials are
+/// created from the identity object since credentials based on an identity
+/// object with an invalid signature will be rejected by the chain.
+pub fn verify_identity_object_signature<
+    P: Pairing,
+    C: Curve<Scalar = P::ScalarField>,
+    AttributeType: Attribute<C::Scalar>,
+>(
+    ip_info: &IpInfo<P>,
+    id_object: &impl HasIdentityObjectFields<P, C, AttributeType>,
+    id_object_use_data: &IdObjectUseData<P, C>,
+) -> bool {
+    let common_fields = id_object.get_common_pio_fields();
+    let alist = id_object.get_attribute_list();
+    let aci = &id_object_use_data.aci;
+    let public_values = match identity_provider::public_message_values::<C, _>(
+        common_fields.choice_ar_parameters.threshold,
+        &common_fields.choice_ar_parameters.ar_identities,
+        alist,
+    ) {
+        Ok(values) => values,
+        Err(_) => return false,
+    };
+    let mut message = Vec::with_capacity(public_values.len() + 2);
+    message.push(*aci.cred_holder_info.id_cred.id_cred_sec);
+    message.push(*aci.prf_key);
+    message.extend(public_values);
+    let retrieved_sig = id_object
+        .get_signature()
+        .retrieve(&id_object_use_data.randomness);
+    ip_info
+        .ip_verify_key
+        .verify(&retrieved_sig, &ps_sig::KnownMessage(message))
+}
+
 /// Generates a credential deployment info and outputs the randomness used in
 /// commitments. The randomness should be stored for later use, e.g. to open
 /// commitments later on. The information is meant to be valid in the context of
@@ -720,6 +759,12 @@ where
         id_object.get_common_pio_fields(),
         id_object.get_attribute_list(),
     );
+    ensure!(
+        cred_counter <= alist.max_accounts,
+        "Cannot create CDI with account number {} since the identity only allows {} accounts.",
+        cred_counter,
+        alist.max_accounts
+    );
     let sig_retrieval_rand = &id_object_use_data.randomness;
     let aci = &id_object_use_data.aci;
 
@@ -253,7 +253,7 @@ fn id_cred_pub_verifier<C: Curve, A: HasArPublicKey<C>>(
 /// Verify a policy. This currently does not do anything since
 /// the only check that is done is that the commitments are opened correctly,
 /// and that check is part of the signature check.
-fn verify_policy<C: Curve, AttributeType: Attribute<C::Scalar>>(
+pub fn verify_policy<C: Curve, AttributeType: Attribute<C::Scalar>>(
     _commitment_key: &CommitmentKey<C>,
     _commitments: &CredentialDeploymentCommitments<C>,
     _policy: &Policy<C, AttributeType>,
@@ -566,16 +566,21 @@ fn sign_initial_cred_values<
         .into()
 }
 
-pub fn compute_message<P: Pairing, AttributeType: Attribute<P::ScalarField>>(
-    cmm_prf: &Commitment<P::G1>,
-    cmm_sc: &Commitment<P::G1>,
+/// The values of the message signed by the identity provider that are known to
+/// both the identity provider and the account holder. These are, in that order,
+/// - created_at and valid_to dates of the attribute list, and the threshold
+/// - encoding of anonymity revokers.
+/// - tags of the attribute list
+/// - the maximum number of accounts
+/// - attribute list elements
+///
+/// The full message additionally starts with idCredSec and the prf key, which
+/// the identity provider only knows commitments to.
+pub fn public_message_values<C: Curve, AttributeType: Attribute<C::Scalar>>(
     threshold: Threshold,
     ar_list: &BTreeSet<ArIdentity>,
-    att_list: &AttributeList<P::ScalarField, AttributeType>,
-    ps_public_key: &ps_sig::PublicKey<P>,
-) -> Result<ps_sig::UnknownMessage<P>, Reason> {
-    let max_accounts = P::G1::scalar_from_u64(att_list.max_accounts.into());
-
+    att_list: &AttributeList<C::Scalar, AttributeType>,
+) -> Result<Vec<C::Scalar>, Reason> {
     let tags = {
         match utils::encode_tags(att_list.alist.keys()) {
             Ok(f) => f,
@@ -583,63 +588,67 @@ pub fn compute_message<P: Pairing, AttributeType: Attribute<P::ScalarField>>(
         }
     };
 
-    // the list to be signed consists of (in that order)
-    // - commitment to idcredsec
-    // - commitment to prf key
-    // - created_at and valid_to dates of the attribute list
-    // - encoding of anonymity revokers.
-    // - tags of the attribute list
-    // - attribute list elements
-
     let ar_encoded = match utils::encode_ars(ar_list) {
         Some(x) => x,
         None => return Err(Reason::WrongArParameters),
     };
 
-    let att_vec = &att_list.alist;
-    let m = ar_encoded.len();
-    let n = att_vec.len();
-    let key_vec = &ps_public_key.ys;
-
-    if key_vec.len() < n + m + 5 {
-        return Err(Reason::TooManyAttributes);
-    }
-
-    let mut gs = Vec::with_capacity(1 + m + 2 + n);
-    let mut exps = Vec::with_capacity(1 + m + 2 + n);
-
     // The error here should never happen, but it is safe to just propagate it if it
     // does by any chance.
     let public_params =
         utils::encode_public_credential_values(att_list.created_at, att_list.valid_to, threshold)
             .map_err(|_| Reason::IllegalAttributeRequirements)?;
 
-    // add valid_to, created_at, threshold.
-    gs.push(key_vec[2]);
-    exps.push(public_params);
-
-    // and add all anonymity revocation
-    for i in 3..(m + 3) {
-        let ar_handle = ar_encoded[i - 3];
-        gs.push(key_vec[i]);
-        exps.push(ar_handle);
-    }
+    let mut values = Vec::with_capacity(ar_encoded.len() + att_list.alist.len() + 3);
+    values.push(public_params);
+    values.extend(ar_encoded);
+    values.push(tags);
+    values.push(C::scalar_from_u64(att_list.max_accounts.into()));
+    // NB: It is crucial that att_vec is an ordered map and that .values iterator
+    // returns messages in order of tags.
+    values.extend(att_list.alist.values().map(|v| v.to_field_element()));
+    Ok(values)
+}
 
-    gs.push(key_vec[m + 3]);
-    exps.push(tags);
+/// The length of the message signed by the identity provider, i.e., the
+/// number of values it consists of, when using the given anonymity revokers
+/// and the given number of attributes. The key of the identity provider must
+/// be at least this long. This is `None` if the anonymity revokers cannot be
+/// encoded.
+pub fn signed_message_len<C: Curve>(
+    ar_list: &BTreeSet<ArIdentity>,
+    num_attributes: usize,
+) -> Option<usize> {
+    // idCredSec and the prf key, the public credential values, the encoded
+    // anonymity revokers, the tags, the maximum number of accounts, and the
+    // attributes.
+    let num_ar_scalars = utils::encode_ars::<C::Scalar>(ar_list)?.len();
+    Some(2 + 1 + num_ar_scalars + 2 + num_attributes)
+}
 
-    gs.push(key_vec[m + 3 + 1]);
-    exps.push(max_accounts);
+pub fn compute_message<P: Pairing, AttributeType: Attribute<P::ScalarField>>(
+    cmm_prf: &Commitment<P::G1>,
+    cmm_sc: &Commitment<P::G1>,
+    threshold: Threshold,
+    ar_list: &BTreeSet<ArIdentity>,
+    att_list: &AttributeList<P::ScalarField, AttributeType>,
+    ps_public_key: &ps_sig::PublicKey<P>,
+) -> Result<ps_sig::UnknownMessage<P>, Reason> {
+    // the list to be signed consists of (in that order)
+    // - commitment to idcredsec
+    // - commitment to prf key
+    // - the values computed by public_message_values
+    let exps = public_message_values::<P::G1, _>(threshold, ar_list, att_list)?;
 
-    // NB: It is crucial that att_vec is an ordered map and that .values iterator
-    // returns messages in order of tags.
-    for (&k, v) in key_vec.iter().skip(m + 5).zip(att_vec.values()) {
-        let att = v.to_field_element();
-        gs.push(k);
-        exps.push(att);
+    // The first two keys are for idCredSec and the prf key.
+    let key_vec = &ps_public_key.ys;
+    if key_vec.len() < exps.len() + 2 {
+        return Err(Reason::TooManyAttributes);
     }
+    let gs = &key_vec[2..exps.len() + 2];
+
     let msg =
-        ps_sig::UnknownMessage(multiexp(&gs, &exps).plus_point(&cmm_sc.0.plus_point(&cmm_prf.0)));
+        ps_sig::UnknownMessage(multiexp(gs, &exps).plus_point(&cmm_sc.0.plus_point(&cmm_prf.0)));
     Ok(msg)
 }
 
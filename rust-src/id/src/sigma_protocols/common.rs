@@ -73,6 +73,7 @@ pub trait SigmaProtocol: Sized {
 
 #[derive(Debug, Serialize, Eq, PartialEq, SerdeBase16Serialize, Clone)]
 /// Generic structure to contain a single sigma proof.
+/// It is serialized as the challenge followed by the witness of the protocol.
 pub struct SigmaProof<W: Serialize> {
     pub challenge: Challenge,
     pub witness:   W,
@@ -1,5 +1,11 @@
-use crate::sigma_protocols::{com_enc_eq, com_eq_sig, common::*, dlog};
+use crate::sigma_protocols::{
+    aggregate_dlog, com_enc_eq, com_eq, com_eq_different_groups, com_eq_sig, com_lin, com_mult,
+    common::*, dlog,
+};
+use crypto_common::*;
+use curve_arithmetic::Curve;
 use pairing::bls12_381::{Bls12, G1, G2};
+use pedersen_scheme::Commitment;
 use random_oracle::RandomOracle;
 
 #[test]
@@ -14,3 +20,61 @@ pub fn test_and() {
         assert!(verify(&mut RandomOracle::domain("test"), &prover, &proof))
     })
 }
+
+/// Test that a conjunction of a dlog and a com_enc_eq proof verifies, and
+/// that it is rejected if the statement of either part changes.
+#[test]
+pub fn test_and_dlog_com_enc_eq() {
+    let mut csprng = rand::thread_rng();
+    for _ in 0..10 {
+        AndAdapter::<dlog::Dlog<G1>, com_enc_eq::ComEncEq<G1>>::with_valid_data(
+            0,
+            &mut csprng,
+            |mut prover, secret, csprng| {
+                let proof = prove(&mut RandomOracle::domain("test"), &prover, secret, csprng)
+                    .expect("Proving should succeed.");
+                assert!(verify(&mut RandomOracle::domain("test"), &prover, &proof));
+
+                let public = prover.first.public;
+                prover.first.public = G1::generate(csprng);
+                assert!(!verify(&mut RandomOracle::domain("test"), &prover, &proof));
+                prover.first.public = public;
+
+                prover.second.commitment = Commitment(G1::generate(csprng));
+                assert!(!verify(&mut RandomOracle::domain("test"), &prover, &proof));
+            },
+        )
+    }
+}
+
+/// Check that proofs of the given protocol survive a serialization round trip,
+/// and that parsing a truncated proof fails.
+fn test_proof_serialization<D: SigmaProtocol>(data_size: usize) {
+    let mut csprng = rand::thread_rng();
+    D::with_valid_data(data_size, &mut csprng, |prover, secret, csprng| {
+        let proof = prove(&mut RandomOracle::domain("test"), &prover, secret, csprng)
+            .expect("Proving should succeed.");
+        let bytes = to_bytes(&proof);
+        let parsed: SigmaProof<D::ProverWitness> =
+            from_bytes(&mut std::io::Cursor::new(&bytes)).expect("Parsing should succeed.");
+        assert_eq!(to_bytes(&parsed), bytes);
+        assert!(verify(&mut RandomOracle::domain("test"), &prover, &parsed));
+        for len in 0..bytes.len() {
+            let truncated: ParseResult<SigmaProof<D::ProverWitness>> =
+                from_bytes(&mut std::io::Cursor::new(&bytes[..len]));
+            assert!(truncated.is_err(), "Parsing {} bytes should fail.", len);
+        }
+    })
+}
+
+#[test]
+pub fn test_serialization() {
+    test_proof_serialization::<aggregate_dlog::AggregateDlog<G1>>(10);
+    test_proof_serialization::<com_enc_eq::ComEncEq<G1>>(10);
+    test_proof_serialization::<com_eq::ComEq<G1, G1>>(10);
+    test_proof_serialization::<com_eq_different_groups::ComEqDiffGroups<G1, G2>>(10);
+    test_proof_serialization::<com_eq_sig::ComEqSig<Bls12, G1>>(10);
+    test_proof_serialization::<com_lin::ComLin<G1>>(10);
+    test_proof_serialization::<com_mult::ComMult<G1>>(10);
+    test_proof_serialization::<dlog::Dlog<G1>>(10);
+}
@@ -534,3 +534,241 @@ pub fn test_pipeline_v1() {
     let cdi_check = verify_cdi(&global_ctx, &ip_info, &ars_infos, &cdi, &Left(EXPIRY));
     assert_ne!(cdi_check, Ok(()));
 }
+
+/// Test that the number of attributes an identity provider can sign is
+/// determined by the length of its signing key, and that lists that do not fit
+/// are rejected.
+#[test]
+pub fn test_attribute_capacity() {
+    let mut csprng = thread_rng();
+
+    let max_attrs = 16;
+    let num_ars = 1;
+    let IpData {
+        public_ip_info: ip_info,
+        ip_secret_key,
+        ..
+    } = test_create_ip_info(&mut csprng, num_ars, max_attrs);
+
+    let global_ctx = GlobalContext::generate(String::from("genesis_string"));
+
+    let (ars_infos, _) =
+        test_create_ars(&global_ctx.on_chain_commitment_key.g, num_ars, &mut csprng);
+
+    let id_use_data = test_create_id_use_data(&mut csprng);
+
+    let mut alist = test_create_attributes();
+    alist.alist = (0..12u8)
+        .map(|i| (AttributeTag::from(i), AttributeKind::from(u64::from(i))))
+        .collect();
+    let (context, pio, _) =
+        test_create_pio_v1(&id_use_data, &ip_info, &ars_infos, &global_ctx, num_ars);
+    let ver_ok = verify_credentials_v1(&pio, context, &alist, &ip_secret_key);
+    assert!(
+        ver_ok.is_ok(),
+        "Signing 12 attributes with capacity 16 should succeed."
+    );
+
+    alist.alist = (0..=max_attrs)
+        .map(|i| (AttributeTag::from(i), AttributeKind::from(u64::from(i))))
+        .collect();
+    let (context, pio, _) =
+        test_create_pio_v1(&id_use_data, &ip_info, &ars_infos, &global_ctx, num_ars);
+    let ver_err = verify_credentials_v1(&pio, context, &alist, &ip_secret_key);
+    assert_eq!(
+        ver_err.err(),
+        Some(Reason::TooManyAttributes),
+        "Attribute lists exceeding the capacity should be rejected."
+    );
+}
+
+/// Test that the account holder accepts a correctly signed identity object, and
+/// rejects one whose signature has been tampered with.
+#[test]
+pub fn test_verify_identity_object_signature() {
+    let mut csprng = thread_rng();
+
+    let num_ars = 3;
+    let IpData {
+        public_ip_info: ip_info,
+        ip_secret_key,
+        ..
+    } = test_create_ip_info(&mut csprng, num_ars, 10);
+
+    let global_ctx = GlobalContext::generate(String::from("genesis_string"));
+
+    let (ars_infos, _) =
+        test_create_ars(&global_ctx.on_chain_commitment_key.g, num_ars, &mut csprng);
+
+    let id_use_data = test_create_id_use_data(&mut csprng);
+    let alist = test_create_attributes();
+    let (context, pio, _) =
+        test_create_pio_v1(&id_use_data, &ip_info, &ars_infos, &global_ctx, num_ars);
+    let signature = verify_credentials_v1(&pio, context, &alist, &ip_secret_key)
+        .expect("Signing the identity object should succeed.");
+
+    let mut id_object = IdentityObjectV1 {
+        pre_identity_object: pio,
+        alist,
+        signature,
+    };
+    assert!(
+        verify_identity_object_signature(&ip_info, &id_object, &id_use_data),
+        "The identity provider's signature should verify."
+    );
+
+    id_object.signature.1 = id_object.signature.1.double_point();
+    assert!(
+        !verify_identity_object_signature(&ip_info, &id_object, &id_use_data),
+        "A tampered signature should be rejected."
+    );
+}
+
+/// Test that credentials can only be created for account numbers up to the
+/// maximum number of accounts in the attribute list.
+#[test]
+pub fn test_max_accounts() {
+    let mut csprng = thread_rng();
+
+    let num_ars = 1;
+    let IpData {
+        public_ip_info: ip_info,
+        ip_secret_key,
+        ..
+    } = test_create_ip_info(&mut csprng, num_ars, 10);
+
+    let global_ctx = GlobalContext::generate(String::from("genesis_string"));
+
+    let (ars_infos, _) =
+        test_create_ars(&global_ctx.on_chain_commitment_key.g, num_ars, &mut csprng);
+
+    let id_use_data = test_create_id_use_data(&mut csprng);
+    let (context, pio, _) =
+        test_create_pio_v1(&id_use_data, &ip_info, &ars_infos, &global_ctx, num_ars);
+    let mut alist = test_create_attributes();
+    alist.max_accounts = 2;
+    let signature = verify_credentials_v1(&pio, context, &alist, &ip_secret_key)
+        .expect("Signing the identity object should succeed.");
+    let id_object = IdentityObjectV1 {
+        pre_identity_object: pio,
+        alist,
+        signature,
+    };
+
+    let policy = Policy {
+        valid_to:   YearMonth::try_from(2022 << 8 | 5).unwrap(), // May 2022
+        created_at: YearMonth::try_from(2020 << 8 | 5).unwrap(), // May 2020
+        policy_vec: BTreeMap::new(),
+        _phantom:   Default::default(),
+    };
+    let acc_data = CredentialData {
+        keys:      {
+            let mut keys = BTreeMap::new();
+            keys.insert(KeyIndex(0), KeyPair::generate(&mut csprng));
+            keys
+        },
+        threshold: SignatureThreshold(1),
+    };
+    let mk_credential = |cred_counter| {
+        create_credential(
+            context,
+            &id_object,
+            &id_use_data,
+            cred_counter,
+            policy.clone(),
+            &acc_data,
+            &SystemAttributeRandomness {},
+            &Left(EXPIRY),
+        )
+    };
+
+    let (cdi, _) = mk_credential(2).expect("Account number 2 should be allowed.");
+    assert_eq!(
+        verify_cdi(&global_ctx, &ip_info, &ars_infos, &cdi, &Left(EXPIRY)),
+        Ok(())
+    );
+    assert!(
+        mk_credential(3).is_err(),
+        "Account numbers beyond the maximum should be rejected."
+    );
+}
+
+/// Test that the fingerprint of an identity provider is stable under
+/// re-serialization, and depends on the verification keys.
+#[test]
+pub fn test_ip_info_fingerprint() {
+    let mut csprng = thread_rng();
+
+    let ip_info = test_create_ip_info(&mut csprng, 3, 10).public_ip_info;
+    let json = serde_json::to_string(&ip_info).expect("JSON serialization should succeed.");
+    let from_json: IpInfo<IpPairing> =
+        serde_json::from_str(&json).expect("JSON deserialization should succeed.");
+    assert_eq!(ip_info.fingerprint(), from_json.fingerprint());
+    let parsed: IpInfo<IpPairing> = from_bytes(&mut std::io::Cursor::new(to_bytes(&ip_info)))
+        .expect("Deserialization should succeed.");
+    assert_eq!(ip_info.fingerprint(), parsed.fingerprint());
+
+    let mut other = test_create_ip_info(&mut csprng, 3, 10).public_ip_info;
+    other.ip_identity = ip_info.ip_identity;
+    other.ip_description = ip_info.ip_description.clone();
+    assert_ne!(
+        ip_info.fingerprint(),
+        other.fingerprint(),
+        "A different verify key should give a different fingerprint."
+    );
+}
+
+/// Check that a value survives a round trip through its JSON representation,
+/// comparing the binary serializations of the original and the parsed value.
+fn json_roundtrip<T: Serial + Deserial + SerdeSerialize + serde::de::DeserializeOwned>(
+    x: &T,
+    fields: &[&str],
+) {
+    let json = serde_json::to_value(x).expect("JSON serialization should succeed.");
+    for field in fields {
+        assert!(
+            json.get(field).is_some(),
+            "JSON field {} should be present.",
+            field
+        );
+    }
+    let parsed: T = serde_json::from_value(json).expect("JSON deserialization should succeed.");
+    assert_eq!(to_bytes(x), to_bytes(&parsed));
+}
+
+/// Test that the id types survive a JSON round trip, and use the expected
+/// field names.
+#[test]
+pub fn test_json_roundtrip() {
+    let mut csprng = thread_rng();
+
+    let num_ars = 3;
+    let IpData {
+        public_ip_info: ip_info,
+        ..
+    } = test_create_ip_info(&mut csprng, num_ars, 10);
+    json_roundtrip(&ip_info, &[
+        "ipIdentity",
+        "ipDescription",
+        "ipVerifyKey",
+        "ipCdiVerifyKey",
+    ]);
+
+    let global_ctx = GlobalContext::generate(String::from("genesis_string"));
+    json_roundtrip(&global_ctx, &[
+        "onChainCommitmentKey",
+        "bulletproofGenerators",
+        "genesisString",
+    ]);
+
+    let (ars_infos, _) =
+        test_create_ars(&global_ctx.on_chain_commitment_key.g, num_ars, &mut csprng);
+    for ar_info in ars_infos.values() {
+        json_roundtrip(ar_info, &["arIdentity", "arDescription", "arPublicKey"]);
+    }
+
+    let id_use_data = test_create_id_use_data(&mut csprng);
+    json_roundtrip(&id_use_data.aci, &["credentialHolderInformation", "prfKey"]);
+    let (_, pio, _) = test_create_pio_v1(&id_use_data, &ip_info, &ars_infos, &global_ctx, num_ars);
+    json_roundtrip(&pio, &["idCredPub", "ipArData", "choiceArData"]);
+}
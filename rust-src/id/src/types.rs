@@ -28,7 +28,7 @@ use ff::Field;
 use hex::{decode, encode};
 use pedersen_scheme::{
     Commitment as PedersenCommitment, CommitmentKey as PedersenKey,
-    Randomness as PedersenRandomness, Value as PedersenValue,
+    Randomness as PedersenRandomness, Value as PedersenValue, VecCommitmentKey,
 };
 use random_oracle::Challenge;
 use serde::{
@@ -425,7 +425,8 @@ pub trait Attribute<F: Field>:
 /// The year is in Gregorian calendar and months are numbered from 1, i.e.,
 /// 1 is January, ..., 12 is December.
 /// Year must be a 4 digit year, i.e., between 1000 and 9999.
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+/// The ordering is chronological since the year is compared first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub struct YearMonth {
     pub year:  u16,
     pub month: u8,
@@ -523,6 +524,36 @@ impl YearMonth {
             month: now.month() as u8,
         }
     }
+
+    /// The following month, or `None` if the year would be out of range.
+    pub fn succ(self) -> Option<Self> {
+        if self.month == 12 {
+            YearMonth::new(self.year.checked_add(1)?, 1)
+        } else {
+            YearMonth::new(self.year, self.month.checked_add(1)?)
+        }
+    }
+
+    /// The preceding month, or `None` if the year would be out of range.
+    pub fn pred(self) -> Option<Self> {
+        if self.month == 1 {
+            YearMonth::new(self.year.checked_sub(1)?, 12)
+        } else {
+            YearMonth::new(self.year, self.month.checked_sub(1)?)
+        }
+    }
+
+    /// Iterate over the months from `self` to `end`, both inclusive. The
+    /// iterator is empty if `end` is before `self`.
+    pub fn iter_until(self, end: YearMonth) -> impl Iterator<Item = YearMonth> {
+        std::iter::successors(Some(self), |ym| ym.succ()).take_while(move |ym| *ym <= end)
+    }
+
+    /// Whether `self` is strictly before `other`.
+    pub fn is_before(&self, other: &YearMonth) -> bool { self < other }
+
+    /// Whether `self` is strictly after `other`.
+    pub fn is_after(&self, other: &YearMonth) -> bool { self > other }
 }
 
 impl TryFrom<u64> for YearMonth {
@@ -1100,6 +1131,22 @@ pub struct IpInfo<P: Pairing> {
     pub ip_cdi_verify_key: ed25519::PublicKey,
 }
 
+impl<P: Pairing> IpInfo<P> {
+    /// A fingerprint identifying the identity provider. This is the SHA256
+    /// hash of a domain separator followed by the binary serialization of the
+    /// identity and the two verification keys, in that order. The free-form
+    /// description is not included, so the fingerprint does not depend on how
+    /// the provider chooses to describe itself, nor on the JSON representation.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(b"concordium_ip_info_fingerprint");
+        self.ip_identity.serial(&mut hasher);
+        self.ip_verify_key.serial(&mut hasher);
+        self.ip_cdi_verify_key.serial(&mut hasher);
+        hasher.finalize().into()
+    }
+}
+
 /// Collection of identity providers.
 #[derive(Debug, SerdeSerialize, SerdeDeserialize)]
 #[serde(bound(serialize = "P: Pairing", deserialize = "P: Pairing"))]
@@ -1908,6 +1955,30 @@ impl<C: Curve> GlobalContext<C> {
     /// A wrapper function to support changes in internal structure of the
     /// context in the future, e.g., lazy generation of generators.
     pub fn bulletproof_generators(&self) -> &Generators<C> { &self.bulletproof_generators }
+
+    /// A commitment key for vectors of at most `n` values, e.g., for committing
+    /// to a whole attribute list at once.
+    ///
+    /// The key is derived from the on-chain commitment key, so it does not
+    /// change the serialization of the global context. The first base and the
+    /// base for the randomness are those of the on-chain commitment key, so
+    /// that a key of size 1 gives the same commitments as the on-chain key. The
+    /// remaining bases are obtained by hashing the on-chain key with a domain
+    /// separator and the index of the base.
+    pub fn vec_commitment_key(&self, n: usize) -> VecCommitmentKey<C> {
+        let key_bytes = to_bytes(&self.on_chain_commitment_key);
+        let mut gs = Vec::with_capacity(n);
+        if n > 0 {
+            gs.push(self.on_chain_commitment_key.g);
+        }
+        for i in 1..n {
+            let mut input = b"concordium_vec_commitment_key".to_vec();
+            input.extend_from_slice(&key_bytes);
+            input.extend_from_slice(&(i as u64).to_be_bytes());
+            gs.push(C::hash_to_group(&input));
+        }
+        VecCommitmentKey::new(gs, self.on_chain_commitment_key.h)
+    }
 }
 
 /// Make a context in which the account holder can produce a pre-identity object
@@ -2421,6 +2492,39 @@ mod tests {
     use super::*;
     use ed25519::Signer;
 
+    #[test]
+    fn test_vec_commitment_key() {
+        use pairing::bls12_381::G1;
+        use rand::thread_rng;
+
+        let mut csprng = thread_rng();
+        let gc = GlobalContext::<G1>::generate(String::from("genesis_string"));
+        let key = gc.vec_commitment_key(16);
+        assert_eq!(key.len(), 16);
+        assert_eq!(
+            key,
+            gc.vec_commitment_key(16),
+            "Derivation should be deterministic."
+        );
+        let distinct: std::collections::BTreeSet<_> = key.gs.iter().map(to_bytes).collect();
+        assert_eq!(distinct.len(), 16, "All bases should be distinct.");
+
+        let values: Vec<_> = (0..16)
+            .map(|_| PedersenValue::<G1>::generate(&mut csprng))
+            .collect();
+        let r = PedersenRandomness::<G1>::generate(&mut csprng);
+        assert!(
+            key.hide_vector(&values, &r).is_some(),
+            "Committing to 16 values should succeed."
+        );
+
+        // A key of size 1 agrees with the on-chain commitment key.
+        assert_eq!(
+            gc.vec_commitment_key(1).hide_vector(&values[..1], &r),
+            Some(gc.on_chain_commitment_key.hide(&values[0], &r))
+        );
+    }
+
     #[test]
     fn test_serde_sig() {
         use rand::thread_rng;
@@ -2465,6 +2569,53 @@ mod tests {
         assert_eq!(ym1, ym1_parsed);
     }
 
+    #[test]
+    fn test_yearmonth_succ_pred() {
+        let dec = YearMonth::new(2020, 12).unwrap();
+        let jan = YearMonth::new(2021, 1).unwrap();
+        assert_eq!(dec.succ(), Some(jan));
+        assert_eq!(jan.pred(), Some(dec));
+        assert!(dec.is_before(&jan));
+        assert!(jan.is_after(&dec));
+        assert!(!dec.is_before(&dec) && !dec.is_after(&dec));
+        assert_eq!(
+            YearMonth::new(2020, 5).unwrap().succ(),
+            YearMonth::new(2020, 6)
+        );
+        assert_eq!(YearMonth::new(9999, 12).unwrap().succ(), None);
+        assert_eq!(YearMonth::new(1000, 1).unwrap().pred(), None);
+        // The fields are public, so values outside the valid range can be
+        // constructed directly. These must not overflow.
+        let max = YearMonth {
+            year:  u16::MAX,
+            month: 12,
+        };
+        assert_eq!(max.succ(), None);
+        let min = YearMonth {
+            year:  0,
+            month: 1,
+        };
+        assert_eq!(min.pred(), None);
+        let no_month = YearMonth {
+            year:  2020,
+            month: 0,
+        };
+        assert_eq!(no_month.pred(), None);
+    }
+
+    #[test]
+    fn test_yearmonth_iter_until() {
+        let start = YearMonth::new(2020, 11).unwrap();
+        let end = YearMonth::new(2021, 11).unwrap();
+        let months = start.iter_until(end).collect::<Vec<_>>();
+        assert_eq!(months.len(), 13);
+        assert_eq!(months.first(), Some(&start));
+        assert_eq!(months.last(), Some(&end));
+        assert!(months.windows(2).all(|w| w[0].succ() == Some(w[1])));
+        assert_eq!(start.iter_until(start).collect::<Vec<_>>(), vec![start]);
+        assert_eq!(end.iter_until(start).count(), 0);
+    }
+
     #[test]
     fn test_aliases() -> anyhow::Result<()> {
         use rand::{thread_rng, Rng};
@@ -2483,4 +2634,48 @@ mod tests {
         anyhow::ensure!(base.get_alias(1 << 24).is_none());
         Ok(())
     }
+
+    #[test]
+    fn test_attribute_list_json() {
+        use crate::constants::{AttributeKind, BaseField};
+        let json = r#"{
+            "validTo": "202412",
+            "createdAt": "202301",
+            "maxAccounts": 200,
+            "chosenAttributes": {
+                "countryOfResidence": "DK",
+                "idDocType": "1",
+                "idDocNo": "1234567890"
+            }
+        }"#;
+        let alist: AttributeList<BaseField, AttributeKind> =
+            serde_json::from_str(json).expect("Attribute list should parse.");
+        assert_eq!(alist.alist.len(), 3);
+        assert_eq!(
+            alist
+                .alist
+                .get(&AttributeTag::from_str("countryOfResidence").unwrap()),
+            Some(&AttributeKind("DK".into()))
+        );
+        let value = serde_json::to_value(&alist).expect("JSON serialization should succeed.");
+        let parsed: AttributeList<BaseField, AttributeKind> =
+            serde_json::from_value(value.clone()).expect("Attribute list should parse.");
+        assert_eq!(to_bytes(&alist), to_bytes(&parsed));
+        assert_eq!(
+            value,
+            serde_json::from_str::<serde_json::Value>(json).unwrap(),
+            "JSON serialization should be stable."
+        );
+
+        // The field element encoding is the length of the value in the first byte,
+        // followed by the value right-aligned in the remaining 31 bytes.
+        let mut expected = [0u8; 32];
+        expected[0] = 2;
+        expected[30..].copy_from_slice(b"DK");
+        assert_eq!(
+            to_bytes(&AttributeKind("DK".into()).to_field_element()),
+            expected.to_vec(),
+            "Attribute encoding should be stable."
+        );
+    }
 }
@@ -0,0 +1,48 @@
+//! Benchmarks for committing to vectors of values
+#[macro_use]
+extern crate criterion;
+
+use criterion::{BenchmarkId, Criterion};
+use curve_arithmetic::*;
+use pairing::bls12_381::G1;
+use pedersen_scheme::{Commitment, CommitmentKey, Randomness, VecCommitmentKey};
+use rand::*;
+use std::time::Duration;
+
+pub fn bench_vector_commitment(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Vector commitment");
+
+    for i in 0..8 {
+        let rng = &mut thread_rng();
+        let n = 2_usize.pow(i);
+        let vec_key = VecCommitmentKey::<G1>::generate(n, rng);
+        let values: Vec<_> = (0..n).map(|_| Value::<G1>::generate(rng)).collect();
+        let randomness = Randomness::<G1>::generate(rng);
+        // Keys for committing to each element separately.
+        let keys: Vec<_> = vec_key
+            .gs
+            .iter()
+            .map(|&g| CommitmentKey::new(g, vec_key.h))
+            .collect();
+        let zero = Randomness::<G1>::zero();
+
+        group.bench_function(BenchmarkId::new("hide_vector", n), |b| {
+            b.iter(|| vec_key.hide_vector(&values, &randomness).unwrap())
+        });
+        group.bench_function(BenchmarkId::new("hide per element", n), |b| {
+            b.iter(|| {
+                let mut cmm = Commitment(vec_key.h.mul_by_scalar(&randomness));
+                for (key, v) in keys.iter().zip(values.iter()) {
+                    cmm = cmm.combine(&key.hide(v, &zero));
+                }
+                cmm
+            })
+        });
+    }
+}
+
+criterion_group!(
+    name = commitment_bench;
+    config = Criterion::default().measurement_time(Duration::from_millis(1000)).sample_size(10);
+    targets = bench_vector_commitment);
+criterion_main!(commitment_bench);
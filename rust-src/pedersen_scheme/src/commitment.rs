@@ -6,7 +6,9 @@ use crypto_common::*;
 use crypto_common_derive::*;
 use curve_arithmetic::*;
 
-use std::ops::Deref;
+use crate::{key::*, randomness::*};
+use std::ops::{Add, Deref};
+use subtle::{Choice, ConstantTimeEq};
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, SerdeBase16Serialize)]
 /// A Commitment is a group element.
@@ -22,6 +24,36 @@ impl<C: Curve> Commitment<C> {
     pub fn combine(&self, other: &Commitment<C>) -> Commitment<C> {
         Commitment(self.0.plus_point(&other.0))
     }
+
+    /// Scale the commitment. If the commitment is to v with randomness r, the
+    /// resulting commitment is a commitment to `s * v` with randomness `s * r`.
+    #[inline]
+    pub fn mul_scalar(&self, s: &C::Scalar) -> Commitment<C> { Commitment(self.0.mul_by_scalar(s)) }
+
+    /// Check that the commitment is a commitment to the given value with the
+    /// given randomness, with respect to the given commitment key. See
+    /// [CommitmentKey::open].
+    pub fn verify_opening(
+        &self,
+        key: &CommitmentKey<C>,
+        value: &Value<C>,
+        randomness: &Randomness<C>,
+    ) -> bool {
+        key.open(value, randomness, self)
+    }
+}
+
+/// Comparison of the serializations of commitments in constant time.
+impl<C: Curve> ConstantTimeEq for Commitment<C> {
+    fn ct_eq(&self, other: &Self) -> Choice { to_bytes(self).ct_eq(&to_bytes(other)) }
+}
+
+/// Addition of commitments, see [Commitment::combine].
+impl<C: Curve> Add for Commitment<C> {
+    type Output = Commitment<C>;
+
+    #[inline]
+    fn add(self, other: Commitment<C>) -> Commitment<C> { self.combine(&other) }
 }
 
 /// This trait allows automatic conversion of &Commitment<C> to &C. In
@@ -66,4 +98,29 @@ mod tests {
     macro_test_commitment_to_byte_conversion!(commitment_to_byte_conversion_bls12_381_g1, G1);
 
     macro_test_commitment_to_byte_conversion!(commitment_to_byte_conversion_bls12_381_g2, G2);
+
+    macro_rules! macro_test_verify_opening {
+        ($function_name:ident, $curve_type:path) => {
+            #[test]
+            pub fn $function_name() {
+                let mut csprng = thread_rng();
+                for _i in 0..20 {
+                    let key = CommitmentKey::<$curve_type>::generate(&mut csprng);
+                    let v = Value::<$curve_type>::generate(&mut csprng);
+                    let (c, r) = key.commit(&v, &mut csprng);
+                    assert!(c.verify_opening(&key, &v, &r));
+                    let v_other = Value::<$curve_type>::generate(&mut csprng);
+                    assert!(!c.verify_opening(&key, &v_other, &r));
+                    let r_other = Randomness::<$curve_type>::generate(&mut csprng);
+                    assert!(!c.verify_opening(&key, &v, &r_other));
+                    let key_other = CommitmentKey::<$curve_type>::generate(&mut csprng);
+                    assert!(!c.verify_opening(&key_other, &v, &r));
+                }
+            }
+        };
+    }
+
+    macro_test_verify_opening!(verify_opening_bls12_381_g1, G1);
+
+    macro_test_verify_opening!(verify_opening_bls12_381_g2, G2);
 }
@@ -7,6 +7,7 @@ use curve_arithmetic::*;
 use crypto_common::*;
 use crypto_common_derive::*;
 use rand::*;
+use subtle::ConstantTimeEq;
 
 /// A commitment key is a pair of group elements that are used as a base to
 /// raise the value and randomness, respectively.
@@ -52,10 +53,11 @@ impl<C: Curve> CommitmentKey<C> {
         self.hide_worker(s.as_ref(), r.as_ref())
     }
 
-    /// Prove that the commitment `self` contains the given value and
-    /// randomness.
+    /// Check that the commitment `c` contains the given value and randomness
+    /// with respect to this key. The recomputed commitment is compared to `c`
+    /// in constant time.
     pub fn open(&self, s: &Value<C>, r: &Randomness<C>, c: &Commitment<C>) -> bool {
-        self.hide(s, r) == *c
+        self.hide(s, r).ct_eq(c).into()
     }
 
     pub fn generate<T>(csprng: &mut T) -> CommitmentKey<C>
@@ -70,6 +72,7 @@ impl<C: Curve> CommitmentKey<C> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use ff::Field;
     use pairing::bls12_381::{G1Affine, G2Affine, G1, G2};
 
     macro_rules! macro_test_key_byte_conversion {
@@ -114,4 +117,33 @@ mod tests {
 
     macro_test_commit_open!(commit_open_bls12_381_g2_affine, G2Affine);
     macro_test_commit_open!(commit_open_bls12_381_g2_projective, G2);
+
+    macro_rules! macro_test_homomorphism {
+        ($function_name:ident, $curve_type:path) => {
+            #[test]
+            pub fn $function_name() {
+                let mut csprng = thread_rng();
+                for _i in 1..100 {
+                    let sk = CommitmentKey::<$curve_type>::generate(&mut csprng);
+                    let a = Value::<$curve_type>::generate(&mut csprng);
+                    let b = Value::<$curve_type>::generate(&mut csprng);
+                    let (c_a, r_a) = sk.commit(&a, &mut csprng);
+                    let (c_b, r_b) = sk.commit(&b, &mut csprng);
+                    let mut sum = *a;
+                    sum.add_assign(&b);
+                    let sum = Value::<$curve_type>::new(sum);
+                    assert!(sk.open(&sum, &(&r_a + &r_b), &(c_a + c_b)));
+
+                    let s = <$curve_type as Curve>::generate_scalar(&mut csprng);
+                    let mut scaled = *a;
+                    scaled.mul_assign(&s);
+                    let scaled = Value::<$curve_type>::new(scaled);
+                    assert!(sk.open(&scaled, &r_a.mul_scalar(&s), &c_a.mul_scalar(&s)));
+                }
+            }
+        };
+    }
+
+    macro_test_homomorphism!(homomorphism_bls12_381_g1, G1);
+    macro_test_homomorphism!(homomorphism_bls12_381_g2, G2);
 }
@@ -4,8 +4,9 @@ mod errors;
 mod key;
 mod randomness;
 mod value;
+mod vec_key;
 
-pub use crate::{commitment::*, key::*, randomness::*, value::*};
+pub use crate::{commitment::*, key::*, randomness::*, value::*, vec_key::*};
 
 #[macro_use]
 extern crate crypto_common_derive;
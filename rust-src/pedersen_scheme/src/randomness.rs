@@ -9,7 +9,7 @@ use curve_arithmetic::*;
 use ff::Field;
 
 use rand::*;
-use std::ops::Deref;
+use std::ops::{Add, Deref};
 
 use std::rc::Rc;
 
@@ -28,6 +28,13 @@ impl<C: Curve> Deref for Randomness<C> {
     fn deref(&self) -> &C::Scalar { &self.randomness }
 }
 
+/// Addition of randomness values, see [Randomness::combine].
+impl<'a, C: Curve> Add for &'a Randomness<C> {
+    type Output = Randomness<C>;
+
+    fn add(self, other: &'a Randomness<C>) -> Randomness<C> { self.combine(other) }
+}
+
 impl<C: Curve> AsRef<C::Scalar> for Randomness<C> {
     fn as_ref(&self) -> &C::Scalar { &self.randomness }
 }
@@ -70,6 +77,23 @@ impl<C: Curve> Randomness<C> {
         T: Rng, {
         Randomness::new(C::generate_non_zero_scalar(csprng))
     }
+
+    /// Add two randomness values. This is the randomness of the combination
+    /// of two commitments, see
+    /// [Commitment::combine](crate::Commitment::combine).
+    pub fn combine(&self, other: &Randomness<C>) -> Randomness<C> {
+        let mut r = **self;
+        r.add_assign(other);
+        Randomness::new(r)
+    }
+
+    /// Multiply the randomness by a scalar. This is the randomness of a scaled
+    /// commitment, see [Commitment::mul_scalar](crate::Commitment::mul_scalar).
+    pub fn mul_scalar(&self, s: &C::Scalar) -> Randomness<C> {
+        let mut r = **self;
+        r.mul_assign(s);
+        Randomness::new(r)
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,94 @@
+//! Commitment key for committing to vectors of values
+
+use crate::{commitment::*, randomness::*};
+
+use curve_arithmetic::*;
+
+use crypto_common::*;
+use crypto_common_derive::*;
+use rand::*;
+
+/// A commitment key for vectors of values. It consists of one base for each
+/// position in the vector, and a single base to raise the randomness to.
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, SerdeBase16Serialize)]
+pub struct VecCommitmentKey<C: Curve> {
+    /// Bases to raise the values to when committing.
+    pub gs: Vec<C>,
+    /// Base to raise the randomness to when committing.
+    pub h:  C,
+}
+
+impl<C: Curve> VecCommitmentKey<C> {
+    pub fn new(gs: Vec<C>, h: C) -> Self { VecCommitmentKey { gs, h } }
+
+    /// The maximum length of a vector that can be committed to with this key.
+    pub fn len(&self) -> usize { self.gs.len() }
+
+    pub fn is_empty(&self) -> bool { self.gs.is_empty() }
+
+    /// Hide the vector of values inside a single commitment using the given
+    /// randomness. The commitment is computed with a single
+    /// multi-exponentiation. Returns `None` if there are more values than
+    /// bases in the key. If there are fewer, only the leading bases are used.
+    pub fn hide_vector(
+        &self,
+        values: &[Value<C>],
+        randomness: &Randomness<C>,
+    ) -> Option<Commitment<C>> {
+        if values.len() > self.gs.len() {
+            return None;
+        }
+        let mut bases = Vec::with_capacity(values.len() + 1);
+        let mut exps = Vec::with_capacity(values.len() + 1);
+        for (g, v) in self.gs.iter().zip(values.iter()) {
+            bases.push(*g);
+            exps.push(**v);
+        }
+        bases.push(self.h);
+        exps.push(**randomness);
+        Some(Commitment(multiexp(&bases, &exps)))
+    }
+
+    /// Generate a key for vectors of length at most `n`.
+    pub fn generate<T>(n: usize, csprng: &mut T) -> VecCommitmentKey<C>
+    where
+        T: Rng, {
+        let gs = (0..n).map(|_| C::generate(csprng)).collect();
+        let h = C::generate(csprng);
+        VecCommitmentKey { gs, h }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pairing::bls12_381::{G1, G2};
+
+    macro_rules! macro_test_hide_vector {
+        ($function_name:ident, $curve_type:path) => {
+            #[test]
+            pub fn $function_name() {
+                let mut csprng = thread_rng();
+                for n in 0..20 {
+                    let key = VecCommitmentKey::<$curve_type>::generate(n, &mut csprng);
+                    let values: Vec<_> = (0..n)
+                        .map(|_| Value::<$curve_type>::generate(&mut csprng))
+                        .collect();
+                    let r = Randomness::<$curve_type>::generate(&mut csprng);
+                    let cmm = key.hide_vector(&values, &r);
+                    // Compute the commitment naively, one element at a time.
+                    let mut naive = key.h.mul_by_scalar(&r);
+                    for (g, v) in key.gs.iter().zip(values.iter()) {
+                        naive = naive.plus_point(&g.mul_by_scalar(v));
+                    }
+                    assert_eq!(cmm, Some(Commitment(naive)));
+                    let too_many = [values, vec![Value::generate(&mut csprng)]].concat();
+                    assert_eq!(key.hide_vector(&too_many, &r), None);
+                }
+            }
+        };
+    }
+
+    macro_test_hide_vector!(hide_vector_bls12_381_g1, G1);
+    macro_test_hide_vector!(hide_vector_bls12_381_g2, G2);
+}
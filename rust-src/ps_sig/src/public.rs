@@ -4,7 +4,7 @@
 
 use rand::*;
 
-use crate::{known_message::*, signature::*};
+use crate::{known_message::*, signature::*, unknown_message::*};
 use crypto_common::*;
 use crypto_common_derive::*;
 use curve_arithmetic::*;
@@ -64,6 +64,29 @@ impl<C: Pairing> PublicKey<C> {
         C::check_pairing_eq(&sig.0, &hx, &sig.1, &self.g_tilda)
     }
 
+    /// Commit to the given messages so that they can be signed with
+    /// [SecretKey::sign_unknown_message] without revealing them to the signer.
+    /// The resulting signature is turned into a signature on the messages with
+    /// [Signature::retrieve] using the same randomness.
+    /// Returns `None` if there are more messages than the key supports.
+    pub fn commit_messages(
+        &self,
+        message: &KnownMessage<C>,
+        randomness: &SigRetrievalRandomness<C>,
+    ) -> Option<UnknownMessage<C>> {
+        let ms = &message.0;
+        if ms.len() > self.ys.len() {
+            return None;
+        }
+        let mut gs = Vec::with_capacity(ms.len() + 1);
+        gs.push(self.g);
+        gs.extend_from_slice(&self.ys[..ms.len()]);
+        let mut exps = Vec::with_capacity(ms.len() + 1);
+        exps.push(**randomness);
+        exps.extend_from_slice(ms);
+        Some(UnknownMessage(multiexp(&gs, &exps)))
+    }
+
     /// Generate a public key  from a `csprng`.
     pub fn arbitrary<T>(n: usize, csprng: &mut T) -> PublicKey<C>
     where
@@ -209,4 +232,28 @@ mod tests {
     }
 
     macro_test_sign_verify_dummy_sig!(sign_verify_dummy_sig_bls12_381, Bls12);
+
+    macro_rules! macro_test_sign_blind_verify {
+        ($function_name:ident, $pairing_type:path) => {
+            #[test]
+            pub fn $function_name() {
+                let mut csprng = thread_rng();
+                let sk = SecretKey::<$pairing_type>::generate(5, &mut csprng);
+                let pk = PublicKey::from(&sk);
+                let message = KnownMessage::<$pairing_type>::generate(3, &mut csprng);
+                let randomness = SigRetrievalRandomness::generate_non_zero(&mut csprng);
+                let commitment = pk
+                    .commit_messages(&message, &randomness)
+                    .expect("The key supports 3 messages.");
+                let blind_sig = sk.sign_unknown_message(&commitment, &mut csprng);
+                assert!(!pk.verify(&blind_sig, &message));
+                let sig = blind_sig.retrieve(&randomness);
+                assert!(pk.verify(&sig, &message));
+                let too_long = KnownMessage::<$pairing_type>::generate(6, &mut csprng);
+                assert!(pk.commit_messages(&too_long, &randomness).is_none());
+            }
+        };
+    }
+
+    macro_test_sign_blind_verify!(sign_blind_verify_bls12_381, Bls12);
 }
@@ -49,6 +49,14 @@ impl<C: Pairing> Signature<C> {
         Signature(h, b.minus_point(&hr))
     }
 
+    /// Re-randomize a signature by raising both components to the same fresh
+    /// non-zero power. The result is a valid signature on the same message, but
+    /// it cannot be linked to the original one.
+    pub fn randomize<R: Rng>(&self, csprng: &mut R) -> Self {
+        let r = C::generate_non_zero_scalar(csprng);
+        Signature(self.0.mul_by_scalar(&r), self.1.mul_by_scalar(&r))
+    }
+
     /// Blind a signature.
     pub fn blind<R: Rng>(&self, csprng: &mut R) -> (BlindedSignature<C>, BlindingRandomness<C>) {
         let r = C::generate_non_zero_scalar(csprng);
@@ -65,6 +73,7 @@ impl<C: Pairing> Signature<C> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{known_message::*, public::*, secret::*};
     use pairing::bls12_381::Bls12;
 
     macro_rules! macro_test_signature_to_byte_conversion {
@@ -83,4 +92,27 @@ mod tests {
     }
 
     macro_test_signature_to_byte_conversion!(signature_to_byte_conversion_bls12_381, Bls12);
+
+    macro_rules! macro_test_randomize {
+        ($function_name:ident, $pairing_type:path) => {
+            #[test]
+            pub fn $function_name() {
+                let mut csprng = thread_rng();
+                let sk = SecretKey::<$pairing_type>::generate(3, &mut csprng);
+                let pk = PublicKey::from(&sk);
+                let message = KnownMessage::<$pairing_type>::generate(3, &mut csprng);
+                let sig = sk
+                    .sign_known_message(&message, &mut csprng)
+                    .expect("Signing should succeed.");
+                let randomized = sig.randomize(&mut csprng);
+                assert_ne!(to_bytes(&sig), to_bytes(&randomized));
+                assert!(pk.verify(&randomized, &message));
+                // Randomizing the components with different scalars is not valid.
+                let tampered = Signature(randomized.0, sig.randomize(&mut csprng).1);
+                assert!(!pk.verify(&tampered, &message));
+            }
+        };
+    }
+
+    macro_test_randomize!(randomize_bls12_381, Bls12);
 }
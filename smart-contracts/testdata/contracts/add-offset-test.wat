@@ -0,0 +1,8 @@
+(module
+
+  ;; This module has a data segment whose offset is computed with i32.add.
+  ;; Only a single constant instruction is allowed in an offset expression.
+
+  (memory 1)
+  (data (i32.add (i32.const 0) (i32.const 1)) "\2a")
+)
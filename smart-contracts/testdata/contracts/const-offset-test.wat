@@ -0,0 +1,8 @@
+(module
+
+  ;; This module has a data segment whose offset is a single i32.const, which
+  ;; is a valid constant expression.
+
+  (memory 1)
+  (data (i32.const 0) "\2a")
+)
@@ -0,0 +1,8 @@
+(module
+
+  ;; This module has a memory of one page, but the data segment starts at the
+  ;; end of that page, which is not allowed.
+
+  (memory 1)
+  (data (i32.const 65536) "\2a")
+)
@@ -0,0 +1,14 @@
+(module
+
+  ;; This module has code after a return inside a block, and after an
+  ;; unreachable at the end of the function. The code is well typed when the
+  ;; stack is treated as polymorphic after an unconditional control transfer,
+  ;; so the module is valid.
+
+  (func $init_test (export "init_test") (param $amount i64) (result i32)
+    (block
+      (return (i32.const 0))
+      (drop (i32.const 1)))
+    (unreachable)
+    (i32.add))
+)
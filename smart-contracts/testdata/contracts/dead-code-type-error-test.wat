@@ -0,0 +1,12 @@
+(module
+
+  ;; This module has code after a return inside a block that applies an i32
+  ;; instruction to an i64. Code after a return must still be well typed, so the
+  ;; module is invalid.
+
+  (func $init_test (export "init_test") (param $amount i64) (result i32)
+    (block
+      (return (i32.const 0))
+      (drop (i32.eqz (i64.const 1))))
+    (i32.const 0))
+)
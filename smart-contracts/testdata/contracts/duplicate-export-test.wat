@@ -0,0 +1,12 @@
+(module
+
+  ;; This module exports the same function twice under the name init_foo.
+  ;; Export names must be unique, so the module is invalid.
+  ;; To compile it with wat2wasm, use the --no-check option.
+
+  (func $init (param i64) (result i32)
+    (i32.const 0))
+
+  (export "init_foo" (func $init))
+  (export "init_foo" (func $init))
+)
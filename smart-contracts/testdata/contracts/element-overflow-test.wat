@@ -0,0 +1,10 @@
+(module
+
+  ;; This module has a table of size 1, but the element segment initializes two
+  ;; entries, which is not allowed.
+
+  (table 1 funcref)
+  (elem (i32.const 0) $f $f)
+
+  (func $f)
+)
@@ -0,0 +1,9 @@
+(module
+
+  ;; This module exports a function whose name contains a NUL character, which
+  ;; is not allowed in import and export names.
+
+  (type (func))
+  (func (type 0))
+  (export "init_a\00b" (func 0))
+)
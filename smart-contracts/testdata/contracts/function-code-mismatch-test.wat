@@ -0,0 +1,10 @@
+(module
+
+  ;; This module declares two functions in the function section, but the code
+  ;; section only contains a single body. The binary was edited by hand since
+  ;; the text format cannot express this.
+
+  (type (func))
+  (func (type 0))
+  (func (type 0))
+)
@@ -0,0 +1,8 @@
+(module
+
+  ;; This module imports a function whose name contains a space, which is not
+  ;; allowed in import and export names.
+
+  (type (func))
+  (import "concordium" "accept now" (func (type 0)))
+)
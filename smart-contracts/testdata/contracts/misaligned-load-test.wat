@@ -0,0 +1,11 @@
+(module
+
+  ;; This module uses an alignment hint that exceeds the natural alignment
+  ;; of the i32.load instruction, and thus is invalid.
+  ;; To compile it with wat2wasm, use the --no-check option.
+
+  (func (result i32)
+    (i32.load align=8 (i32.const 0)))
+
+  (memory 1)
+)
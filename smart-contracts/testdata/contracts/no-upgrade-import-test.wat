@@ -0,0 +1,6 @@
+(module
+
+  ;; This module imports a host function, but not the upgrade host function.
+
+  (import "concordium" "get_slot_time" (func $get_slot_time (result i64)))
+)
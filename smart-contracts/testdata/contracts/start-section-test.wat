@@ -0,0 +1,8 @@
+(module
+
+  ;; This module has a start function, which is not supported.
+
+  (func $start)
+
+  (start $start)
+)
@@ -0,0 +1,7 @@
+(module
+
+  ;; This module imports the upgrade host function, which is only available
+  ;; from protocol version 5.
+
+  (import "concordium" "upgrade" (func $upgrade (param $module_ref i32) (result i64)))
+)
@@ -8,6 +8,37 @@ pub const MAX_CONTRACT_STATE: u32 = 16384; // 16kB
 /// Maximum number of nested function calls.
 pub const MAX_ACTIVATION_FRAMES: u32 = 1024;
 
+/// Maximum size of parameters of actions produced by V0 receive functions, in
+/// bytes.
+pub const MAX_PARAMETER_SIZE: usize = 1024;
+
+/// Limits on execution that can differ between protocol versions. The
+/// [`DEFAULT`](RuntimeLimits::DEFAULT) limits are given by the constants
+/// above.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuntimeLimits {
+    /// Maximum size of a V0 contract state in bytes.
+    pub max_contract_state:    u32,
+    /// Maximum number of nested function calls.
+    pub max_activation_frames: u32,
+    /// Maximum size of parameters of actions produced by V0 receive
+    /// functions, in bytes.
+    pub max_parameter_size:    usize,
+}
+
+impl RuntimeLimits {
+    /// The limits that are in effect in all current protocol versions.
+    pub const DEFAULT: Self = Self {
+        max_contract_state:    MAX_CONTRACT_STATE,
+        max_activation_frames: MAX_ACTIVATION_FRAMES,
+        max_parameter_size:    MAX_PARAMETER_SIZE,
+    };
+}
+
+impl Default for RuntimeLimits {
+    fn default() -> Self { Self::DEFAULT }
+}
+
 /// Maximum size of a log message.
 pub const MAX_LOG_SIZE: u32 = 512;
 
@@ -136,6 +136,17 @@ impl std::fmt::Display for OutOfEnergy {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { "Out of energy".fmt(f) }
 }
 
+#[derive(Debug)]
+/// An error raised by the interpreter when the maximum number of nested
+/// function calls is exceeded. This is distinct from [`OutOfEnergy`].
+pub struct CallDepthExceeded;
+
+impl std::fmt::Display for CallDepthExceeded {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        "Too many nested functions".fmt(f)
+    }
+}
+
 impl InterpreterEnergy {
     pub fn tick_energy(&mut self, amount: u64) -> ExecResult<()> {
         if self.energy >= amount {
@@ -20,17 +20,36 @@
 //!   validate the Wasm module, then inject cost metering instructions, and then
 //!   convert it to an [`Artifact`] and run it using the appropriate `invoke_*`
 //!   function.
+//! - [`execute_init_with_limits`] and [`execute_receive_with_limits`] which
+//!   report the [`ExecutionOutcome`], including traps, together with the energy
+//!   consumed by execution.
+//!
+//! The host functions available to contracts are implemented by [`InitHost`]
+//! and [`ReceiveHost`]. They get all information about the chain through the
+//! [`HasInitContext`], [`HasReceiveContext`], and [`HasChainMetadata`] traits.
+//! Contracts can thus be tested off-chain by running them with a context that
+//! implements these traits with the desired values. The parameter is given
+//! directly to the `invoke_*` functions, and the resulting state is returned.
+//! With the `test-utils` feature the [`test_host::TestHost`] provides such a
+//! context with a settable slot time, parameter, and state.
 
 #[cfg(feature = "enable-ffi")]
 pub(crate) mod ffi;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_host;
+#[cfg(test)]
+mod tests;
 mod types;
 
-use crate::{constants, ExecResult, InterpreterEnergy, OutOfEnergy};
+use crate::{
+    constants::{self, RuntimeLimits},
+    CallDepthExceeded, ExecResult, InterpreterEnergy,
+};
 use anyhow::{anyhow, bail, ensure};
 use concordium_contracts_common::*;
 use concordium_wasm::{
     artifact::{Artifact, RunnableCode},
-    machine::{self, ExecutionOutcome, NoInterrupt},
+    machine::{self, NoInterrupt},
     utils,
 };
 use machine::Value;
@@ -191,14 +210,21 @@ impl State {
 
     pub fn len(&self) -> u32 { self.state.len() as u32 }
 
-    pub(crate) fn write_state(&mut self, offset: u32, bytes: &[u8]) -> ExecResult<u32> {
+    /// Write the bytes to the state at the given offset. The state is extended
+    /// if needed, but not beyond `max_size` bytes.
+    pub(crate) fn write_state(
+        &mut self,
+        offset: u32,
+        bytes: &[u8],
+        max_size: u32,
+    ) -> ExecResult<u32> {
         let length = bytes.len();
         ensure!(offset <= self.len(), "Cannot write past the offset.");
         let offset = offset as usize;
         let end = offset
             .checked_add(length)
             .ok_or_else(|| anyhow!("Writing past the end of memory."))? as usize;
-        let end = std::cmp::min(end, constants::MAX_CONTRACT_STATE as usize) as u32;
+        let end = std::cmp::min(end, max_size as usize) as u32;
         if self.len() < end {
             self.state.resize(end as usize, 0u8);
         }
@@ -215,8 +241,10 @@ impl State {
         Ok(amt as u32)
     }
 
-    pub(crate) fn resize_state(&mut self, new_size: u32) -> u32 {
-        if new_size > constants::MAX_CONTRACT_STATE {
+    /// Resize the state to the given size. Returns 0 if the new size exceeds
+    /// `max_size`, and 1 otherwise.
+    pub(crate) fn resize_state(&mut self, new_size: u32, max_size: u32) -> u32 {
+        if new_size > max_size {
             0
         } else {
             self.state.resize(new_size as usize, 0u8);
@@ -239,6 +267,8 @@ pub struct InitHost<ParamType, Ctx> {
     pub(crate) logs: Logs,
     /// The contract's state.
     pub(crate) state: State,
+    /// Maximum size of the contract's state in bytes.
+    pub(crate) max_state_size: u32,
     /// The parameter to the init method.
     pub(crate) param: ParamType,
     /// The init context for this invocation.
@@ -259,9 +289,10 @@ impl<ParamType, Ctx> InitHost<ParamType, Ctx> {
     ) -> Self {
         Self {
             energy,
-            activation_frames: constants::MAX_ACTIVATION_FRAMES,
+            activation_frames: RuntimeLimits::DEFAULT.max_activation_frames,
             logs: Logs::new(),
             state: State::new(None),
+            max_state_size: RuntimeLimits::DEFAULT.max_contract_state,
             param,
             init_ctx,
             limit_logs_and_return_values,
@@ -284,6 +315,8 @@ pub struct ReceiveHost<ParamType, Ctx> {
     pub(crate) logs: Logs,
     /// The contract's state.
     pub(crate) state: State,
+    /// Maximum size of the contract's state in bytes.
+    pub(crate) max_state_size: u32,
     /// The parameter to the receive method.
     pub(crate) param: ParamType,
     /// Outcomes of the execution, i.e., the actions tree.
@@ -311,9 +344,10 @@ impl<ParamType, Ctx> ReceiveHost<ParamType, Ctx> {
     ) -> Self {
         ReceiveHost {
             energy,
-            activation_frames: constants::MAX_ACTIVATION_FRAMES,
+            activation_frames: RuntimeLimits::DEFAULT.max_activation_frames,
             logs: Logs::new(),
             state,
+            max_state_size: RuntimeLimits::DEFAULT.max_contract_state,
             param,
             outcomes: Default::default(),
             receive_ctx,
@@ -547,6 +581,7 @@ pub(crate) mod host {
         stack: &mut machine::RuntimeStack,
         energy: &mut InterpreterEnergy,
         state: &mut State,
+        max_state_size: u32,
     ) -> machine::RunResult<()> {
         let offset = unsafe { stack.pop_u32() };
         let length = unsafe { stack.pop_u32() };
@@ -555,7 +590,7 @@ pub(crate) mod host {
         energy.tick_energy(constants::copy_to_host_cost(length))?;
         let end = start + length as usize; // this cannot overflow on 64-bit machines.
         ensure!(end <= memory.len(), "Illegal memory access.");
-        let res = state.write_state(offset, &memory[start..end])?;
+        let res = state.write_state(offset, &memory[start..end], max_state_size)?;
         stack.push_value(res);
         Ok(())
     }
@@ -565,6 +600,7 @@ pub(crate) mod host {
         stack: &mut machine::RuntimeStack,
         energy: &mut InterpreterEnergy,
         state: &mut State,
+        max_state_size: u32,
     ) -> machine::RunResult<()> {
         let new_size = stack.pop();
         let new_size = unsafe { new_size.short } as u32;
@@ -576,7 +612,7 @@ pub(crate) mod host {
                 new_size - old_size,
             )))?;
         }
-        stack.push_value(state.resize_state(new_size));
+        stack.push_value(state.resize_state(new_size, max_state_size));
         Ok(())
     }
 
@@ -770,7 +806,7 @@ pub(crate) mod host {
             *activation_frames = fr;
             Ok(())
         } else {
-            bail!("Too many nested functions.")
+            bail!(CallDepthExceeded)
         }
     }
 
@@ -834,12 +870,19 @@ impl<ParamType: AsRef<[u8]>, Ctx: HasInitContext> machine::Host<ProcessedImports
                 CommonFunc::LoadState => {
                     host::load_state(memory, stack, &mut self.energy, &mut self.state)
                 }
-                CommonFunc::WriteState => {
-                    host::write_state(memory, stack, &mut self.energy, &mut self.state)
-                }
-                CommonFunc::ResizeState => {
-                    host::resize_state(stack, &mut self.energy, &mut self.state)
-                }
+                CommonFunc::WriteState => host::write_state(
+                    memory,
+                    stack,
+                    &mut self.energy,
+                    &mut self.state,
+                    self.max_state_size,
+                ),
+                CommonFunc::ResizeState => host::resize_state(
+                    stack,
+                    &mut self.energy,
+                    &mut self.state,
+                    self.max_state_size,
+                ),
                 CommonFunc::StateSize => host::state_size(stack, &mut self.state),
                 CommonFunc::GetSlotTime => host::get_slot_time(stack, self.init_ctx.metadata()),
             }?,
@@ -902,12 +945,19 @@ impl<ParamType: AsRef<[u8]>, Ctx: HasReceiveContext> machine::Host<ProcessedImpo
                 CommonFunc::LoadState => {
                     host::load_state(memory, stack, &mut self.energy, &mut self.state)
                 }
-                CommonFunc::WriteState => {
-                    host::write_state(memory, stack, &mut self.energy, &mut self.state)
-                }
-                CommonFunc::ResizeState => {
-                    host::resize_state(stack, &mut self.energy, &mut self.state)
-                }
+                CommonFunc::WriteState => host::write_state(
+                    memory,
+                    stack,
+                    &mut self.energy,
+                    &mut self.state,
+                    self.max_state_size,
+                ),
+                CommonFunc::ResizeState => host::resize_state(
+                    stack,
+                    &mut self.energy,
+                    &mut self.state,
+                    self.max_state_size,
+                ),
                 CommonFunc::StateSize => host::state_size(stack, &mut self.state),
                 CommonFunc::GetSlotTime => host::get_slot_time(stack, self.receive_ctx.metadata()),
             }?,
@@ -968,62 +1018,133 @@ pub struct InitInvocation<'a> {
     pub energy:    InterpreterEnergy,
 }
 
-/// Invokes an init-function from a given artifact.
+/// Invokes an init-function from a given artifact, using the
+/// [default](RuntimeLimits::DEFAULT) runtime limits.
+#[cfg_attr(not(feature = "fuzz-coverage"), inline)]
 pub fn invoke_init<C: RunnableCode, Ctx: HasInitContext>(
     artifact: &Artifact<ProcessedImports, C>,
     init_ctx: Ctx,
     init_invocation: InitInvocation,
     limit_logs_and_return_values: bool,
 ) -> ExecResult<InitResult> {
+    invoke_init_with_limits(
+        artifact,
+        init_ctx,
+        init_invocation,
+        limit_logs_and_return_values,
+        RuntimeLimits::DEFAULT,
+    )
+}
+
+/// Same as [`invoke_init`], except that execution is subject to the given
+/// runtime limits.
+pub fn invoke_init_with_limits<C: RunnableCode, Ctx: HasInitContext>(
+    artifact: &Artifact<ProcessedImports, C>,
+    init_ctx: Ctx,
+    init_invocation: InitInvocation,
+    limit_logs_and_return_values: bool,
+    limits: RuntimeLimits,
+) -> ExecResult<InitResult> {
+    let energy = init_invocation.energy;
+    match execute_init_with_limits(
+        artifact,
+        init_ctx,
+        init_invocation,
+        limit_logs_and_return_values,
+        limits,
+    ) {
+        ExecutionOutcome::Success {
+            result,
+            energy_used,
+        } => Ok(InitResult::Success {
+            state:            result.state,
+            logs:             result.logs,
+            remaining_energy: energy.subtract(energy_used.energy),
+        }),
+        ExecutionOutcome::Reject {
+            reason,
+            energy_used,
+        } => Ok(InitResult::Reject {
+            reason,
+            remaining_energy: energy.subtract(energy_used.energy),
+        }),
+        ExecutionOutcome::OutOfEnergy {
+            ..
+        } => Ok(InitResult::OutOfEnergy),
+        ExecutionOutcome::Trap {
+            error,
+            ..
+        } => Err(error),
+    }
+}
+
+/// Execute an init-function from a given artifact, subject to the given
+/// runtime limits. In contrast to [`invoke_init_with_limits`] traps are
+/// reported as an outcome, and the outcome records the energy consumed in all
+/// cases.
+pub fn execute_init_with_limits<C: RunnableCode, Ctx: HasInitContext>(
+    artifact: &Artifact<ProcessedImports, C>,
+    init_ctx: Ctx,
+    init_invocation: InitInvocation,
+    limit_logs_and_return_values: bool,
+    limits: RuntimeLimits,
+) -> ExecutionOutcome<InitSuccess> {
+    let energy = init_invocation.energy;
     let mut host = InitHost {
-        energy: init_invocation.energy,
-        activation_frames: constants::MAX_ACTIVATION_FRAMES,
+        energy,
+        activation_frames: limits.max_activation_frames,
         logs: Logs::new(),
         state: State::new(None),
+        max_state_size: limits.max_contract_state,
         param: init_invocation.parameter,
         limit_logs_and_return_values,
         init_ctx,
     };
 
-    let res = match artifact
-        .run(&mut host, init_invocation.init_name, &[Value::I64(init_invocation.amount as i64)])
-    {
-        Ok(ExecutionOutcome::Success {
+    let res = artifact
+        .run(&mut host, init_invocation.init_name, &[Value::I64(init_invocation.amount as i64)]);
+    let energy_used = InterpreterEnergy::from(energy.energy - host.energy.energy);
+    let res = match res {
+        Ok(machine::ExecutionOutcome::Success {
             result,
             ..
         }) => result,
-        Ok(ExecutionOutcome::Interrupted {
+        Ok(machine::ExecutionOutcome::Interrupted {
             reason,
             ..
         }) => match reason {}, // impossible case, InitHost has no interrupts
-        Err(e) => {
-            if e.downcast_ref::<OutOfEnergy>().is_some() {
-                return Ok(InitResult::OutOfEnergy);
-            } else {
-                return Err(e);
-            }
-        }
+        Err(error) => return ExecutionOutcome::from_error(error, energy, energy_used),
     };
-    let remaining_energy = host.energy.energy;
     // process the return value.
     // - 0 indicates success
     // - positive values are a protocol violation, so they lead to a runtime error
     // - negative values lead to a rejection with a specific reject reason.
     if let Some(Value::I32(n)) = res {
         if n == 0 {
-            Ok(InitResult::Success {
-                logs:             host.logs,
-                state:            host.state,
-                remaining_energy: remaining_energy.into(),
-            })
+            ExecutionOutcome::Success {
+                result: InitSuccess {
+                    logs:  host.logs,
+                    state: host.state,
+                },
+                energy_used,
+            }
         } else {
-            Ok(InitResult::Reject {
-                reason:           reason_from_wasm_error_code(n)?,
-                remaining_energy: remaining_energy.into(),
-            })
+            match reason_from_wasm_error_code(n) {
+                Ok(reason) => ExecutionOutcome::Reject {
+                    reason,
+                    energy_used,
+                },
+                Err(error) => ExecutionOutcome::Trap {
+                    error,
+                    energy_used,
+                },
+            }
         }
     } else {
-        bail!("Wasm module should return a value.")
+        ExecutionOutcome::Trap {
+            error: anyhow!("Wasm module should return a value."),
+            energy_used,
+        }
     }
 }
 
@@ -1116,7 +1237,10 @@ pub struct ReceiveInvocation<'a> {
     pub energy:       InterpreterEnergy,
 }
 
-/// Invokes a receive-function from a given artifact
+/// Invokes a receive-function from a given artifact, using the
+/// [default](RuntimeLimits::DEFAULT) runtime limits, except for the given
+/// maximum parameter size.
+#[cfg_attr(not(feature = "fuzz-coverage"), inline)]
 pub fn invoke_receive<C: RunnableCode, Ctx: HasReceiveContext>(
     artifact: &Artifact<ProcessedImports, C>,
     receive_ctx: Ctx,
@@ -1125,38 +1249,105 @@ pub fn invoke_receive<C: RunnableCode, Ctx: HasReceiveContext>(
     max_parameter_size: usize,
     limit_logs_and_return_values: bool,
 ) -> ExecResult<ReceiveResult> {
+    invoke_receive_with_limits(
+        artifact,
+        receive_ctx,
+        receive_invocation,
+        current_state,
+        limit_logs_and_return_values,
+        RuntimeLimits {
+            max_parameter_size,
+            ..RuntimeLimits::DEFAULT
+        },
+    )
+}
+
+/// Same as [`invoke_receive`], except that execution is subject to the given
+/// runtime limits.
+pub fn invoke_receive_with_limits<C: RunnableCode, Ctx: HasReceiveContext>(
+    artifact: &Artifact<ProcessedImports, C>,
+    receive_ctx: Ctx,
+    receive_invocation: ReceiveInvocation,
+    current_state: &[u8],
+    limit_logs_and_return_values: bool,
+    limits: RuntimeLimits,
+) -> ExecResult<ReceiveResult> {
+    let energy = receive_invocation.energy;
+    match execute_receive_with_limits(
+        artifact,
+        receive_ctx,
+        receive_invocation,
+        current_state,
+        limit_logs_and_return_values,
+        limits,
+    ) {
+        ExecutionOutcome::Success {
+            result,
+            energy_used,
+        } => Ok(ReceiveResult::Success {
+            logs:             result.logs,
+            state:            result.state,
+            actions:          result.actions,
+            remaining_energy: energy.subtract(energy_used.energy),
+        }),
+        ExecutionOutcome::Reject {
+            reason,
+            energy_used,
+        } => Ok(ReceiveResult::Reject {
+            reason,
+            remaining_energy: energy.subtract(energy_used.energy),
+        }),
+        ExecutionOutcome::OutOfEnergy {
+            ..
+        } => Ok(ReceiveResult::OutOfEnergy),
+        ExecutionOutcome::Trap {
+            error,
+            ..
+        } => Err(error),
+    }
+}
+
+/// Execute a receive-function from a given artifact, subject to the given
+/// runtime limits. In contrast to [`invoke_receive_with_limits`] traps are
+/// reported as an outcome, and the outcome records the energy consumed in all
+/// cases.
+pub fn execute_receive_with_limits<C: RunnableCode, Ctx: HasReceiveContext>(
+    artifact: &Artifact<ProcessedImports, C>,
+    receive_ctx: Ctx,
+    receive_invocation: ReceiveInvocation,
+    current_state: &[u8],
+    limit_logs_and_return_values: bool,
+    limits: RuntimeLimits,
+) -> ExecutionOutcome<ReceiveSuccess> {
+    let energy = receive_invocation.energy;
     let mut host = ReceiveHost {
-        energy: receive_invocation.energy,
-        activation_frames: constants::MAX_ACTIVATION_FRAMES,
+        energy,
+        activation_frames: limits.max_activation_frames,
         logs: Logs::new(),
         state: State::new(Some(current_state)),
+        max_state_size: limits.max_contract_state,
         param: &receive_invocation.parameter,
-        max_parameter_size,
+        max_parameter_size: limits.max_parameter_size,
         limit_logs_and_return_values,
         receive_ctx,
         outcomes: Outcome::new(),
     };
 
-    let res = match artifact.run(&mut host, receive_invocation.receive_name, &[Value::I64(
+    let res = artifact.run(&mut host, receive_invocation.receive_name, &[Value::I64(
         receive_invocation.amount as i64,
-    )]) {
-        Ok(ExecutionOutcome::Success {
+    )]);
+    let energy_used = InterpreterEnergy::from(energy.energy - host.energy.energy);
+    let res = match res {
+        Ok(machine::ExecutionOutcome::Success {
             result,
             ..
         }) => result,
-        Ok(ExecutionOutcome::Interrupted {
+        Ok(machine::ExecutionOutcome::Interrupted {
             reason,
             ..
         }) => match reason {}, // impossible case, ReceiveHost has no interrupts
-        Err(e) => {
-            if e.downcast_ref::<OutOfEnergy>().is_some() {
-                return Ok(ReceiveResult::OutOfEnergy);
-            } else {
-                return Err(e);
-            }
-        }
+        Err(error) => return ExecutionOutcome::from_error(error, energy, energy_used),
     };
-    let remaining_energy = host.energy.energy;
     if let Some(Value::I32(n)) = res {
         // FIXME: We should filter out to only return the ones reachable from
         // the root.
@@ -1164,25 +1355,39 @@ pub fn invoke_receive<C: RunnableCode, Ctx: HasReceiveContext>(
         if n >= 0 && (n as usize) < actions.len() {
             let n = n as usize;
             actions.truncate(n + 1);
-            Ok(ReceiveResult::Success {
-                logs: host.logs,
-                state: host.state,
-                actions,
-                remaining_energy: remaining_energy.into(),
-            })
+            ExecutionOutcome::Success {
+                result: ReceiveSuccess {
+                    logs: host.logs,
+                    state: host.state,
+                    actions,
+                },
+                energy_used,
+            }
         } else if n >= 0 {
-            bail!("Invalid return.")
+            ExecutionOutcome::Trap {
+                error: anyhow!("Invalid return."),
+                energy_used,
+            }
         } else {
-            Ok(ReceiveResult::Reject {
-                reason:           reason_from_wasm_error_code(n)?,
-                remaining_energy: remaining_energy.into(),
-            })
+            match reason_from_wasm_error_code(n) {
+                Ok(reason) => ExecutionOutcome::Reject {
+                    reason,
+                    energy_used,
+                },
+                Err(error) => ExecutionOutcome::Trap {
+                    error,
+                    energy_used,
+                },
+            }
         }
     } else {
-        bail!(
-            "Invalid return. Expected a value, but receive nothing. This should not happen for \
-             well-formed modules"
-        );
+        ExecutionOutcome::Trap {
+            error: anyhow!(
+                "Invalid return. Expected a value, but receive nothing. This should not happen \
+                 for well-formed modules"
+            ),
+            energy_used,
+        }
     }
 }
 
@@ -0,0 +1,185 @@
+//! Support for running V0 contracts off-chain in unit tests.
+//!
+//! The [`TestHost`] provides the chain context to the host functions of
+//! [`InitHost`] and [`ReceiveHost`], with a slot time, parameter, and contract
+//! state that can be set directly by the test. This module is only available
+//! with the `test-utils` feature.
+use super::*;
+
+/// Chain context and contract state for running V0 contracts in tests.
+///
+/// The slot time, parameter, and state are set directly. Successful
+/// invocations of [`init`](TestHost::init) and
+/// [`receive`](TestHost::receive) replace the state with the one produced by
+/// the contract. Context fields that are `None` lead to a runtime error if the
+/// contract queries them.
+#[derive(Debug, Clone)]
+pub struct TestHost {
+    /// Chain metadata, including the slot time, returned by `get_slot_time`.
+    pub metadata:        ChainMetadata,
+    /// The parameter passed to the next invocation.
+    pub parameter:       Vec<u8>,
+    /// The current state of the contract instance.
+    pub state:           Vec<u8>,
+    /// The limits that execution is subject to.
+    pub limits:          RuntimeLimits,
+    /// The account that initiated the init invocation.
+    pub init_origin:     Option<AccountAddress>,
+    /// The account that initiated the receive invocation.
+    pub invoker:         Option<AccountAddress>,
+    /// The address of the instance being updated.
+    pub self_address:    Option<ContractAddress>,
+    /// The balance of the instance being updated.
+    pub self_balance:    Option<Amount>,
+    /// The immediate sender of the receive invocation.
+    pub sender:          Option<Address>,
+    /// The owner of the instance being updated.
+    pub owner:           Option<AccountAddress>,
+    /// The serialized policies of the sender.
+    pub sender_policies: Option<Vec<u8>>,
+}
+
+impl Default for TestHost {
+    fn default() -> Self {
+        Self {
+            metadata:        ChainMetadata {
+                slot_time: Timestamp::from_timestamp_millis(0),
+            },
+            parameter:       Vec::new(),
+            state:           Vec::new(),
+            limits:          RuntimeLimits::DEFAULT,
+            init_origin:     None,
+            invoker:         None,
+            self_address:    None,
+            self_balance:    None,
+            sender:          None,
+            owner:           None,
+            sender_policies: None,
+        }
+    }
+}
+
+impl TestHost {
+    /// Construct a host with slot time 0, an empty parameter and state, and no
+    /// other chain context.
+    pub fn new() -> Self { Self::default() }
+
+    /// Set the slot time returned by `get_slot_time`.
+    pub fn set_slot_time(&mut self, slot_time: SlotTime) { self.metadata.slot_time = slot_time; }
+
+    /// Set the parameter passed to the next invocation.
+    pub fn set_parameter(&mut self, parameter: impl Into<Vec<u8>>) {
+        self.parameter = parameter.into();
+    }
+
+    /// Set the current state of the contract instance.
+    pub fn set_state(&mut self, state: impl Into<Vec<u8>>) { self.state = state.into(); }
+
+    /// Invoke the given init function with the current parameter. If
+    /// initialization succeeds the state is replaced by the initial state of
+    /// the instance.
+    pub fn init<C: RunnableCode>(
+        &mut self,
+        artifact: &Artifact<ProcessedImports, C>,
+        init_name: &str,
+        amount: u64,
+        energy: InterpreterEnergy,
+    ) -> ExecResult<InitResult> {
+        let res = invoke_init_with_limits(
+            artifact,
+            &*self,
+            InitInvocation {
+                amount,
+                init_name,
+                parameter: Parameter(&self.parameter),
+                energy,
+            },
+            true,
+            self.limits,
+        )?;
+        if let InitResult::Success {
+            state,
+            ..
+        } = &res
+        {
+            self.state = state.state.clone();
+        }
+        Ok(res)
+    }
+
+    /// Invoke the given receive function with the current parameter and
+    /// state. If the invocation succeeds the state is replaced by the new
+    /// state of the instance.
+    pub fn receive<C: RunnableCode>(
+        &mut self,
+        artifact: &Artifact<ProcessedImports, C>,
+        receive_name: &str,
+        amount: u64,
+        energy: InterpreterEnergy,
+    ) -> ExecResult<ReceiveResult> {
+        let res = invoke_receive_with_limits(
+            artifact,
+            &*self,
+            ReceiveInvocation {
+                amount,
+                receive_name,
+                parameter: Parameter(&self.parameter),
+                energy,
+            },
+            &self.state,
+            true,
+            self.limits,
+        )?;
+        if let ReceiveResult::Success {
+            state,
+            ..
+        } = &res
+        {
+            self.state = state.state.clone();
+        }
+        Ok(res)
+    }
+}
+
+/// Return the value if it is set, and fail with a runtime error otherwise.
+fn get_set<'a, A>(value: &'a Option<A>, name: &str) -> ExecResult<&'a A> {
+    value.as_ref().ok_or_else(|| anyhow!("The {} is not set in the test host.", name))
+}
+
+impl HasInitContext for TestHost {
+    type MetadataType = ChainMetadata;
+
+    fn metadata(&self) -> &Self::MetadataType { &self.metadata }
+
+    fn init_origin(&self) -> ExecResult<&AccountAddress> {
+        get_set(&self.init_origin, "init origin")
+    }
+
+    fn sender_policies(&self) -> ExecResult<&[u8]> {
+        get_set(&self.sender_policies, "sender policies").map(Vec::as_slice)
+    }
+}
+
+impl HasReceiveContext for TestHost {
+    type MetadataType = ChainMetadata;
+
+    fn metadata(&self) -> &Self::MetadataType { &self.metadata }
+
+    fn invoker(&self) -> ExecResult<&AccountAddress> { get_set(&self.invoker, "invoker") }
+
+    fn self_address(&self) -> ExecResult<&ContractAddress> {
+        get_set(&self.self_address, "self address")
+    }
+
+    fn self_balance(&self) -> ExecResult<Amount> {
+        get_set(&self.self_balance, "self balance").map(|b| *b)
+    }
+
+    fn sender(&self) -> ExecResult<&Address> { get_set(&self.sender, "sender") }
+
+    fn owner(&self) -> ExecResult<&AccountAddress> { get_set(&self.owner, "owner") }
+
+    fn sender_policies(&self) -> ExecResult<&[u8]> {
+        get_set(&self.sender_policies, "sender policies").map(Vec::as_slice)
+    }
+}
@@ -0,0 +1,256 @@
+//! Tests of V0 contract execution.
+use super::*;
+use concordium_wasm::machine::RuntimeError;
+
+static RESIZE_STATE_BYTES: &[u8] = include_bytes!("../../test-data/code/v0/resize-state-test.wasm");
+static RECURSION_BYTES: &[u8] = include_bytes!("../../test-data/code/v0/recursion-test.wasm");
+static CALL_INDIRECT_BYTES: &[u8] =
+    include_bytes!("../../test-data/code/v0/call-indirect-type-mismatch-test.wasm");
+static WRITE_STATE_BYTES: &[u8] = include_bytes!("../../test-data/code/v0/write-state-test.wasm");
+static LOOP_BYTES: &[u8] = include_bytes!("../../test-data/code/v0/loop-test.wasm");
+
+fn init_ctx() -> InitContext<Vec<u8>> {
+    InitContext {
+        metadata:        ChainMetadata {
+            slot_time: Timestamp::from_timestamp_millis(0),
+        },
+        init_origin:     AccountAddress([0u8; 32]),
+        sender_policies: Vec::new(),
+    }
+}
+
+fn run_init(limits: RuntimeLimits) -> InitResult {
+    let artifact = utils::instantiate(&ConcordiumAllowedImports, RESIZE_STATE_BYTES)
+        .expect("Instantiation should succeed.");
+    invoke_init_with_limits(
+        &artifact,
+        init_ctx(),
+        InitInvocation {
+            amount:    0,
+            init_name: "init_test",
+            parameter: Parameter(&[]),
+            energy:    InterpreterEnergy::from(1_000_000),
+        },
+        false,
+        limits,
+    )
+    .expect("Running the init function should succeed.")
+}
+
+#[test]
+fn test_default_state_limit() {
+    // The contract resizes its state to 100 bytes, which is within the default
+    // limit.
+    match run_init(RuntimeLimits::DEFAULT) {
+        InitResult::Success {
+            state,
+            ..
+        } => assert_eq!(state.len(), 100, "The state should have been resized."),
+        _ => panic!("Initialization should succeed with the default limits."),
+    }
+}
+
+#[test]
+fn test_lowered_state_limit() {
+    // With a state limit below 100 bytes the resize fails, and the contract
+    // rejects.
+    let limits = RuntimeLimits {
+        max_contract_state: 50,
+        ..RuntimeLimits::DEFAULT
+    };
+    match run_init(limits) {
+        InitResult::Reject {
+            reason,
+            ..
+        } => assert_eq!(reason, -1, "The contract should reject with its own reason."),
+        _ => panic!("Initialization should be rejected with a lowered state limit."),
+    }
+}
+
+#[test]
+fn test_call_depth_exceeded() {
+    // The contract recurses without bound. With plenty of energy execution must
+    // stop because the call depth is exceeded, and not because energy runs out.
+    let artifact = utils::instantiate_with_metering(&ConcordiumAllowedImports, RECURSION_BYTES)
+        .expect("Instantiation should succeed.");
+    for max_activation_frames in [10, RuntimeLimits::DEFAULT.max_activation_frames] {
+        let limits = RuntimeLimits {
+            max_activation_frames,
+            ..RuntimeLimits::DEFAULT
+        };
+        let res = invoke_init_with_limits(
+            &artifact,
+            init_ctx(),
+            InitInvocation {
+                amount:    0,
+                init_name: "init_test",
+                parameter: Parameter(&[]),
+                energy:    InterpreterEnergy::from(1_000_000_000),
+            },
+            false,
+            limits,
+        );
+        match res {
+            Err(e) => assert!(
+                e.downcast_ref::<CallDepthExceeded>().is_some(),
+                "Execution should fail because the call depth is exceeded, but failed with: {}",
+                e
+            ),
+            Ok(_) => panic!("Unbounded recursion should fail."),
+        }
+    }
+}
+
+#[test]
+fn test_call_indirect_type_mismatch() {
+    // The table contains a function whose type differs from the one expected by
+    // call_indirect, so execution must trap.
+    let res = invoke_init_from_source(
+        CALL_INDIRECT_BYTES,
+        0,
+        init_ctx(),
+        "init_test",
+        Parameter(&[]),
+        false,
+        InterpreterEnergy::from(1_000_000),
+    );
+    match res {
+        Err(e) => assert!(
+            matches!(
+                e.downcast_ref::<RuntimeError>(),
+                Some(RuntimeError::IndirectCallTypeMismatch)
+            ),
+            "Execution should fail because of the type mismatch, but failed with: {}",
+            e
+        ),
+        Ok(_) => panic!("An indirect call with the wrong type should fail."),
+    }
+}
+
+#[test]
+fn test_export_names() {
+    use concordium_wasm::{
+        types::{FunctionType, ValueType},
+        validate::ValidateImportExport,
+    };
+    let ty = FunctionType {
+        parameters: vec![ValueType::I64],
+        result:     Some(ValueType::I32),
+    };
+    let validators: [&dyn ValidateImportExport; 2] =
+        [&ConcordiumAllowedImports, &StrictConcordiumAllowedImports];
+    for v in validators.iter() {
+        let valid = |name: &str| v.validate_export_function(&name.into(), &ty);
+        assert!(valid("init_counter"), "Init names should be allowed.");
+        assert!(valid("counter.increment"), "Receive names should be allowed.");
+        assert!(!valid("init_counter.increment"), "Init names must not contain a '.'.");
+        assert!(!valid("random_export"), "Other exports should be rejected.");
+    }
+    assert!(
+        ConcordiumAllowedImports.validate_export_function(&"counter.incr.ement".into(), &ty),
+        "Existing modules may export receive names with several '.'s."
+    );
+    assert!(
+        !StrictConcordiumAllowedImports.validate_export_function(&"counter.incr.ement".into(), &ty),
+        "Strict receive names must contain exactly one '.'."
+    );
+}
+
+#[test]
+fn test_run_with_test_host() {
+    // The contract writes the slot time followed by the parameter to its state.
+    let artifact = utils::instantiate(&ConcordiumAllowedImports, WRITE_STATE_BYTES)
+        .expect("Instantiation should succeed.");
+    let mut host = test_host::TestHost::new();
+    host.set_slot_time(Timestamp::from_timestamp_millis(12345));
+    host.set_parameter([1u8, 2, 3]);
+    host.set_state(vec![0u8; 4]);
+    let res = host
+        .init(&artifact, "init_test", 0, InterpreterEnergy::from(1_000_000))
+        .expect("Running the init function should succeed.");
+    assert!(matches!(res, InitResult::Success { .. }), "Initialization should succeed.");
+    let mut expected = 12345u64.to_le_bytes().to_vec();
+    expected.extend_from_slice(&[1, 2, 3]);
+    assert_eq!(host.state, expected, "The state should contain the slot time and parameter.");
+}
+
+fn execute_init_with_metering(source: &[u8], energy: u64) -> ExecutionOutcome<InitSuccess> {
+    let artifact = utils::instantiate_with_metering(&ConcordiumAllowedImports, source)
+        .expect("Instantiation should succeed.");
+    execute_init_with_limits(
+        &artifact,
+        init_ctx(),
+        InitInvocation {
+            amount:    0,
+            init_name: "init_test",
+            parameter: Parameter(&[]),
+            energy:    InterpreterEnergy::from(energy),
+        },
+        false,
+        RuntimeLimits::DEFAULT,
+    )
+}
+
+#[test]
+fn test_out_of_energy() {
+    // The contract loops forever, so with metering it must stop by running out of
+    // energy. This is reported as an outcome, not an error, and the whole budget
+    // is consumed and charged for.
+    for energy in [0, 1, 1000, 100_000] {
+        let res = execute_init_with_metering(LOOP_BYTES, energy);
+        match res {
+            ExecutionOutcome::OutOfEnergy {
+                energy_used,
+            } => assert_eq!(energy_used.energy, energy, "The whole budget should be consumed."),
+            _ => panic!(
+                "Execution with {} energy should run out of energy, but got {:?}.",
+                energy, res
+            ),
+        }
+        let res = invoke_init_with_metering_from_source(
+            LOOP_BYTES,
+            0,
+            init_ctx(),
+            "init_test",
+            Parameter(&[]),
+            false,
+            InterpreterEnergy::from(energy),
+        )
+        .expect("Running out of energy should not be an error.");
+        assert!(
+            matches!(res, InitResult::OutOfEnergy),
+            "Execution with {} energy should run out of energy, but got {:?}.",
+            energy,
+            res
+        );
+    }
+}
+
+#[test]
+fn test_trap_energy_used() {
+    // A trap is reported as an outcome together with the energy consumed until
+    // the trap.
+    let energy = 1_000_000;
+    let res = execute_init_with_metering(CALL_INDIRECT_BYTES, energy);
+    match res {
+        ExecutionOutcome::Trap {
+            error,
+            energy_used,
+        } => {
+            assert!(
+                matches!(
+                    error.downcast_ref::<RuntimeError>(),
+                    Some(RuntimeError::IndirectCallTypeMismatch)
+                ),
+                "Execution should trap because of the type mismatch, but failed with: {}",
+                error
+            );
+            assert!(
+                energy_used.energy > 0 && energy_used.energy < energy,
+                "Only the energy used until the trap should be consumed, but {} was.",
+                energy_used
+            );
+        }
+        _ => panic!("An indirect call with the wrong type should trap, but got {:?}.", res),
+    }
+}
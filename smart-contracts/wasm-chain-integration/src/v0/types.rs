@@ -5,7 +5,7 @@ use concordium_contracts_common::*;
 use concordium_wasm::{
     artifact::TryFromImport,
     output::Output,
-    parse::{Byte, GetParseable, Parseable},
+    parse::{Byte, GetParseable, ParseContext, Parseable},
     types::{FunctionType, Import, Name, ValueType},
     validate,
 };
@@ -180,6 +180,100 @@ pub enum InitResult {
     OutOfEnergy,
 }
 
+/// The result of a successful execution of an init function.
+#[derive(Debug)]
+pub struct InitSuccess {
+    /// The initial state of the instance.
+    pub state: State,
+    /// The logs produced by the `init` function.
+    pub logs:  Logs,
+}
+
+/// The result of a successful execution of a receive function.
+#[derive(Debug)]
+pub struct ReceiveSuccess {
+    /// The final state of the instance.
+    pub state:   State,
+    /// The logs produced by the execution.
+    pub logs:    Logs,
+    /// The actions returned by the entrypoint.
+    pub actions: Vec<Action>,
+}
+
+/// Outcome of executing an init or receive function of a `v0` contract.
+///
+/// Every variant records the interpreter energy consumed by the execution,
+/// since the chain charges for it also when execution fails.
+#[derive(Debug)]
+pub enum ExecutionOutcome<Success> {
+    /// Execution succeeded.
+    Success {
+        result:      Success,
+        energy_used: InterpreterEnergy,
+    },
+    /// The contract rejected the invocation due to its own logic.
+    Reject {
+        /// The error code that the contract signalled.
+        reason:      i32,
+        energy_used: InterpreterEnergy,
+    },
+    /// Execution exceeded the given energy amount. The whole amount is
+    /// consumed.
+    OutOfEnergy {
+        energy_used: InterpreterEnergy,
+    },
+    /// Execution trapped, either due to a runtime error in the contract, or
+    /// because the contract violated the protocol, e.g., by returning an
+    /// invalid value.
+    Trap {
+        error:       anyhow::Error,
+        energy_used: InterpreterEnergy,
+    },
+}
+
+impl<Success> ExecutionOutcome<Success> {
+    /// The interpreter energy consumed by the execution.
+    pub fn energy_used(&self) -> InterpreterEnergy {
+        match self {
+            ExecutionOutcome::Success {
+                energy_used,
+                ..
+            } => *energy_used,
+            ExecutionOutcome::Reject {
+                energy_used,
+                ..
+            } => *energy_used,
+            ExecutionOutcome::OutOfEnergy {
+                energy_used,
+            } => *energy_used,
+            ExecutionOutcome::Trap {
+                energy_used,
+                ..
+            } => *energy_used,
+        }
+    }
+
+    /// Construct the outcome of an execution with the given energy budget that
+    /// failed with the given error. Running out of energy consumes the whole
+    /// budget, and any other error is a trap.
+    pub(crate) fn from_error(
+        error: anyhow::Error,
+        budget: InterpreterEnergy,
+        energy_used: InterpreterEnergy,
+    ) -> Self {
+        if error.downcast_ref::<crate::OutOfEnergy>().is_some() {
+            ExecutionOutcome::OutOfEnergy {
+                energy_used: budget,
+            }
+        } else {
+            ExecutionOutcome::Trap {
+                error,
+                energy_used,
+            }
+        }
+    }
+}
+
 impl InitResult {
     #[cfg(feature = "enable-ffi")]
     pub(crate) fn to_bytes(&self) -> Vec<u8> {
@@ -451,7 +545,7 @@ pub(crate) enum ImportFunc {
     ReceiveOnly(ReceiveOnlyFunc),
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ImportFunc {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ImportFunc {
     fn parse(
         ctx: Ctx,
         cursor: &mut std::io::Cursor<&'a [u8]>,
@@ -533,7 +627,7 @@ pub struct ProcessedImports {
     ty:             FunctionType,
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ProcessedImports {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ProcessedImports {
     fn parse(
         ctx: Ctx,
         cursor: &mut std::io::Cursor<&'a [u8]>,
@@ -604,7 +698,7 @@ impl validate::ValidateImportExport for ConcordiumAllowedImports {
 
     /// Validate that all the exported functions either
     /// - start with `init_` and contain no `.`
-    /// - do contain a `.`
+    /// - contain a `.`
     ///
     /// Names are already ensured to be valid ASCII sequences by parsing, here
     /// we additionally ensure that they contain only alphanumeric and
@@ -627,6 +721,36 @@ impl validate::ValidateImportExport for ConcordiumAllowedImports {
     }
 }
 
+/// A variant of [`ConcordiumAllowedImports`] that additionally requires
+/// receive function names to contain exactly one `.`, separating the contract
+/// and method names.
+///
+/// Modules already deployed on the chain may export names such as
+/// `counter.incr.ement`, which [`ConcordiumAllowedImports`] accepts. This
+/// stricter validator must thus only be used for new deployments in the
+/// protocol versions that enforce the rule, and never when re-validating
+/// existing modules.
+pub struct StrictConcordiumAllowedImports;
+
+impl validate::ValidateImportExport for StrictConcordiumAllowedImports {
+    #[inline(always)]
+    fn validate_import_function(
+        &self,
+        duplicate: bool,
+        mod_name: &Name,
+        item_name: &Name,
+        ty: &FunctionType,
+    ) -> bool {
+        ConcordiumAllowedImports.validate_import_function(duplicate, mod_name, item_name, ty)
+    }
+
+    fn validate_export_function(&self, item_name: &Name, ty: &FunctionType) -> bool {
+        ConcordiumAllowedImports.validate_export_function(item_name, ty)
+            && (item_name.as_ref().starts_with("init_")
+                || item_name.as_ref().matches('.').count() == 1)
+    }
+}
+
 impl TryFromImport for ProcessedImports {
     fn try_from_import(
         ctx: &[FunctionType],
@@ -8,8 +8,8 @@ use concordium_contracts_common::OwnedEntrypointName;
 use concordium_wasm::{
     artifact::TryFromImport,
     output::Output,
-    parse::{Byte, GetParseable, Parseable},
-    types::{FunctionType, Import, Name, ValueType},
+    parse::{Byte, GetParseable, ParseContext, Parseable},
+    types::{FunctionType, Import, Module, Name, ValueType},
     validate,
 };
 use derive_more::{From, Into};
@@ -366,7 +366,7 @@ pub(crate) enum ImportFunc {
     ReceiveOnly(ReceiveOnlyFunc),
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ImportFunc {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ImportFunc {
     fn parse(
         ctx: Ctx,
         cursor: &mut std::io::Cursor<&'a [u8]>,
@@ -476,7 +476,7 @@ pub struct ProcessedImports {
     ty:             FunctionType,
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ProcessedImports {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ProcessedImports {
     fn parse(
         ctx: Ctx,
         cursor: &mut std::io::Cursor<&'a [u8]>,
@@ -497,6 +497,18 @@ impl Output for ProcessedImports {
     }
 }
 
+/// Whether the import is of the `upgrade` host function. This is the only
+/// import whose availability depends on the protocol version.
+pub fn is_upgrade_import(mod_name: &Name, item_name: &Name) -> bool {
+    mod_name.name == "concordium" && item_name.name == "upgrade"
+}
+
+/// Whether the module imports the `upgrade` host function, and thus requires
+/// support for upgrades to be valid.
+pub fn module_uses_upgrade(module: &Module) -> bool {
+    module.import.imports.iter().any(|i| is_upgrade_import(&i.mod_name, &i.item_name))
+}
+
 /// Allowed imports for V1 modules. Whether some imports are allowed
 /// depends on the protocol version that is used to validate the module.
 pub struct ConcordiumAllowedImports {
@@ -517,6 +529,10 @@ impl validate::ValidateImportExport for ConcordiumAllowedImports {
         if duplicate {
             return false;
         };
+        if is_upgrade_import(mod_name, item_name) {
+            // Upgrade is only available from P5.
+            return self.support_upgrade && type_matches!(ty => [I32]; I64);
+        }
         if mod_name.name == "concordium" {
             match item_name.name.as_ref() {
                 "invoke" => type_matches!(ty => [I32, I32, I32]; I64),
@@ -554,8 +570,6 @@ impl validate::ValidateImportExport for ConcordiumAllowedImports {
                 "hash_sha2_256" => type_matches!(ty => [I32, I32, I32]),
                 "hash_sha3_256" => type_matches!(ty => [I32, I32, I32]),
                 "hash_keccak_256" => type_matches!(ty => [I32, I32, I32]),
-                // Upgrade is only available from P5.
-                "upgrade" => self.support_upgrade && type_matches!(ty => [I32]; I64),
                 _ => false,
             }
         } else {
@@ -7,7 +7,9 @@
 
 use concordium_wasm::{
     artifact::{Artifact, CompiledFunction},
+    parse::parse_skeleton,
     utils::instantiate,
+    validate::{validate_module, validate_module_with, ValidationConfig},
 };
 
 use crate::v0::ProcessedImports;
@@ -40,3 +42,223 @@ fn init_global_with_ref_test() {
         instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
     assert!(res.is_err(), "Globals cannot be initialized with references to other globals.");
 }
+
+#[test]
+fn misaligned_load_test() {
+    // This module uses an alignment exponent larger than the natural alignment of
+    // i32.load.
+    let contract = std::fs::read("../testdata/contracts/misaligned-load-test.wasm").unwrap();
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
+    match res {
+        Ok(_) => panic!("Alignment larger than the natural alignment is not allowed."),
+        Err(e) => assert!(
+            e.to_string().contains("i32.load"),
+            "The error should mention the instruction, but was: {}",
+            e
+        ),
+    }
+}
+
+#[test]
+fn duplicate_export_test() {
+    // This module exports two functions with the name init_foo.
+    let contract = std::fs::read("../testdata/contracts/duplicate-export-test.wasm").unwrap();
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
+    match res {
+        Ok(_) => panic!("Duplicate export names are not allowed."),
+        Err(e) => assert!(
+            e.to_string().contains("init_foo"),
+            "The error should mention the duplicate name, but was: {}",
+            e
+        ),
+    }
+}
+
+#[test]
+fn start_section_test() {
+    // This module has a well-formed start section.
+    let contract = std::fs::read("../testdata/contracts/start-section-test.wasm").unwrap();
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
+    match res {
+        Ok(_) => panic!("Start sections are not allowed."),
+        Err(e) => assert!(
+            e.to_string().contains("Start sections are not supported"),
+            "The error should mention the start section, but was: {}",
+            e
+        ),
+    }
+}
+
+#[test]
+fn upgrade_import_test() {
+    // This module imports the upgrade host function.
+    let contract = std::fs::read("../testdata/contracts/upgrade-import-test.wasm").unwrap();
+    let skeleton = parse_skeleton(&contract).expect("The module should parse.");
+    let module = validate_module(
+        &crate::v1::ConcordiumAllowedImports {
+            support_upgrade: true,
+        },
+        &skeleton,
+    )
+    .expect("Importing upgrade is allowed when upgrades are supported.");
+    assert!(crate::v1::module_uses_upgrade(&module), "The module uses upgrade.");
+    let res = validate_module(
+        &crate::v1::ConcordiumAllowedImports {
+            support_upgrade: false,
+        },
+        &skeleton,
+    );
+    assert!(res.is_err(), "Importing upgrade is not allowed when upgrades are not supported.");
+}
+
+#[test]
+fn no_upgrade_import_test() {
+    // This module imports a host function other than upgrade.
+    let contract = std::fs::read("../testdata/contracts/no-upgrade-import-test.wasm").unwrap();
+    let skeleton = parse_skeleton(&contract).expect("The module should parse.");
+    let module = validate_module(
+        &crate::v1::ConcordiumAllowedImports {
+            support_upgrade: false,
+        },
+        &skeleton,
+    )
+    .expect("The module should be valid without upgrade support.");
+    assert!(!crate::v1::module_uses_upgrade(&module), "The module does not use upgrade.");
+}
+
+#[test]
+fn element_overflow_test() {
+    // This module initializes more table entries than the size of the table.
+    let contract = std::fs::read("../testdata/contracts/element-overflow-test.wasm").unwrap();
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
+    match res {
+        Ok(_) => panic!("Element segments must fit in the table."),
+        Err(e) => assert!(
+            e.to_string().contains("exceeds table size"),
+            "The error should mention the table size, but was: {}",
+            e
+        ),
+    }
+}
+
+#[test]
+fn data_overflow_test() {
+    // This module has a data segment that starts past the initial memory.
+    let contract = std::fs::read("../testdata/contracts/data-overflow-test.wasm").unwrap();
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
+    match res {
+        Ok(_) => panic!("Data segments must fit in the initial memory."),
+        Err(e) => assert!(
+            e.to_string().contains("exceeds initial memory size"),
+            "The error should mention the memory size, but was: {}",
+            e
+        ),
+    }
+}
+
+#[test]
+fn const_offset_test() {
+    // This module uses a single i32.const as a data segment offset.
+    let contract = std::fs::read("../testdata/contracts/const-offset-test.wasm").unwrap();
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
+    assert!(res.is_ok(), "Constant offsets are allowed in data sections: {:?}", res);
+}
+
+#[test]
+fn add_offset_test() {
+    // This module computes a data segment offset with i32.add.
+    let contract = std::fs::read("../testdata/contracts/add-offset-test.wasm").unwrap();
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
+    assert!(res.is_err(), "Only constant expressions are allowed as data segment offsets.");
+}
+
+#[test]
+fn function_code_mismatch_test() {
+    // This module declares two functions but only has one code body.
+    let contract = std::fs::read("../testdata/contracts/function-code-mismatch-test.wasm").unwrap();
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
+    match res {
+        Ok(_) => panic!("The function and code sections must have the same length."),
+        Err(e) => assert!(
+            e.to_string().contains("(2 declared, 1 bodies)"),
+            "The error should mention both counts, but was: {}",
+            e
+        ),
+    }
+}
+
+/// Validate the module with strict import and export names, and compile it.
+fn instantiate_with_strict_names(
+    contract: &[u8],
+) -> anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> {
+    let config = ValidationConfig {
+        strict_names: true,
+        ..ValidationConfig::default()
+    };
+    let skeleton = parse_skeleton(contract)?;
+    validate_module_with(&crate::v0::ConcordiumAllowedImports, &skeleton, &config)?.compile()
+}
+
+/// Check that the module is rejected because of a name with disallowed
+/// characters, but only if strict names are required.
+fn check_disallowed_name(contract: &[u8]) {
+    match instantiate_with_strict_names(contract) {
+        Ok(_) => panic!("Names must only contain printable ASCII characters other than space."),
+        Err(e) => assert!(
+            e.to_string().contains("contains disallowed characters"),
+            "The error should mention the disallowed characters, but was: {}",
+            e
+        ),
+    }
+    // By default the module may still be rejected for other reasons, but not
+    // because of the name.
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, contract);
+    if let Err(e) = res {
+        assert!(
+            !e.to_string().contains("contains disallowed characters"),
+            "Names should only be checked if strict names are required, but got: {}",
+            e
+        )
+    }
+}
+
+#[test]
+fn export_name_nul_test() {
+    // This module has an export whose name contains a NUL character.
+    let contract = std::fs::read("../testdata/contracts/export-name-nul-test.wasm").unwrap();
+    check_disallowed_name(&contract);
+}
+
+#[test]
+fn import_name_space_test() {
+    // This module has an import whose name contains a space.
+    let contract = std::fs::read("../testdata/contracts/import-name-space-test.wasm").unwrap();
+    check_disallowed_name(&contract);
+}
+
+#[test]
+fn dead_code_test() {
+    // This module has well-typed code after return and unreachable.
+    let contract = std::fs::read("../testdata/contracts/dead-code-test.wasm").unwrap();
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
+    assert!(res.is_ok(), "Well-typed code after an unconditional jump is allowed: {:?}", res);
+}
+
+#[test]
+fn dead_code_type_error_test() {
+    // This module has ill-typed code after return.
+    let contract = std::fs::read("../testdata/contracts/dead-code-type-error-test.wasm").unwrap();
+    let res: anyhow::Result<Artifact<ProcessedImports, CompiledFunction>> =
+        instantiate(&crate::v0::ConcordiumAllowedImports, &contract);
+    assert!(res.is_err(), "Code after an unconditional jump must still be well typed.");
+}
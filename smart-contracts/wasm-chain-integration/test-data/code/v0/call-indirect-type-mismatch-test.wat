@@ -0,0 +1,18 @@
+(module
+
+  ;; A V0 contract whose init function uses call_indirect with a type that is
+  ;; different from the type of the function in the table.
+
+  (type $init (func (param i64) (result i32)))
+  (type $void (func))
+
+  (table 1 funcref)
+  (elem (i32.const 0) $not_void)
+
+  (func $init_test (export "init_test") (type $init) (param $amount i64) (result i32)
+    (call_indirect (type $void) (i32.const 0))
+    (i32.const 0))
+
+  (func $not_void (type $init) (param i64) (result i32)
+    (i32.const 0))
+)
@@ -0,0 +1,10 @@
+(module
+
+  ;; A V0 contract whose init function loops forever, so it can only stop by
+  ;; running out of energy.
+
+  (func $init_test (export "init_test") (param $amount i64) (result i32)
+    (loop $l
+      (br $l))
+    (unreachable))
+)
@@ -0,0 +1,12 @@
+(module
+
+  ;; A V0 contract whose init function calls a function that recurses without
+  ;; bound, so execution exceeds the maximum number of nested calls.
+
+  (func $init_test (export "init_test") (param $amount i64) (result i32)
+    (call $recurse)
+    (i32.const 0))
+
+  (func $recurse
+    (call $recurse))
+)
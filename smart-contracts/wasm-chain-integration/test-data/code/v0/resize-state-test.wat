@@ -0,0 +1,12 @@
+(module
+
+  ;; A V0 contract whose init function resizes the state to 100 bytes, and
+  ;; rejects if that exceeds the maximum state size.
+
+  (import "concordium" "resize_state" (func $resize_state (param $new_size i32) (result i32)))
+
+  (func $init_test (export "init_test") (param $amount i64) (result i32)
+    (if (result i32) (call $resize_state (i32.const 100))
+      (then (i32.const 0))
+      (else (i32.const -1))))
+)
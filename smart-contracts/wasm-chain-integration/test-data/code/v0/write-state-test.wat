@@ -0,0 +1,23 @@
+(module
+
+  ;; A V0 contract whose init function writes the slot time, followed by the
+  ;; parameter, to the contract state.
+
+  (type (func (result i64)))
+  (type (func (result i32)))
+  (type (func (param i32 i32 i32) (result i32)))
+  (type (func (param i64) (result i32)))
+
+  (import "concordium" "get_slot_time" (func $get_slot_time (type 0)))
+  (import "concordium" "get_parameter_size" (func $get_parameter_size (type 1)))
+  (import "concordium" "get_parameter_section" (func $get_parameter_section (type 2)))
+  (import "concordium" "write_state" (func $write_state (type 2)))
+
+  (memory 1)
+
+  (func $init_test (export "init_test") (type 3) (param $amount i64) (result i32)
+    (i64.store (i32.const 0) (call $get_slot_time))
+    (drop (call $get_parameter_section (i32.const 8) (call $get_parameter_size) (i32.const 0)))
+    (drop (call $write_state (i32.const 0) (i32.add (i32.const 8) (call $get_parameter_size)) (i32.const 0)))
+    (i32.const 0))
+)
@@ -102,7 +102,13 @@ fn validate(source: &[u8]) -> anyhow::Result<Module> {
     }
 
     let skel = concordium_wasm::parse::parse_skeleton(source)?;
-    concordium_wasm::validate::validate_module(&AllowAll, &skel)
+    // The test suite includes the sign-extension instructions, which are
+    // supported by the interpreter but disabled by default.
+    let config = concordium_wasm::validate::ValidationConfig {
+        sign_extension: true,
+        ..Default::default()
+    };
+    concordium_wasm::validate::validate_module_with(&AllowAll, &skel, &config)
 }
 
 macro_rules! fail_test {
@@ -127,7 +133,7 @@ pub const DISALLOWED_INSTRUCTIONS: &[u8] = &[
     0x9D, 0x9E, 0x9F, 0xA0, 0xA1, 0xA2, 0xA3, 0xA4, 0xA5, 0xA6, 0xA7, 0xA8, 0xA9, 0xAA, 0xAB, 0xAC,
     0xAD, 0xAE, 0xAF, 0xB0, 0xB1, 0xB2, 0xB3, 0xB4, 0xB5, 0xB6, 0xB7, 0xB8, 0xB9, 0xBA, 0xBB, 0xBC,
     0xBD, 0xBE, 0xBF, 0xFC, // saturating truncation
-    0x2A, 0x2B, 0xC0, 0xC2, 0xC3,
+    0x2A, 0x2B,
 ];
 
 fn mk_values(exprs: &[Expression<'_>]) -> anyhow::Result<Vec<Value>> {
@@ -322,6 +328,22 @@ fn main() -> anyhow::Result<()> {
                                                     ParseError::NameTooLong => {}
                                                     ParseError::FuncNameTooLong => {}
                                                     ParseError::StartFunctionsNotSupported => {}
+                                                    ParseError::FloatingPointUnsupported {
+                                                        opcode,
+                                                    } => ensure!(
+                                                        DISALLOWED_INSTRUCTIONS
+                                                            .iter()
+                                                            .any(|x| x == opcode),
+                                                        "{}",
+                                                        e
+                                                    ),
+                                                    // Malformed modules are not valid Wasm, so
+                                                    // these indicate a bug in the parser.
+                                                    _ => bail!(
+                                                        "Module {:?} not valid due to {}.",
+                                                        m.id,
+                                                        e
+                                                    ),
                                                 }
                                             } else if let Some(e) =
                                                 e.downcast_ref::<ValidationError>()
@@ -329,6 +351,15 @@ fn main() -> anyhow::Result<()> {
                                                 match e {
                                                     ValidationError::TooManyLocals {
                                                         ..
+                                                    }
+                                                    | ValidationError::TooManyFunctions {
+                                                        ..
+                                                    }
+                                                    | ValidationError::FunctionBodyTooLarge {
+                                                        ..
+                                                    }
+                                                    | ValidationError::TooManyInstructions {
+                                                        ..
                                                     } => {}
                                                 }
                                             } else {
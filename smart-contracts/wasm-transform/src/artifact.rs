@@ -400,23 +400,28 @@ impl<'a> RunnableCode for CompiledFunctionBytes<'a> {
 #[derive(Debug, Clone)]
 pub struct Artifact<ImportFunc, CompiledCode> {
     /// Imports by (module name, item name).
-    pub imports: Vec<ImportFunc>,
+    pub imports:             Vec<ImportFunc>,
     /// Types of the module. These are needed for dynamic dispatch, i.e.,
     /// call-indirect.
-    pub ty:      Vec<FunctionType>,
+    pub ty:                  Vec<FunctionType>,
     /// A fully instantiated table.
-    pub table:   InstantiatedTable,
+    pub table:               InstantiatedTable,
     /// The memory of the artifact.
-    pub memory:  Option<ArtifactMemory>,
+    pub memory:              Option<ArtifactMemory>,
+    /// Memories with index 1 and above. This is only non-empty for modules
+    /// compiled with the `multi-memory` feature enabled. These memories can
+    /// only be accessed by load and store instructions, and are not available
+    /// to host functions.
+    pub additional_memories: Vec<ArtifactMemory>,
     /// Globals initialized with initial values.
-    pub global:  InstantiatedGlobals,
+    pub global:              InstantiatedGlobals,
     /// The exported functions.
     /// Validation should ensure that an exported function is a defined one,
     /// and not one of the imported ones.
     /// Thus the index refers to the index in the code section.
-    pub export:  BTreeMap<Name, FuncIndex>,
+    pub export:              BTreeMap<Name, FuncIndex>,
     /// The list of functions in the module.
-    pub code:    Vec<CompiledCode>,
+    pub code:                Vec<CompiledCode>,
 }
 
 /// Ar artifact which does not own the code to run. The code is only a reference
@@ -434,6 +439,7 @@ impl<'a, ImportFunc> From<BorrowedArtifact<'a, ImportFunc>> for OwnedArtifact<Im
             ty,
             table,
             memory,
+            additional_memories,
             global,
             export,
             code,
@@ -443,6 +449,7 @@ impl<'a, ImportFunc> From<BorrowedArtifact<'a, ImportFunc>> for OwnedArtifact<Im
             ty,
             table,
             memory,
+            additional_memories,
             global,
             export,
             code: code.into_iter().map(CompiledFunction::from).collect::<Vec<_>>(),
@@ -576,6 +583,17 @@ pub enum InternalOpcode {
     I32WrapI64,
     I64ExtendI32S,
     I64ExtendI32U,
+
+    I32Extend8S,
+    I32Extend16S,
+    I64Extend8S,
+    I64Extend16S,
+    I64Extend32S,
+
+    /// Prefix of a load or store instruction that accesses a memory other than
+    /// memory 0. It is followed by the memory index, and then the instruction
+    /// with its offset as usual.
+    IndexedMemory,
 }
 
 /// Result of compilation. Either Ok(_) or an error indicating the reason.
@@ -598,6 +616,19 @@ impl Instructions {
 
     fn push_i64(&mut self, x: i64) { self.bytes.extend_from_slice(&x.to_le_bytes()); }
 
+    /// Push a load or store instruction followed by its offset. Accesses to
+    /// memories other than memory 0 are prefixed by
+    /// [`IndexedMemory`](InternalOpcode::IndexedMemory) and the memory index,
+    /// so that code that only uses memory 0 is compiled as before.
+    fn push_memory_access(&mut self, opcode: InternalOpcode, memarg: &MemArg) {
+        if memarg.memory != 0 {
+            self.push(InternalOpcode::IndexedMemory);
+            self.push_u32(memarg.memory);
+        }
+        self.push(opcode);
+        self.push_u32(memarg.offset);
+    }
+
     fn current_offset(&self) -> usize { self.bytes.len() }
 
     fn back_patch(&mut self, back_loc: usize, to_write: u32) -> CompileResult<()> {
@@ -878,82 +909,25 @@ impl Handler<&OpCode> for BackPatch {
                 // the as u16 is safe because idx < MAX_NUM_GLOBALS <= 2^16
                 self.out.push_u16(*idx as u16);
             }
-            OpCode::I32Load(memarg) => {
-                self.out.push(I32Load);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Load(memarg) => {
-                self.out.push(I64Load);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I32Load8S(memarg) => {
-                self.out.push(I32Load8S);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I32Load8U(memarg) => {
-                self.out.push(I32Load8U);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I32Load16S(memarg) => {
-                self.out.push(I32Load16S);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I32Load16U(memarg) => {
-                self.out.push(I32Load16U);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Load8S(memarg) => {
-                self.out.push(I64Load8S);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Load8U(memarg) => {
-                self.out.push(I64Load8U);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Load16S(memarg) => {
-                self.out.push(I64Load16S);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Load16U(memarg) => {
-                self.out.push(I64Load16U);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Load32S(memarg) => {
-                self.out.push(I64Load32S);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Load32U(memarg) => {
-                self.out.push(I64Load32U);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I32Store(memarg) => {
-                self.out.push(I32Store);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Store(memarg) => {
-                self.out.push(I64Store);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I32Store8(memarg) => {
-                self.out.push(I32Store8);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I32Store16(memarg) => {
-                self.out.push(I32Store16);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Store8(memarg) => {
-                self.out.push(I64Store8);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Store16(memarg) => {
-                self.out.push(I64Store16);
-                self.out.push_u32(memarg.offset);
-            }
-            OpCode::I64Store32(memarg) => {
-                self.out.push(I64Store32);
-                self.out.push_u32(memarg.offset);
-            }
+            OpCode::I32Load(memarg) => self.out.push_memory_access(I32Load, memarg),
+            OpCode::I64Load(memarg) => self.out.push_memory_access(I64Load, memarg),
+            OpCode::I32Load8S(memarg) => self.out.push_memory_access(I32Load8S, memarg),
+            OpCode::I32Load8U(memarg) => self.out.push_memory_access(I32Load8U, memarg),
+            OpCode::I32Load16S(memarg) => self.out.push_memory_access(I32Load16S, memarg),
+            OpCode::I32Load16U(memarg) => self.out.push_memory_access(I32Load16U, memarg),
+            OpCode::I64Load8S(memarg) => self.out.push_memory_access(I64Load8S, memarg),
+            OpCode::I64Load8U(memarg) => self.out.push_memory_access(I64Load8U, memarg),
+            OpCode::I64Load16S(memarg) => self.out.push_memory_access(I64Load16S, memarg),
+            OpCode::I64Load16U(memarg) => self.out.push_memory_access(I64Load16U, memarg),
+            OpCode::I64Load32S(memarg) => self.out.push_memory_access(I64Load32S, memarg),
+            OpCode::I64Load32U(memarg) => self.out.push_memory_access(I64Load32U, memarg),
+            OpCode::I32Store(memarg) => self.out.push_memory_access(I32Store, memarg),
+            OpCode::I64Store(memarg) => self.out.push_memory_access(I64Store, memarg),
+            OpCode::I32Store8(memarg) => self.out.push_memory_access(I32Store8, memarg),
+            OpCode::I32Store16(memarg) => self.out.push_memory_access(I32Store16, memarg),
+            OpCode::I64Store8(memarg) => self.out.push_memory_access(I64Store8, memarg),
+            OpCode::I64Store16(memarg) => self.out.push_memory_access(I64Store16, memarg),
+            OpCode::I64Store32(memarg) => self.out.push_memory_access(I64Store32, memarg),
             OpCode::MemorySize => self.out.push(MemorySize),
             OpCode::MemoryGrow => self.out.push(MemoryGrow),
             OpCode::I32Const(c) => {
@@ -1147,6 +1121,21 @@ impl Handler<&OpCode> for BackPatch {
             OpCode::I64ExtendI32U => {
                 self.out.push(I64ExtendI32U);
             }
+            OpCode::I32Extend8S => {
+                self.out.push(I32Extend8S);
+            }
+            OpCode::I32Extend16S => {
+                self.out.push(I32Extend16S);
+            }
+            OpCode::I64Extend8S => {
+                self.out.push(I64Extend8S);
+            }
+            OpCode::I64Extend16S => {
+                self.out.push(I64Extend16S);
+            }
+            OpCode::I64Extend32S => {
+                self.out.push(I64Extend32S);
+            }
         }
         Ok(())
     }
@@ -1189,6 +1178,10 @@ impl<'a> HasValidationContext for ModuleContext<'a> {
 
     fn memory_exists(&self) -> bool { self.module.memory.memory_type.is_some() }
 
+    fn memory_index_exists(&self, index: MemIndex) -> bool {
+        self.module.memory.get(index).is_some()
+    }
+
     fn table_exists(&self) -> bool { self.module.table.table_type.is_some() }
 
     fn get_func(&self, idx: FuncIndex) -> CompileResult<&std::rc::Rc<FunctionType>> {
@@ -1300,26 +1293,32 @@ impl Module {
                 }
             }
         };
-        let memory = {
-            if let Some(mt) = self.memory.memory_type {
-                Some(ArtifactMemory {
-                    init_size: mt.limits.min,
-                    max_size:  mt
-                        .limits
-                        .max
-                        .map(|x| std::cmp::min(x, MAX_NUM_PAGES))
-                        .unwrap_or(MAX_NUM_PAGES),
-                    init:      self
-                        .data
-                        .sections
-                        .into_iter()
-                        .map(ArtifactData::from)
-                        .collect::<Vec<_>>(),
-                })
-            } else {
-                None
-            }
-        };
+        let mut memories = self
+            .memory
+            .memory_type
+            .iter()
+            .chain(self.memory.additional_memory_types.iter())
+            .map(|mt| ArtifactMemory {
+                init_size: mt.limits.min,
+                max_size:  mt
+                    .limits
+                    .max
+                    .map(|x| std::cmp::min(x, MAX_NUM_PAGES))
+                    .unwrap_or(MAX_NUM_PAGES),
+                init:      Vec::new(),
+            })
+            .collect::<Vec<_>>();
+        for data in self.data.sections {
+            // validation has already ensured that the memory exists.
+            memories
+                .get_mut(data.memory as usize)
+                .ok_or_else(|| anyhow!("Data segment for a non-existent memory."))?
+                .init
+                .push(ArtifactData::from(data));
+        }
+        let mut memories = memories.into_iter();
+        let memory = memories.next();
+        let additional_memories = memories.collect();
         let global = InstantiatedGlobals {
             inits: self.global.globals.iter().map(|x| x.init).collect::<Vec<_>>(),
         };
@@ -1349,6 +1348,7 @@ impl Module {
             ty,
             table,
             memory,
+            additional_memories,
             global,
             export,
             code: code_out,
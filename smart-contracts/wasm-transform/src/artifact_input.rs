@@ -8,10 +8,10 @@ use crate::{
     parse::*,
     types::{BlockType, FuncIndex, FunctionType, GlobalInit, Name, TypeIndex, ValueType},
 };
-use anyhow::bail;
+use anyhow::{bail, ensure};
 use std::{collections::BTreeMap, io::Cursor};
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ArtifactLocal {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ArtifactLocal {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let multiplicity = cursor.next(ctx)?;
         let ty = cursor.next(ctx)?;
@@ -22,7 +22,7 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ArtifactLocal {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ArtifactNamedImport {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ArtifactNamedImport {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let mod_name = cursor.next(ctx)?;
         let item_name = cursor.next(ctx)?;
@@ -35,28 +35,27 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ArtifactNamedImport {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for InstantiatedGlobals {
+/// The initial value of a global in an artifact, tagged with its type.
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for GlobalInit {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
-        let len = u32::parse(ctx, cursor)?;
-        let mut inits = Vec::with_capacity(len as usize);
-        for _ in 0..len {
-            match Byte::parse(ctx, cursor)? {
-                0 => {
-                    inits.push(GlobalInit::I32(cursor.next(ctx)?));
-                }
-                1 => {
-                    inits.push(GlobalInit::I64(cursor.next(ctx)?));
-                }
-                _ => bail!("Unsupported global init tag."),
-            }
+        match Byte::parse(ctx, cursor)? {
+            0 => Ok(GlobalInit::I32(cursor.next(ctx)?)),
+            1 => Ok(GlobalInit::I64(cursor.next(ctx)?)),
+            _ => bail!("Unsupported global init tag."),
         }
+    }
+}
+
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for InstantiatedGlobals {
+    fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
+        let inits = cursor.next(ctx)?;
         Ok(InstantiatedGlobals {
             inits,
         })
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for CompiledFunctionBytes<'a> {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for CompiledFunctionBytes<'a> {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let type_idx = TypeIndex::parse(ctx, cursor)?;
         let return_type = BlockType::parse(ctx, cursor)?;
@@ -75,7 +74,7 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for CompiledFunctionBytes<'a> {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for InstantiatedTable {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for InstantiatedTable {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let functions = cursor.next(ctx)?;
         Ok(InstantiatedTable {
@@ -84,7 +83,7 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for InstantiatedTable {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ArtifactMemory {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ArtifactMemory {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let init_size = cursor.next(ctx)?;
         let max_size = cursor.next(ctx)?;
@@ -97,7 +96,7 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ArtifactMemory {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ArtifactData {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ArtifactData {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let offset = cursor.next(ctx)?;
         let init = cursor.next(ctx)?;
@@ -108,36 +107,185 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ArtifactData {
     }
 }
 
+/// A consumer of the parts of a serialized artifact, which
+/// [`walk_artifact`] reports in the order they appear in the input.
+pub(crate) trait ArtifactVisitor<'a, I> {
+    fn import(&mut self, import: I);
+    fn ty(&mut self, ty: FunctionType);
+    fn table_entry(&mut self, entry: Option<FuncIndex>);
+    /// Called for each memory of the artifact in order of their index, before
+    /// the data segments of that memory.
+    fn memory(&mut self, init_size: u32, max_size: u32);
+    fn data(&mut self, offset: i32, init: &'a [u8]);
+    fn global(&mut self, init: GlobalInit);
+    fn export(&mut self, name: Name, idx: FuncIndex);
+    fn function(&mut self, code: CompiledFunctionBytes<'a>);
+}
+
+/// Walk over a serialized artifact, reporting its parts to the visitor. This
+/// is the single definition of the artifact format on the parsing side, and
+/// should be kept in sync with the [`Output`](crate::output::Output) instance
+/// of [`Artifact`].
+///
+/// Exports are written in increasing order of their names, since they are
+/// stored in a map. Names that are not in that order indicate duplicates, or
+/// an artifact not produced by this crate, and are rejected.
+pub(crate) fn walk_artifact<
+    'a,
+    Ctx: ParseContext,
+    I: Parseable<'a, Ctx>,
+    V: ArtifactVisitor<'a, I>,
+>(
+    ctx: Ctx,
+    cursor: &mut Cursor<&'a [u8]>,
+    visitor: &mut V,
+) -> ParseResult<()> {
+    let num_imports = u32::parse(ctx, cursor)?;
+    for _ in 0..num_imports {
+        visitor.import(cursor.next(ctx)?);
+    }
+    let num_types = u32::parse(ctx, cursor)?;
+    for _ in 0..num_types {
+        visitor.ty(cursor.next(ctx)?);
+    }
+    let table_size = u32::parse(ctx, cursor)?;
+    for _ in 0..table_size {
+        visitor.table_entry(cursor.next(ctx)?);
+    }
+    // Artifacts with more than one memory use the tag 2, see the
+    // `Output` instance of `Artifact`.
+    match Byte::parse(ctx, cursor)? {
+        0 => (),
+        1 => walk_memory::<_, I, _>(ctx, cursor, visitor)?,
+        2 => {
+            walk_memory::<_, I, _>(ctx, cursor, visitor)?;
+            let num_additional = u32::parse(ctx, cursor)?;
+            for _ in 0..num_additional {
+                walk_memory::<_, I, _>(ctx, cursor, visitor)?;
+            }
+        }
+        tag => bail!(ParseError::UnsupportedOptionTag {
+            tag
+        }),
+    }
+    let num_globals = u32::parse(ctx, cursor)?;
+    for _ in 0..num_globals {
+        visitor.global(cursor.next(ctx)?);
+    }
+    let num_exports = u32::parse(ctx, cursor)?;
+    let mut last_export: Option<Name> = None;
+    for _ in 0..num_exports {
+        let name = Name::parse(ctx, cursor)?;
+        let idx = FuncIndex::parse(ctx, cursor)?;
+        if let Some(last) = last_export.as_ref() {
+            ensure!(
+                *last < name,
+                "Duplicate or unordered names in export list. This should not happen in artifacts."
+            );
+        }
+        last_export = Some(name.clone());
+        visitor.export(name, idx);
+    }
+    let num_functions = u32::parse(ctx, cursor)?;
+    for _ in 0..num_functions {
+        visitor.function(cursor.next(ctx)?);
+    }
+    Ok(())
+}
+
+/// Walk over a single memory of a serialized artifact, followed by its data
+/// segments.
+fn walk_memory<'a, Ctx: ParseContext, I, V: ArtifactVisitor<'a, I>>(
+    ctx: Ctx,
+    cursor: &mut Cursor<&'a [u8]>,
+    visitor: &mut V,
+) -> ParseResult<()> {
+    let init_size = cursor.next(ctx)?;
+    let max_size = cursor.next(ctx)?;
+    visitor.memory(init_size, max_size);
+    let num_segments = u32::parse(ctx, cursor)?;
+    for _ in 0..num_segments {
+        let offset = cursor.next(ctx)?;
+        let init = cursor.next(ctx)?;
+        visitor.data(offset, init);
+    }
+    Ok(())
+}
+
+/// Collects the parts of an artifact reported by [`walk_artifact`].
+struct ArtifactBuilder<'a, I> {
+    imports:  Vec<I>,
+    ty:       Vec<FunctionType>,
+    table:    Vec<Option<FuncIndex>>,
+    /// All the memories, starting with memory 0.
+    memories: Vec<ArtifactMemory>,
+    global:   Vec<GlobalInit>,
+    export:   BTreeMap<Name, FuncIndex>,
+    code:     Vec<CompiledFunctionBytes<'a>>,
+}
+
+impl<'a, I> ArtifactVisitor<'a, I> for ArtifactBuilder<'a, I> {
+    fn import(&mut self, import: I) { self.imports.push(import) }
+
+    fn ty(&mut self, ty: FunctionType) { self.ty.push(ty) }
+
+    fn table_entry(&mut self, entry: Option<FuncIndex>) { self.table.push(entry) }
+
+    fn memory(&mut self, init_size: u32, max_size: u32) {
+        self.memories.push(ArtifactMemory {
+            init_size,
+            max_size,
+            init: Vec::new(),
+        })
+    }
+
+    fn data(&mut self, offset: i32, init: &'a [u8]) {
+        if let Some(memory) = self.memories.last_mut() {
+            memory.init.push(ArtifactData {
+                offset,
+                init: init.to_vec(),
+            })
+        }
+    }
+
+    fn global(&mut self, init: GlobalInit) { self.global.push(init) }
+
+    fn export(&mut self, name: Name, idx: FuncIndex) { self.export.insert(name, idx); }
+
+    fn function(&mut self, code: CompiledFunctionBytes<'a>) { self.code.push(code) }
+}
+
 /// NB: This implementation is only meant to be used on trusted sources.
 /// It optimistically allocates memory, which could lead to problems if the
 /// input is untrusted.
-impl<'a, Ctx: Copy, I: Parseable<'a, Ctx>> Parseable<'a, Ctx>
+impl<'a, Ctx: ParseContext, I: Parseable<'a, Ctx>> Parseable<'a, Ctx>
     for Artifact<I, CompiledFunctionBytes<'a>>
 {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
-        let imports: Vec<I> = Vec::parse(ctx, cursor)?;
-        let ty: Vec<FunctionType> = Vec::parse(ctx, cursor)?;
-        let table = InstantiatedTable::parse(ctx, cursor)?;
-        let memory = cursor.next(ctx)?;
-        let global = InstantiatedGlobals::parse(ctx, cursor)?;
-        let export_len = u32::parse(ctx, cursor)?;
-        let mut export = BTreeMap::new();
-        for _ in 0..export_len {
-            let name = Name::parse(ctx, cursor)?;
-            let idx = FuncIndex::parse(ctx, cursor)?;
-            if export.insert(name, idx).is_some() {
-                bail!("Duplicate names in export list. This should not happen in artifacts.")
-            }
-        }
-        let code = Vec::parse(ctx, cursor)?;
+        let mut builder = ArtifactBuilder {
+            imports:  Vec::new(),
+            ty:       Vec::new(),
+            table:    Vec::new(),
+            memories: Vec::new(),
+            global:   Vec::new(),
+            export:   BTreeMap::new(),
+            code:     Vec::new(),
+        };
+        walk_artifact::<_, I, _>(ctx, cursor, &mut builder)?;
+        let mut memories = builder.memories.into_iter();
         Ok(Artifact {
-            imports,
-            ty,
-            table,
-            memory,
-            global,
-            export,
-            code,
+            imports:             builder.imports,
+            ty:                  builder.ty,
+            table:               InstantiatedTable {
+                functions: builder.table,
+            },
+            memory:              memories.next(),
+            additional_memories: memories.collect(),
+            global:              InstantiatedGlobals {
+                inits: builder.global,
+            },
+            export:              builder.export,
+            code:                builder.code,
         })
     }
 }
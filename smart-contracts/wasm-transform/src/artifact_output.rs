@@ -72,7 +72,22 @@ impl<ImportFunc: Output, CompiledCode: RunnableCode> Output for Artifact<ImportF
         self.imports.output(out)?;
         self.ty.output(out)?;
         self.table.functions.output(out)?;
-        self.memory.output(out)?;
+        // The memory is written as an optional value. Artifacts with additional
+        // memories use the tag 2, followed by memory 0 and the list of additional
+        // memories, so that the format of artifacts with at most one memory is
+        // unchanged.
+        match self.memory {
+            None => 0u8.output(out)?,
+            Some(ref memory) if self.additional_memories.is_empty() => {
+                1u8.output(out)?;
+                memory.output(out)?;
+            }
+            Some(ref memory) => {
+                2u8.output(out)?;
+                memory.output(out)?;
+                self.additional_memories.output(out)?;
+            }
+        }
         self.global.output(out)?;
         (self.export.len() as u32).output(out)?;
         for (name, idx) in self.export.iter() {
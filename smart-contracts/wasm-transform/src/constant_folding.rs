@@ -0,0 +1,157 @@
+//! An optional optimization pass that folds integer operations on constant
+//! arguments. For example `i32.const 1 i32.const 2 i32.add` is replaced by
+//! `i32.const 3`. This reduces the number of instructions, and thus the cost of
+//! executing them when the pass is applied before metering is injected.
+//!
+//! The results are computed exactly as the interpreter in
+//! [`machine`](crate::machine) computes them, with wrapping arithmetic and
+//! shift amounts taken modulo the bit width. Operations that would trap at
+//! runtime, such as division by zero, are left in place so that the trap is
+//! preserved.
+//!
+//! Only instructions that directly follow the constants they consume are
+//! folded. Instructions involving memory, locals, globals, calls, or control
+//! flow are never folded.
+
+use crate::types::*;
+
+impl Module {
+    /// Fold constant integer operations in all the function bodies of the
+    /// module. See [`fold_constants`] for details.
+    pub fn fold_constants(&mut self) {
+        for code in self.code.impls.iter_mut() {
+            fold_constants(&mut code.expr.instrs);
+        }
+    }
+}
+
+/// Fold operations whose arguments are given by the directly preceding
+/// constant instructions. Folding is repeated so that nested constant
+/// expressions are reduced to a single constant.
+pub fn fold_constants(instrs: &mut InstrSeq) {
+    let mut out: InstrSeq = Vec::with_capacity(instrs.len());
+    for instr in instrs.drain(..) {
+        let folded = match out.as_slice() {
+            [.., OpCode::I32Const(x), OpCode::I32Const(y)] => fold_binary_i32(&instr, *x, *y)
+                .map(|r| (2, r))
+                .or_else(|| fold_unary_i32(&instr, *y).map(|r| (1, r))),
+            [.., OpCode::I64Const(x), OpCode::I64Const(y)] => fold_binary_i64(&instr, *x, *y)
+                .map(|r| (2, r))
+                .or_else(|| fold_unary_i64(&instr, *y).map(|r| (1, r))),
+            [.., OpCode::I32Const(y)] => fold_unary_i32(&instr, *y).map(|r| (1, r)),
+            [.., OpCode::I64Const(y)] => fold_unary_i64(&instr, *y).map(|r| (1, r)),
+            _ => None,
+        };
+        if let Some((consumed, result)) = folded {
+            out.truncate(out.len() - consumed);
+            out.push(result);
+        } else {
+            out.push(instr);
+        }
+    }
+    *instrs = out;
+}
+
+/// Fold a unary operation whose argument is an `i32`.
+fn fold_unary_i32(instr: &OpCode, x: i32) -> Option<OpCode> {
+    use OpCode::*;
+    let result = match instr {
+        I32Eqz => I32Const((x == 0) as i32),
+        I32Clz => I32Const(x.leading_zeros() as i32),
+        I32Ctz => I32Const(x.trailing_zeros() as i32),
+        I32Popcnt => I32Const(x.count_ones() as i32),
+        I64ExtendI32S => I64Const(x as i64),
+        I64ExtendI32U => I64Const(x as u32 as i64),
+        I32Extend8S => I32Const(x as i8 as i32),
+        I32Extend16S => I32Const(x as i16 as i32),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Fold a unary operation whose argument is an `i64`.
+fn fold_unary_i64(instr: &OpCode, x: i64) -> Option<OpCode> {
+    use OpCode::*;
+    let result = match instr {
+        I64Eqz => I32Const((x == 0) as i32),
+        I64Clz => I64Const(x.leading_zeros() as i64),
+        I64Ctz => I64Const(x.trailing_zeros() as i64),
+        I64Popcnt => I64Const(x.count_ones() as i64),
+        I32WrapI64 => I32Const(x as i32),
+        I64Extend8S => I64Const(x as i8 as i64),
+        I64Extend16S => I64Const(x as i16 as i64),
+        I64Extend32S => I64Const(x as i32 as i64),
+        _ => return None,
+    };
+    Some(result)
+}
+
+/// Fold a binary operation whose arguments are `i32`s. Operations that would
+/// trap at runtime are not folded.
+fn fold_binary_i32(instr: &OpCode, x: i32, y: i32) -> Option<OpCode> {
+    use OpCode::*;
+    let result = match instr {
+        I32Eq => (x == y) as i32,
+        I32Ne => (x != y) as i32,
+        I32LtS => (x < y) as i32,
+        I32LtU => ((x as u32) < (y as u32)) as i32,
+        I32GtS => (x > y) as i32,
+        I32GtU => ((x as u32) > (y as u32)) as i32,
+        I32LeS => (x <= y) as i32,
+        I32LeU => ((x as u32) <= (y as u32)) as i32,
+        I32GeS => (x >= y) as i32,
+        I32GeU => ((x as u32) >= (y as u32)) as i32,
+        I32Add => x.wrapping_add(y),
+        I32Sub => x.wrapping_sub(y),
+        I32Mul => x.wrapping_mul(y),
+        I32DivS => x.checked_div(y)?,
+        I32DivU => (x as u32).checked_div(y as u32)? as i32,
+        I32RemS => x.checked_rem(y)?,
+        I32RemU => (x as u32).checked_rem(y as u32)? as i32,
+        I32And => x & y,
+        I32Or => x | y,
+        I32Xor => x ^ y,
+        I32Shl => x << (y as u32 % 32),
+        I32ShrS => x >> (y as u32 % 32),
+        I32ShrU => ((x as u32) >> (y as u32 % 32)) as i32,
+        I32Rotl => x.rotate_left(y as u32 % 32),
+        I32Rotr => x.rotate_right(y as u32 % 32),
+        _ => return None,
+    };
+    Some(I32Const(result))
+}
+
+/// Fold a binary operation whose arguments are `i64`s. Operations that would
+/// trap at runtime are not folded.
+fn fold_binary_i64(instr: &OpCode, x: i64, y: i64) -> Option<OpCode> {
+    use OpCode::*;
+    let result = match instr {
+        I64Eq => I32Const((x == y) as i32),
+        I64Ne => I32Const((x != y) as i32),
+        I64LtS => I32Const((x < y) as i32),
+        I64LtU => I32Const(((x as u64) < (y as u64)) as i32),
+        I64GtS => I32Const((x > y) as i32),
+        I64GtU => I32Const(((x as u64) > (y as u64)) as i32),
+        I64LeS => I32Const((x <= y) as i32),
+        I64LeU => I32Const(((x as u64) <= (y as u64)) as i32),
+        I64GeS => I32Const((x >= y) as i32),
+        I64GeU => I32Const(((x as u64) >= (y as u64)) as i32),
+        I64Add => I64Const(x.wrapping_add(y)),
+        I64Sub => I64Const(x.wrapping_sub(y)),
+        I64Mul => I64Const(x.wrapping_mul(y)),
+        I64DivS => I64Const(x.checked_div(y)?),
+        I64DivU => I64Const((x as u64).checked_div(y as u64)? as i64),
+        I64RemS => I64Const(x.checked_rem(y)?),
+        I64RemU => I64Const((x as u64).checked_rem(y as u64)? as i64),
+        I64And => I64Const(x & y),
+        I64Or => I64Const(x | y),
+        I64Xor => I64Const(x ^ y),
+        I64Shl => I64Const(x << (y as u64 % 64)),
+        I64ShrS => I64Const(x >> (y as u64 % 64)),
+        I64ShrU => I64Const(((x as u64) >> (y as u64 % 64)) as i64),
+        I64Rotl => I64Const(x.rotate_left((y as u64 % 64) as u32)),
+        I64Rotr => I64Const(x.rotate_right((y as u64 % 64) as u32)),
+        _ => return None,
+    };
+    Some(result)
+}
@@ -0,0 +1,71 @@
+//! Tests for the constant folding pass.
+use crate::{
+    constant_folding::fold_constants,
+    types::{OpCode::*, *},
+};
+
+fn folded(mut instrs: InstrSeq) -> InstrSeq {
+    fold_constants(&mut instrs);
+    instrs
+}
+
+#[test]
+fn fold_nested_arithmetic() {
+    // (1 + 2) * 5 - 4
+    let instrs =
+        vec![I32Const(1), I32Const(2), I32Add, I32Const(5), I32Mul, I32Const(4), I32Sub, End];
+    assert_eq!(folded(instrs), vec![I32Const(11), End]);
+}
+
+#[test]
+fn fold_wrapping() {
+    assert_eq!(folded(vec![I32Const(i32::MAX), I32Const(1), I32Add]), vec![I32Const(i32::MIN)]);
+    assert_eq!(folded(vec![I64Const(i64::MIN), I64Const(1), I64Sub]), vec![I64Const(i64::MAX)]);
+    assert_eq!(folded(vec![I32Const(i32::MAX), I32Const(2), I32Mul]), vec![I32Const(-2)]);
+    // Shift amounts are taken modulo the bit width.
+    assert_eq!(folded(vec![I32Const(1), I32Const(33), I32Shl]), vec![I32Const(2)]);
+    assert_eq!(folded(vec![I64Const(-1), I64Const(65), I64ShrU]), vec![I64Const(i64::MAX)]);
+}
+
+#[test]
+fn fold_conversions_and_comparisons() {
+    assert_eq!(folded(vec![I32Const(-1), I64ExtendI32U]), vec![I64Const(0xFFFF_FFFF)]);
+    assert_eq!(folded(vec![I64Const(0x1_0000_0002), I32WrapI64]), vec![I32Const(2)]);
+    assert_eq!(folded(vec![I64Const(-1), I64Const(0), I64LtU]), vec![I32Const(0)]);
+    assert_eq!(folded(vec![I32Const(0), I32Eqz]), vec![I32Const(1)]);
+}
+
+#[test]
+fn no_fold_trapping() {
+    // Division and remainder by zero, as well as signed overflow, trap at runtime
+    // and must not be folded.
+    let div_by_zero = vec![I32Const(1), I32Const(0), I32DivS];
+    assert_eq!(folded(div_by_zero.clone()), div_by_zero);
+    let rem_by_zero = vec![I64Const(1), I64Const(0), I64RemU];
+    assert_eq!(folded(rem_by_zero.clone()), rem_by_zero);
+    let overflow = vec![I32Const(i32::MIN), I32Const(-1), I32DivS];
+    assert_eq!(folded(overflow.clone()), overflow);
+}
+
+#[test]
+fn no_fold_non_constant() {
+    // Only arguments that are constants directly preceding the operation are
+    // folded.
+    let with_local = vec![LocalGet(0), I32Const(1), I32Add];
+    assert_eq!(folded(with_local.clone()), with_local);
+    let with_memory = vec![
+        I32Const(0),
+        I32Load(MemArg {
+            offset: 0,
+            align:  2,
+            memory: 0,
+        }),
+    ];
+    assert_eq!(folded(with_memory.clone()), with_memory);
+    let across_block =
+        vec![I32Const(1), Block(BlockType::ValueType(ValueType::I32)), I32Const(2), End, I32Add];
+    assert_eq!(folded(across_block.clone()), across_block);
+    // Mismatched types are left alone.
+    let mismatched = vec![I64Const(1), I32Const(2), I32Add];
+    assert_eq!(folded(mismatched.clone()), mismatched);
+}
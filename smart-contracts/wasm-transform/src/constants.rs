@@ -4,6 +4,18 @@
 /// This includes parameters and declared locals.
 pub const ALLOWED_LOCALS: u32 = 1024;
 
+/// Maximum number of functions, imported and declared, in a module. This and
+/// the following two limits are only enforced when validating with
+/// `ValidationConfig::with_limits`.
+pub const MAX_NUM_FUNCTIONS: usize = 10_000;
+
+/// Maximum size in bytes of a single function body, including the declaration
+/// of its locals.
+pub const MAX_FUNCTION_BODY_SIZE: u32 = 512 * 1024;
+
+/// Maximum number of instructions in a single function body.
+pub const MAX_NUM_INSTRUCTIONS: usize = 1 << 18;
+
 /// Maximum number of bytes we will preallocate when parsing vector-like things.
 /// Preallocation is more efficient than starting from 0, but we need to be
 /// careful not to explode by maliciously crafted input.
@@ -32,6 +44,11 @@ pub const MAX_INIT_MEMORY_SIZE: u32 = 32;
 /// Maximum number of pages allowed by our contracts.
 pub const MAX_NUM_PAGES: u32 = 512; // corresponds to 32MB memory at most.
 
+/// Maximum number of memories a module may declare when the `multi-memory`
+/// feature is enabled.
+#[cfg(feature = "multi-memory")]
+pub const MAX_NUM_MEMORIES: usize = 4;
+
 /// Maximum allowed stack + locals height.
 pub const MAX_ALLOWED_STACK_HEIGHT: usize = 1024;
 
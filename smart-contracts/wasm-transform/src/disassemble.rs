@@ -0,0 +1,109 @@
+//! A disassembler for compiled function bodies. It shows the internal
+//! instruction sequence that the interpreter executes, i.e., the code after
+//! metering injection and compilation. This is intended for debugging energy
+//! accounting and compilation, and is not used when executing contracts.
+
+use crate::artifact::{Artifact, InternalOpcode, RunnableCode};
+use anyhow::{anyhow, ensure};
+use std::convert::TryFrom;
+
+/// A cursor into a compiled function body.
+struct CodeCursor<'a> {
+    code: &'a [u8],
+    pc:   usize,
+}
+
+impl<'a> CodeCursor<'a> {
+    fn next_bytes<const N: usize>(&mut self) -> anyhow::Result<[u8; N]> {
+        let end = self.pc + N;
+        ensure!(
+            end <= self.code.len(),
+            "Instruction sequence ends in the middle of an instruction."
+        );
+        let mut dst = [0u8; N];
+        dst.copy_from_slice(&self.code[self.pc..end]);
+        self.pc = end;
+        Ok(dst)
+    }
+
+    fn next_u16(&mut self) -> anyhow::Result<u16> { Ok(u16::from_le_bytes(self.next_bytes()?)) }
+
+    fn next_u32(&mut self) -> anyhow::Result<u32> { Ok(u32::from_le_bytes(self.next_bytes()?)) }
+
+    /// Read a jump, which is the stack height difference followed by the
+    /// target, and render it as `@target -diff`.
+    fn next_jump(&mut self) -> anyhow::Result<String> {
+        let diff = self.next_u32()?;
+        let target = self.next_u32()?;
+        Ok(format!(" @{} -{}", target, diff))
+    }
+}
+
+/// Disassemble a compiled function body into one line per instruction. Each
+/// line is of the form `offset: Opcode immediates`, where the offset is the
+/// position of the instruction in the sequence. Jumps are printed as
+/// `@target -diff`, where `target` is the offset jumped to and `diff` is the
+/// number of stack values removed by the jump.
+pub fn disassemble_code(code: &[u8]) -> anyhow::Result<Vec<String>> {
+    use InternalOpcode::*;
+    let mut cursor = CodeCursor {
+        code,
+        pc: 0,
+    };
+    let mut out = Vec::new();
+    while cursor.pc < code.len() {
+        let offset = cursor.pc;
+        let [byte] = cursor.next_bytes::<1>()?;
+        let opcode = InternalOpcode::try_from(byte)
+            .map_err(|_| anyhow!("Unknown opcode {:#04x} at offset {}.", byte, offset))?;
+        let immediates = match opcode {
+            If => format!(" @{}", cursor.next_u32()?),
+            Br | BrCarry | BrIf | BrIfCarry => cursor.next_jump()?,
+            BrTable | BrTableCarry => {
+                let num_labels = cursor.next_u16()?;
+                // The default target comes first, followed by the labels.
+                let mut jumps = cursor.next_jump()?;
+                for _ in 0..num_labels {
+                    jumps.push_str(&cursor.next_jump()?);
+                }
+                jumps
+            }
+            Call | CallIndirect | IndexedMemory => format!(" {}", cursor.next_u32()?),
+            LocalGet | LocalSet | LocalTee | GlobalGet | GlobalSet => {
+                format!(" {}", cursor.next_u16()?)
+            }
+            I32Load | I64Load | I32Load8S | I32Load8U | I32Load16S | I32Load16U | I64Load8S
+            | I64Load8U | I64Load16S | I64Load16U | I64Load32S | I64Load32U | I32Store
+            | I64Store | I32Store8 | I32Store16 | I64Store8 | I64Store16 | I64Store32 => {
+                format!(" offset={}", cursor.next_u32()?)
+            }
+            I32Const => format!(" {}", i32::from_le_bytes(cursor.next_bytes()?)),
+            I64Const => format!(" {}", i64::from_le_bytes(cursor.next_bytes()?)),
+            _ => String::new(),
+        };
+        out.push(format!("{}: {:?}{}", offset, opcode, immediates));
+    }
+    Ok(out)
+}
+
+impl<ImportFunc, CompiledCode: RunnableCode> Artifact<ImportFunc, CompiledCode> {
+    /// Disassemble all the functions defined in the artifact. Functions are
+    /// numbered including the imports, as in the original module, and the
+    /// instructions of each are listed as described in [`disassemble_code`].
+    pub fn disassemble(&self) -> anyhow::Result<String> {
+        let mut out = String::new();
+        for (i, f) in self.code.iter().enumerate() {
+            out.push_str(&format!(
+                "(func (;{};) (type {})\n",
+                self.imports.len() + i,
+                f.type_idx()
+            ));
+            for line in disassemble_code(f.code())? {
+                out.push_str("  ");
+                out.push_str(&line);
+                out.push('\n');
+            }
+        }
+        Ok(out)
+    }
+}
@@ -0,0 +1,39 @@
+//! Tests for disassembling compiled code.
+use crate::{
+    artifact::{Artifact, ArtifactNamedImport, CompiledFunction, RunnableCode},
+    disassemble::disassemble_code,
+    output_test::parse_module,
+};
+
+#[test]
+fn artifact_disassemble() {
+    // A module with a single function that adds its two arguments.
+    let add_module: &[u8] = &[
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // magic hash and version
+        0x01, 0x07, 0x01, 0x60, 0x02, 0x7F, 0x7F, 0x01, 0x7F, // types
+        0x03, 0x02, 0x01, 0x00, // functions
+        0x0A, 0x09, 0x01, 0x07, 0x00, 0x20, 0x00, 0x20, 0x01, 0x6A, 0x0B, // code
+    ];
+    let mut module = parse_module(add_module);
+    module.inject_metering().expect("Metering injection should succeed.");
+    let artifact: Artifact<ArtifactNamedImport, CompiledFunction> =
+        module.compile().expect("Module should compile.");
+    let lines = disassemble_code(artifact.code[0].code())
+        .expect("Disassembling compiled code should succeed.");
+    // The energy charged depends on the cost specification, so only the shape of
+    // the charging instruction is checked.
+    assert!(lines[0].starts_with("0: I64Const "), "Energy should be charged first: {:?}", lines);
+    assert_eq!(&lines[1..], &[
+        "9: Call 0",
+        "14: LocalGet 0",
+        "17: LocalGet 1",
+        "20: I32Add",
+        "21: Return"
+    ]);
+    let listing = artifact.disassemble().expect("Disassembling the artifact should succeed.");
+    assert!(
+        listing.starts_with("(func (;4;) (type 0)\n  0: I64Const "),
+        "Functions should be numbered after the metering imports: {}",
+        listing
+    );
+}
@@ -13,14 +13,33 @@
 pub mod artifact;
 mod artifact_input;
 mod artifact_output;
+pub mod constant_folding;
 pub mod constants;
+pub mod disassemble;
 pub mod machine;
-mod metering_transformation;
+pub mod metering_transformation;
 pub mod output;
 pub mod parse;
 pub mod types;
 pub mod utils;
 pub mod validate;
+mod wat;
 
+#[cfg(test)]
+mod constant_folding_test;
+#[cfg(test)]
+mod disassemble_test;
+#[cfg(test)]
+mod machine_test;
 #[cfg(test)]
 mod metering_transformation_test;
+#[cfg(test)]
+mod output_test;
+#[cfg(test)]
+mod parse_test;
+#[cfg(test)]
+mod types_test;
+#[cfg(test)]
+mod utils_test;
+#[cfg(test)]
+mod validate_test;
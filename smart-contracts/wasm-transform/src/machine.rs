@@ -9,7 +9,10 @@ use crate::{
     types::*,
 };
 use anyhow::{anyhow, bail, ensure};
-use std::{convert::TryInto, io::Write};
+use std::{
+    convert::{TryFrom, TryInto},
+    io::Write,
+};
 
 /// An empty type used when no interrupt is possible by a host function call.
 #[derive(Debug, Copy, Clone)]
@@ -55,30 +58,33 @@ pub type RunResult<A> = anyhow::Result<A>;
 #[derive(Debug)]
 pub struct RunConfig {
     /// Current value of the program counter.
-    pc:               usize,
+    pc:                  usize,
     /// Index of the current instruction list that we are executing
     /// (instructions of the current function). Note that this is the index in
     /// the list of defined functions. Imported functions do not count towards
     /// it. It is assumed that this index points to a valid function in the
     /// artifact's list of functions and the interpreter is subject to undefined
     /// behaviour if this is not the case.
-    instructions_idx: usize,
+    instructions_idx:    usize,
     /// Stack of function frames.
-    function_frames:  Vec<FunctionState>,
+    function_frames:     Vec<FunctionState>,
     /// Return value of the current frame.
-    return_type:      BlockType,
+    return_type:         BlockType,
     /// Current state of the memory.
-    memory:           Vec<u8>,
+    memory:              Vec<u8>,
+    /// Current state of the memories with index 1 and above. These cannot
+    /// grow, so their size is fixed at startup.
+    additional_memories: Vec<Vec<u8>>,
     /// Stack of both the locals and the normal stack.
-    stack:            RuntimeStack,
+    stack:               RuntimeStack,
     /// Position where the locals for the current frame start.
-    locals_base:      usize,
+    locals_base:         usize,
     /// Current values of globals.
-    globals:          Vec<StackValue>,
+    globals:             Vec<StackValue>,
     /// Configuration parameter, the maximum size of the memory execution is
     /// allowed to allocate. This is fixed at startup and cannot be changed
     /// during execution.
-    max_memory:       usize,
+    max_memory:          usize,
 }
 
 impl RunConfig {
@@ -176,6 +182,9 @@ pub struct RuntimeStack {
 pub enum RuntimeError {
     /// Calling an imported function directly is not supported.
     DirectlyCallImport,
+    /// The type of the function selected by `call_indirect` is not the
+    /// expected one.
+    IndirectCallTypeMismatch,
 }
 
 impl std::fmt::Display for RuntimeError {
@@ -184,6 +193,9 @@ impl std::fmt::Display for RuntimeError {
             RuntimeError::DirectlyCallImport => {
                 write!(f, "Calling an imported function directly is not supported.")
             }
+            RuntimeError::IndirectCallTypeMismatch => {
+                write!(f, "Actual type different from expected.")
+            }
         }
     }
 }
@@ -377,6 +389,108 @@ fn write_memory_at(memory: &mut [u8], pos: usize, bytes: &[u8]) -> RunResult<()>
     Ok(())
 }
 
+/// Execute the load or store instruction with the given opcode on the given
+/// memory. This is used for instructions prefixed by
+/// [`IndexedMemory`](InternalOpcode::IndexedMemory). Instructions that access
+/// memory 0 are executed directly in the interpreter loop.
+fn indexed_memory_access(
+    opcode: u8,
+    memory: &mut [u8],
+    instructions: &[u8],
+    stack: &mut RuntimeStack,
+    pc: &mut usize,
+) -> RunResult<()> {
+    let opcode =
+        InternalOpcode::try_from(opcode).map_err(|_| anyhow!("Illegal opcode {}.", opcode))?;
+    match opcode {
+        InternalOpcode::I32Load => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_i32(memory, pos)?))
+        }
+        InternalOpcode::I64Load => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_i64(memory, pos)?))
+        }
+        InternalOpcode::I32Load8S => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_i8(memory, pos)? as i32))
+        }
+        InternalOpcode::I32Load8U => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_u8(memory, pos)? as i32))
+        }
+        InternalOpcode::I32Load16S => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_i16(memory, pos)? as i32))
+        }
+        InternalOpcode::I32Load16U => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_u16(memory, pos)? as i32))
+        }
+        InternalOpcode::I64Load8S => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_i8(memory, pos)? as i64))
+        }
+        InternalOpcode::I64Load8U => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_u8(memory, pos)? as i64))
+        }
+        InternalOpcode::I64Load16S => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_i16(memory, pos)? as i64))
+        }
+        InternalOpcode::I64Load16U => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_u16(memory, pos)? as i64))
+        }
+        InternalOpcode::I64Load32S => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_i32(memory, pos)? as i64))
+        }
+        InternalOpcode::I64Load32U => {
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            stack.push(StackValue::from(read_u32(memory, pos)? as i64))
+        }
+        InternalOpcode::I32Store => {
+            let val = unsafe { stack.pop().short };
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            write_memory_at(memory, pos, &val.to_le_bytes())?;
+        }
+        InternalOpcode::I64Store => {
+            let val = unsafe { stack.pop().long };
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            write_memory_at(memory, pos, &val.to_le_bytes())?;
+        }
+        InternalOpcode::I32Store8 => {
+            let val = unsafe { stack.pop().short };
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            write_memory_at(memory, pos, &val.to_le_bytes()[..1])?;
+        }
+        InternalOpcode::I32Store16 => {
+            let val = unsafe { stack.pop().short };
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            write_memory_at(memory, pos, &val.to_le_bytes()[..2])?;
+        }
+        InternalOpcode::I64Store8 => {
+            let val = unsafe { stack.pop().long };
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            write_memory_at(memory, pos, &val.to_le_bytes()[..1])?;
+        }
+        InternalOpcode::I64Store16 => {
+            let val = unsafe { stack.pop().long };
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            write_memory_at(memory, pos, &val.to_le_bytes()[..2])?;
+        }
+        InternalOpcode::I64Store32 => {
+            let val = unsafe { stack.pop().long };
+            let pos = get_memory_pos(instructions, stack, pc)?;
+            write_memory_at(memory, pos, &val.to_le_bytes()[..4])?;
+        }
+        _ => bail!("Only loads and stores can access memories other than memory 0."),
+    }
+    Ok(())
+}
+
 #[cfg_attr(not(feature = "fuzz-coverage"), inline(always))]
 fn unary_i32(stack: &mut RuntimeStack, f: impl Fn(i32) -> i32) {
     let val = stack.peek_mut();
@@ -515,6 +629,17 @@ impl<I: TryFromImport, R: RunnableCode> Artifact<I, R> {
 
         let max_memory = self.memory.as_ref().map(|x| x.max_size).unwrap_or(0) as usize;
 
+        let mut additional_memories = Vec::with_capacity(self.additional_memories.len());
+        for m in self.additional_memories.iter() {
+            host.tick_initial_memory(m.init_size)?;
+            // Additional memories cannot grow, so only the initial memory is allocated.
+            let mut memory = vec![0u8; (m.init_size * PAGE_SIZE) as usize];
+            for data in m.init.iter() {
+                (&mut memory[data.offset as usize..]).write_all(&data.init)?;
+            }
+            additional_memories.push(memory);
+        }
+
         let pc = 0;
 
         let function_frames: Vec<FunctionState> = Vec::new();
@@ -527,6 +652,7 @@ impl<I: TryFromImport, R: RunnableCode> Artifact<I, R> {
             function_frames,
             return_type,
             memory,
+            additional_memories,
             stack,
             locals_base,
             globals,
@@ -576,6 +702,7 @@ impl<I: TryFromImport, R: RunnableCode> Artifact<I, R> {
             mut function_frames,
             mut return_type,
             mut memory,
+            mut additional_memories,
             mut stack,
             mut locals_base,
             mut globals,
@@ -594,7 +721,7 @@ impl<I: TryFromImport, R: RunnableCode> Artifact<I, R> {
             // InternalOpcode::try_from(instr). About 25% faster on a fibonacci test.
             // The ensure here guarantees that the transmute is safe, provided that
             // InternalOpcode stays as it is.
-            // ensure!(instr <= InternalOpcode::I64ExtendI32U as u8, "Illegal opcode.");
+            // ensure!(instr <= InternalOpcode::I64Extend32S as u8, "Illegal opcode.");
             // println!("{:#?}", unsafe { std::mem::transmute::<_,InternalOpcode>(instr) });
             match unsafe { std::mem::transmute(instr) } {
                 // InternalOpcode::try_from(instr)? {
@@ -726,6 +853,7 @@ impl<I: TryFromImport, R: RunnableCode> Artifact<I, R> {
                                     function_frames,
                                     return_type,
                                     memory,
+                                    additional_memories,
                                     stack,
                                     locals_base,
                                     globals,
@@ -772,7 +900,7 @@ impl<I: TryFromImport, R: RunnableCode> Artifact<I, R> {
                         if let Some(f) = self.imports.get(*f_idx as usize) {
                             let ty_actual = f.ty();
                             // call imported function.
-                            ensure!(ty_actual == ty, "Actual type different from expected.");
+                            ensure!(ty_actual == ty, RuntimeError::IndirectCallTypeMismatch);
                             if let Some(reason) = host.call(f, &mut memory, &mut stack)? {
                                 return Ok(ExecutionOutcome::Interrupted {
                                     reason,
@@ -782,6 +910,7 @@ impl<I: TryFromImport, R: RunnableCode> Artifact<I, R> {
                                         function_frames,
                                         return_type,
                                         memory,
+                                        additional_memories,
                                         stack,
                                         locals_base,
                                         globals,
@@ -800,7 +929,7 @@ impl<I: TryFromImport, R: RunnableCode> Artifact<I, R> {
                                 })?;
                             ensure!(
                                 f.type_idx() == ty_idx || ty_actual == ty,
-                                "Actual type different from expected."
+                                RuntimeError::IndirectCallTypeMismatch
                             );
                             // FIXME: Remove duplication.
                             let current_frame = FunctionState {
@@ -1209,6 +1338,36 @@ impl<I: TryFromImport, R: RunnableCode> Artifact<I, R> {
                     // and then extend, making it so that it is extended with 0's.
                     top.long = unsafe { top.short } as u32 as i64;
                 }
+                InternalOpcode::I32Extend8S => {
+                    let top = stack.peek_mut();
+                    top.short = unsafe { top.short } as i8 as i32;
+                }
+                InternalOpcode::I32Extend16S => {
+                    let top = stack.peek_mut();
+                    top.short = unsafe { top.short } as i16 as i32;
+                }
+                InternalOpcode::I64Extend8S => {
+                    let top = stack.peek_mut();
+                    top.long = unsafe { top.long } as i8 as i64;
+                }
+                InternalOpcode::I64Extend16S => {
+                    let top = stack.peek_mut();
+                    top.long = unsafe { top.long } as i16 as i64;
+                }
+                InternalOpcode::I64Extend32S => {
+                    let top = stack.peek_mut();
+                    top.long = unsafe { top.long } as i32 as i64;
+                }
+                InternalOpcode::IndexedMemory => {
+                    let idx = get_u32(instructions, &mut pc);
+                    let memory = (idx as usize)
+                        .checked_sub(1)
+                        .and_then(|i| additional_memories.get_mut(i))
+                        .ok_or_else(|| anyhow!("Accessing non-existent memory {}.", idx))?;
+                    let opcode = instructions[pc];
+                    pc += 1;
+                    indexed_memory_access(opcode, memory, instructions, &mut stack, &mut pc)?;
+                }
             }
         }
 
@@ -0,0 +1,90 @@
+//! Tests for executing compiled modules.
+use crate::{
+    artifact::{Artifact, ArtifactNamedImport, CompiledFunction},
+    machine::{ExecutionOutcome, Host, NoInterrupt, RunResult, RuntimeStack, Value},
+    validate_test::{unop_module, validate_with_sign_extension},
+};
+use anyhow::bail;
+
+/// A host that traps on every call to a host function.
+struct TrapHost;
+
+impl Host<ArtifactNamedImport> for TrapHost {
+    type Interrupt = NoInterrupt;
+
+    fn tick_initial_memory(&mut self, _num_pages: u32) -> RunResult<()> { Ok(()) }
+
+    fn call(
+        &mut self,
+        f: &ArtifactNamedImport,
+        _memory: &mut Vec<u8>,
+        _stack: &mut RuntimeStack,
+    ) -> RunResult<Option<NoInterrupt>> {
+        bail!("Unexpected call to host function {}.", f)
+    }
+}
+
+/// Apply the unary instruction with the given opcode to the argument, and
+/// return the result.
+fn run_unop(param: u8, opcode: u8, arg: Value) -> Value {
+    let module = validate_with_sign_extension(&unop_module(param, param, opcode))
+        .expect("Module should validate.");
+    let artifact: Artifact<ArtifactNamedImport, CompiledFunction> =
+        module.compile().expect("Module should compile.");
+    match artifact.run(&mut TrapHost, "f", &[arg]).expect("Execution should succeed.") {
+        ExecutionOutcome::Success {
+            result: Some(result),
+            ..
+        } => result,
+        ExecutionOutcome::Success {
+            result: None,
+            ..
+        } => panic!("The function should return a value."),
+        ExecutionOutcome::Interrupted {
+            ..
+        } => panic!("Execution should not be interrupted."),
+    }
+}
+
+#[test]
+fn sign_extension_execution() {
+    assert_eq!(run_unop(0x7F, 0xC0, Value::I32(0x80)), Value::I32(-128));
+    assert_eq!(run_unop(0x7F, 0xC0, Value::I32(0x17F)), Value::I32(0x7F));
+    assert_eq!(run_unop(0x7F, 0xC1, Value::I32(0x8000)), Value::I32(-32768));
+    assert_eq!(run_unop(0x7F, 0xC1, Value::I32(0x1_7FFF)), Value::I32(0x7FFF));
+    assert_eq!(run_unop(0x7E, 0xC2, Value::I64(0xFF)), Value::I64(-1));
+    assert_eq!(run_unop(0x7E, 0xC2, Value::I64(0x17F)), Value::I64(0x7F));
+    assert_eq!(run_unop(0x7E, 0xC3, Value::I64(0x8000)), Value::I64(-32768));
+    assert_eq!(run_unop(0x7E, 0xC3, Value::I64(0x1_7FFF)), Value::I64(0x7FFF));
+    assert_eq!(run_unop(0x7E, 0xC4, Value::I64(0x8000_0000)), Value::I64(-2147483648));
+    assert_eq!(run_unop(0x7E, 0xC4, Value::I64(0x1_7FFF_FFFF)), Value::I64(0x7FFF_FFFF));
+}
+
+#[test]
+#[cfg(feature = "multi-memory")]
+fn multiple_memories_execution() {
+    use crate::{output::Output, utils::parse_artifact};
+    let artifact: Artifact<ArtifactNamedImport, CompiledFunction> =
+        crate::output_test::parse_module(crate::parse_test::TWO_MEMORIES)
+            .compile()
+            .expect("Module should compile.");
+    assert_eq!(artifact.additional_memories.len(), 1, "The artifact should have two memories.");
+    let check = |outcome: ExecutionOutcome<NoInterrupt>| match outcome {
+        ExecutionOutcome::Success {
+            result,
+            memory,
+        } => {
+            assert_eq!(result, Some(Value::I32(43)), "Loads should use the given memory.");
+            assert!(memory.iter().all(|&b| b == 0), "Memory 0 should not be modified.");
+        }
+        ExecutionOutcome::Interrupted {
+            ..
+        } => panic!("Execution should not be interrupted."),
+    };
+    check(artifact.run(&mut TrapHost, "f", &[]).expect("Execution should succeed."));
+    // The additional memories should be retained when the artifact is serialized.
+    let mut out = Vec::new();
+    artifact.output(&mut out).expect("Outputting to a vector should succeed.");
+    let parsed = parse_artifact::<ArtifactNamedImport>(&out).expect("Artifact should parse.");
+    check(parsed.run(&mut TrapHost, "f", &[]).expect("Execution should succeed."));
+}
@@ -322,17 +322,86 @@ pub(crate) mod cost {
             I32WrapI64 => SIMPLE_UNOP,
             I64ExtendI32S => SIMPLE_UNOP,
             I64ExtendI32U => SIMPLE_UNOP,
+
+            I32Extend8S => SIMPLE_UNOP,
+            I32Extend16S => SIMPLE_UNOP,
+            I64Extend8S => SIMPLE_UNOP,
+            I64Extend16S => SIMPLE_UNOP,
+            I64Extend32S => SIMPLE_UNOP,
         };
         Ok(res)
     }
 }
 
-use cost::Energy;
+pub use cost::Energy;
+
+/// Get the cost of the given instruction as charged by
+/// [`Module::inject_metering`]. This is intended for tools that estimate the
+/// cost of executing code without running it.
+///
+/// The cost of branches and returns depends on the labels in scope, given from
+/// the outermost, which is the return type of the function, to the innermost.
+/// The cost of calls depends on the type of the callee, which is looked up in
+/// the module. For other instructions these arguments are not used.
+///
+/// Note that in addition to the cost of the instructions, invoking a function
+/// is charged for its locals, see [`CostModel::invoke_after`].
+pub fn instruction_cost<C: HasTransformationContext>(
+    instr: &OpCode,
+    labels: &[BlockType],
+    module: &C,
+) -> TransformationResult<Energy> {
+    cost::get_cost(instr, labels, module)
+}
+
+/// A cost model assigns energy costs to instructions. Different cost models
+/// can be used to price instructions differently, e.g., for different versions
+/// of the chain. The [`DefaultCostModel`] is used by
+/// [`Module::inject_metering`].
+pub trait CostModel {
+    /// Get the cost of the given instruction in the context of the stack of
+    /// labels, and the module.
+    fn get_cost<C: HasTransformationContext>(
+        &self,
+        instr: &OpCode,
+        labels: &[BlockType],
+        module: &C,
+    ) -> TransformationResult<Energy>;
+
+    /// Cost of an unconditional jump with the given label arity. This is
+    /// charged when the branch of a `br_if` instruction is taken.
+    fn branch(&self, label_arity: usize) -> Energy;
+
+    /// Cost incurred by the number of locals when invoking a function. This is
+    /// charged at the beginning of the function body.
+    fn invoke_after(&self, num_locals: u32) -> Energy;
+}
+
+/// The cost model defined by the constants and functions in the `cost` module.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultCostModel;
+
+impl CostModel for DefaultCostModel {
+    fn get_cost<C: HasTransformationContext>(
+        &self,
+        instr: &OpCode,
+        labels: &[BlockType],
+        module: &C,
+    ) -> TransformationResult<Energy> {
+        cost::get_cost(instr, labels, module)
+    }
+
+    fn branch(&self, label_arity: usize) -> Energy { cost::branch(label_arity) }
+
+    fn invoke_after(&self, num_locals: u32) -> Energy { cost::invoke_after(num_locals) }
+}
 
 ///Metadata needed for transformation.
-struct InstrSeqTransformer<'a, C> {
+struct InstrSeqTransformer<'a, C, M> {
     /// Reference to the original module to get the right context.
     module:               &'a C,
+    /// The cost model used to price the instructions.
+    cost_model:           &'a M,
     /// Current label stack (in the form of the labels' arities).
     /// The last item in the vector is the innermost block label.
     labels:               Vec<BlockType>,
@@ -346,7 +415,7 @@ struct InstrSeqTransformer<'a, C> {
     pending_instructions: InstrSeq,
 }
 
-impl<'b, C: HasTransformationContext> InstrSeqTransformer<'b, C> {
+impl<'b, C: HasTransformationContext, M: CostModel> InstrSeqTransformer<'b, C, M> {
     fn lookup_label(&mut self, idx: LabelIndex) -> TransformationResult<usize> {
         lookup_label(&self.labels, idx)
     }
@@ -406,7 +475,8 @@ impl<'b, C: HasTransformationContext> InstrSeqTransformer<'b, C> {
         for instr in input_instructions {
             // First add the energy to be charged for this instruction to the accumulated
             // energy.
-            self.add_energy(cost::get_cost(instr, &self.labels, self.module)?);
+            let energy = self.cost_model.get_cost(instr, &self.labels, self.module)?;
+            self.add_energy(energy);
 
             // Then determine whether the current unconditional instruction sequence stops
             // (in which case the amount to charge for the collected instructions is now
@@ -529,7 +599,7 @@ impl<'b, C: HasTransformationContext> InstrSeqTransformer<'b, C> {
                             self.add_to_new(&If {
                                 ty: BlockType::EmptyType,
                             });
-                            self.account_energy(cost::branch(label_arity));
+                            self.account_energy(self.cost_model.branch(label_arity));
                             // In the replacement instruction, the label moves out by one index and
                             // therefore the index has to be incremented.
                             self.add_to_new(&Br(idx + 1));
@@ -545,7 +615,7 @@ impl<'b, C: HasTransformationContext> InstrSeqTransformer<'b, C> {
                             self.add_to_new(&If {
                                 ty: BlockType::ValueType(ValueType::I32),
                             });
-                            self.account_energy(cost::branch(label_arity));
+                            self.account_energy(self.cost_model.branch(label_arity));
                             self.add_to_new(&I32Const(1));
                             self.add_to_new(&Else);
                             self.add_to_new(&I32Const(0));
@@ -591,7 +661,7 @@ impl<'b, C: HasTransformationContext> InstrSeqTransformer<'b, C> {
 /// A helper trait so that we can use the transformation on different datatypes.
 /// In particular we use it in tests which have their own notion of context to
 /// make it possible to specify modules in a compact way.
-pub(crate) trait HasTransformationContext {
+pub trait HasTransformationContext {
     /// Get the number of arguments and return values of a function type at the
     /// given index.
     fn get_type_len(&self, idx: TypeIndex) -> TransformationResult<(usize, usize)>;
@@ -630,13 +700,23 @@ impl HasTransformationContext for Module {
 pub(crate) fn inject_accounting<C: HasTransformationContext>(
     function: &Code,
     module: &C,
+) -> TransformationResult<Code> {
+    inject_accounting_with(function, module, &DefaultCostModel)
+}
+
+/// Inject cost accounting into the function, according to the given cost
+/// model.
+pub(crate) fn inject_accounting_with<C: HasTransformationContext, M: CostModel>(
+    function: &Code,
+    module: &C,
+    cost_model: &M,
 ) -> TransformationResult<Code> {
     // At the beginning of a function, we charge for its invocation and the first
     // unconditionally executed instructions of the body and account for its maximum
     // stack size.
     let num_params: u32 = function.ty.parameters.len().try_into()?;
     let energy =
-        cost::invoke_after(function.num_locals.checked_sub(num_params).ok_or_else(|| {
+        cost_model.invoke_after(function.num_locals.checked_sub(num_params).ok_or_else(|| {
             anyhow!(
                 "Precondition violation. Number of locals is less than the number of parameters."
             )
@@ -645,6 +725,7 @@ pub(crate) fn inject_accounting<C: HasTransformationContext>(
     let labels = vec![BlockType::from(function.ty.result)];
     let mut transformer = InstrSeqTransformer {
         module,
+        cost_model,
         labels,
         new_seq: InstrSeq::new(),
         energy,
@@ -701,8 +782,18 @@ impl<'a> HasTransformationContext for ModuleContext<'a> {
 }
 
 impl Module {
-    /// Add metering instructions to the module.
+    /// Add metering instructions to the module, using the
+    /// [`DefaultCostModel`].
     pub fn inject_metering(&mut self) -> TransformationResult<()> {
+        self.inject_metering_with(&DefaultCostModel)
+    }
+
+    /// Add metering instructions to the module, pricing instructions according
+    /// to the given cost model.
+    pub fn inject_metering_with(
+        &mut self,
+        cost_model: &impl CostModel,
+    ) -> TransformationResult<()> {
         // Update the elements to account for the inserted imports.
         for elem in self.element.elements.iter_mut() {
             for init in elem.inits.iter_mut() {
@@ -715,7 +806,7 @@ impl Module {
             imported: &self.import.imports,
         };
         for code in self.code.impls.iter_mut() {
-            let injected_code = inject_accounting(code, &ctx)?;
+            let injected_code = inject_accounting_with(code, &ctx, cost_model)?;
             *code = injected_code;
         }
 
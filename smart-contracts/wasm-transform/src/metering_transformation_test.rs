@@ -111,6 +111,7 @@ impl HasTransformationContext for TransformationContext {
 const MEMARG: MemArg = MemArg {
     offset: 0,
     align:  0,
+    memory: 0,
 };
 
 /// Examplary function's stack size for `test_body` test cases.
@@ -1001,3 +1002,118 @@ fn test_memory_grow() {
         ]],
     )
 }
+
+/// A cost model that charges the given extra amount for each call on top of
+/// the default cost.
+struct ExpensiveCalls {
+    extra: Energy,
+}
+
+impl CostModel for ExpensiveCalls {
+    fn get_cost<C: HasTransformationContext>(
+        &self,
+        instr: &OpCode,
+        labels: &[BlockType],
+        module: &C,
+    ) -> TransformationResult<Energy> {
+        let cost = DefaultCostModel.get_cost(instr, labels, module)?;
+        match instr {
+            Call(_) | CallIndirect(_) => Ok(cost + self.extra),
+            _ => Ok(cost),
+        }
+    }
+
+    fn branch(&self, label_arity: usize) -> Energy { DefaultCostModel.branch(label_arity) }
+
+    fn invoke_after(&self, num_locals: u32) -> Energy { DefaultCostModel.invoke_after(num_locals) }
+}
+
+/// Sum up all the energy charged by the instruction sequence.
+fn total_energy(instrs: &[OpCode]) -> Energy {
+    instrs
+        .windows(2)
+        .map(|w| match (&w[0], &w[1]) {
+            (I64Const(e), Call(FN_IDX_ACCOUNT_ENERGY)) => *e as Energy,
+            _ => 0,
+        })
+        .sum()
+}
+
+#[test]
+fn test_cost_model_call_cost() {
+    let ctx = TransformationContext {
+        types: Vec::new(),
+        funcs: vec![FunctionType::empty()],
+    };
+    let num_calls = 5;
+    let mut instrs = vec![Call(0); num_calls];
+    instrs.push(End);
+    let f = Code {
+        locals:     mk_locals(&[I32, I64]),
+        ty_idx:     0,
+        expr:       Expression::from(instrs),
+        ty:         Rc::new(FunctionType::empty()),
+        num_locals: 2,
+    };
+    let default = inject_accounting(&f, &ctx).unwrap();
+    assert_eq!(
+        inject_accounting_with(&f, &ctx, &DefaultCostModel).unwrap().expr.instrs,
+        default.expr.instrs,
+        "Using the default cost model explicitly should not change the result."
+    );
+    let extra = 100;
+    let expensive = inject_accounting_with(&f, &ctx, &ExpensiveCalls {
+        extra,
+    })
+    .unwrap();
+    assert_eq!(
+        total_energy(&expensive.expr.instrs),
+        total_energy(&default.expr.instrs) + num_calls as Energy * extra,
+        "Each call should be charged the extra cost."
+    );
+}
+
+#[test]
+fn test_instruction_cost_matches_metering() {
+    let ctx = TransformationContext {
+        types: Vec::new(),
+        funcs: vec![FunctionType {
+            parameters: vec![I32],
+            result:     Some(I64),
+        }],
+    };
+    let instrs = vec![
+        LocalGet(0),
+        I32Const(1),
+        I32Add,
+        Call(0),
+        Block(EmptyType),
+        I64Const(2),
+        I64Mul,
+        I64Store(MEMARG),
+        End,
+        Return,
+        End,
+    ];
+    let f = Code {
+        locals:     mk_locals(&[I32, I64]),
+        ty_idx:     0,
+        expr:       Expression::from(instrs.clone()),
+        ty:         Rc::new(FunctionType {
+            parameters: vec![I32],
+            result:     None,
+        }),
+        num_locals: 3,
+    };
+    // The block is not a branch target, so the label in scope is always the
+    // return type of the function.
+    let labels = [EmptyType];
+    let summed: Energy =
+        instrs.iter().map(|instr| instruction_cost(instr, &labels, &ctx).unwrap()).sum();
+    let metered = inject_accounting(&f, &ctx).unwrap();
+    assert_eq!(
+        total_energy(&metered.expr.instrs),
+        summed + invoke_after(2),
+        "The instruction costs should add up to the energy charged by metering."
+    );
+}
@@ -3,11 +3,12 @@
 use crate::{
     constants::{MAGIC_HASH, VERSION},
     parse::{Byte, SectionId, Skeleton, UnparsedSection},
-    types::{BlockType, CustomSection, FunctionType, Name, ValueType},
+    types::*,
 };
 use std::{
     convert::{TryFrom, TryInto},
     io::Write,
+    rc::Rc,
 };
 
 /// Result of an output. Outputting a module can fail if the [writer](Write)
@@ -181,6 +182,404 @@ impl<'a> Output for Skeleton<'a> {
     }
 }
 
+impl<A: Output> Output for Rc<A> {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> { self.as_ref().output(out) }
+}
+
+/// The byte used to signal the end of an instruction sequence.
+const END: Byte = 0x0B;
+
+impl Output for Limits {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        match self.max {
+            None => {
+                0x00u8.output(out)?;
+                self.min.output(out)
+            }
+            Some(max) => {
+                0x01u8.output(out)?;
+                self.min.output(out)?;
+                max.output(out)
+            }
+        }
+    }
+}
+
+/// Table types are always of the funcref type in the version of Wasm we
+/// support.
+impl Output for TableType {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        0x70u8.output(out)?;
+        self.limits.output(out)
+    }
+}
+
+impl Output for MemoryType {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> { self.limits.output(out) }
+}
+
+impl Output for ImportDescription {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        match self {
+            ImportDescription::Func {
+                type_idx,
+            } => {
+                0x00u8.output(out)?;
+                type_idx.output(out)
+            }
+        }
+    }
+}
+
+impl Output for Import {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        self.mod_name.output(out)?;
+        self.item_name.output(out)?;
+        self.description.output(out)
+    }
+}
+
+/// Tables and memories are always output with the implicit index 0.
+impl Output for ExportDescription {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        match self {
+            ExportDescription::Func {
+                index,
+            } => {
+                0x00u8.output(out)?;
+                index.output(out)
+            }
+            ExportDescription::Table => {
+                0x01u8.output(out)?;
+                0u32.output(out)
+            }
+            ExportDescription::Memory => {
+                0x02u8.output(out)?;
+                0u32.output(out)
+            }
+            ExportDescription::Global {
+                index,
+            } => {
+                0x03u8.output(out)?;
+                index.output(out)
+            }
+        }
+    }
+}
+
+impl Output for Export {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        self.name.output(out)?;
+        self.description.output(out)
+    }
+}
+
+/// Write a constant expression, including the terminating `end` byte. Note
+/// that the [`GlobalInit`] type has its own [`Output`] instance for the
+/// artifact format, which is different from the Wasm binary format used here.
+fn output_constant_expr(init: GlobalInit, out: &mut impl Write) -> OutResult<()> {
+    match init {
+        GlobalInit::I32(x) => OpCode::I32Const(x).output(out)?,
+        GlobalInit::I64(x) => OpCode::I64Const(x).output(out)?,
+    }
+    END.output(out)
+}
+
+impl Output for Global {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        ValueType::from(self).output(out)?;
+        if self.mutable {
+            0x01u8.output(out)?;
+        } else {
+            0x00u8.output(out)?;
+        }
+        output_constant_expr(self.init, out)
+    }
+}
+
+/// Elements are output with the implicit table index 0.
+impl Output for Element {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        0u32.output(out)?;
+        output_constant_expr(GlobalInit::I32(self.offset), out)?;
+        self.inits.output(out)
+    }
+}
+
+impl Output for Data {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        self.memory.output(out)?;
+        output_constant_expr(GlobalInit::I32(self.offset), out)?;
+        let len: u32 = self.init.len().try_into()?;
+        len.output(out)?;
+        out.write_all(&self.init)?;
+        Ok(())
+    }
+}
+
+/// Alignment is written before the offset, as in the Wasm specification. The
+/// memory index is only written if it is not 0, using the encoding of the
+/// multi-memory proposal.
+impl Output for MemArg {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        if self.memory == 0 {
+            self.align.output(out)?;
+        } else {
+            (self.align | 0x40).output(out)?;
+            self.memory.output(out)?;
+        }
+        self.offset.output(out)
+    }
+}
+
+impl Output for Local {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        self.multiplicity.output(out)?;
+        self.ty.output(out)
+    }
+}
+
+impl Output for OpCode {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        match self {
+            OpCode::End => END.output(out),
+            OpCode::Nop => 0x01u8.output(out),
+            OpCode::Unreachable => 0x00u8.output(out),
+            OpCode::Block(bt) => {
+                0x02u8.output(out)?;
+                bt.output(out)
+            }
+            OpCode::Loop(bt) => {
+                0x03u8.output(out)?;
+                bt.output(out)
+            }
+            OpCode::If {
+                ty,
+            } => {
+                0x04u8.output(out)?;
+                ty.output(out)
+            }
+            OpCode::Else => 0x05u8.output(out),
+            OpCode::Br(l) => {
+                0x0Cu8.output(out)?;
+                l.output(out)
+            }
+            OpCode::BrIf(l) => {
+                0x0Du8.output(out)?;
+                l.output(out)
+            }
+            OpCode::BrTable {
+                labels,
+                default,
+            } => {
+                0x0Eu8.output(out)?;
+                labels.output(out)?;
+                default.output(out)
+            }
+            OpCode::Return => 0x0Fu8.output(out),
+            OpCode::Call(idx) => {
+                0x10u8.output(out)?;
+                idx.output(out)
+            }
+            OpCode::CallIndirect(ty) => {
+                0x11u8.output(out)?;
+                ty.output(out)?;
+                0x00u8.output(out)
+            }
+            OpCode::Drop => 0x1Au8.output(out),
+            OpCode::Select => 0x1Bu8.output(out),
+            OpCode::LocalGet(idx) => output_with_immediate(0x20, idx, out),
+            OpCode::LocalSet(idx) => output_with_immediate(0x21, idx, out),
+            OpCode::LocalTee(idx) => output_with_immediate(0x22, idx, out),
+            OpCode::GlobalGet(idx) => output_with_immediate(0x23, idx, out),
+            OpCode::GlobalSet(idx) => output_with_immediate(0x24, idx, out),
+            OpCode::I32Load(memarg) => output_with_immediate(0x28, memarg, out),
+            OpCode::I64Load(memarg) => output_with_immediate(0x29, memarg, out),
+            OpCode::I32Load8S(memarg) => output_with_immediate(0x2C, memarg, out),
+            OpCode::I32Load8U(memarg) => output_with_immediate(0x2D, memarg, out),
+            OpCode::I32Load16S(memarg) => output_with_immediate(0x2E, memarg, out),
+            OpCode::I32Load16U(memarg) => output_with_immediate(0x2F, memarg, out),
+            OpCode::I64Load8S(memarg) => output_with_immediate(0x30, memarg, out),
+            OpCode::I64Load8U(memarg) => output_with_immediate(0x31, memarg, out),
+            OpCode::I64Load16S(memarg) => output_with_immediate(0x32, memarg, out),
+            OpCode::I64Load16U(memarg) => output_with_immediate(0x33, memarg, out),
+            OpCode::I64Load32S(memarg) => output_with_immediate(0x34, memarg, out),
+            OpCode::I64Load32U(memarg) => output_with_immediate(0x35, memarg, out),
+            OpCode::I32Store(memarg) => output_with_immediate(0x36, memarg, out),
+            OpCode::I64Store(memarg) => output_with_immediate(0x37, memarg, out),
+            OpCode::I32Store8(memarg) => output_with_immediate(0x3A, memarg, out),
+            OpCode::I32Store16(memarg) => output_with_immediate(0x3B, memarg, out),
+            OpCode::I64Store8(memarg) => output_with_immediate(0x3C, memarg, out),
+            OpCode::I64Store16(memarg) => output_with_immediate(0x3D, memarg, out),
+            OpCode::I64Store32(memarg) => output_with_immediate(0x3E, memarg, out),
+            OpCode::MemorySize => output_with_immediate(0x3F, &0x00u8, out),
+            OpCode::MemoryGrow => output_with_immediate(0x40, &0x00u8, out),
+            OpCode::I32Const(n) => output_with_immediate(0x41, n, out),
+            OpCode::I64Const(n) => output_with_immediate(0x42, n, out),
+            OpCode::I32Eqz => 0x45u8.output(out),
+            OpCode::I32Eq => 0x46u8.output(out),
+            OpCode::I32Ne => 0x47u8.output(out),
+            OpCode::I32LtS => 0x48u8.output(out),
+            OpCode::I32LtU => 0x49u8.output(out),
+            OpCode::I32GtS => 0x4Au8.output(out),
+            OpCode::I32GtU => 0x4Bu8.output(out),
+            OpCode::I32LeS => 0x4Cu8.output(out),
+            OpCode::I32LeU => 0x4Du8.output(out),
+            OpCode::I32GeS => 0x4Eu8.output(out),
+            OpCode::I32GeU => 0x4Fu8.output(out),
+            OpCode::I64Eqz => 0x50u8.output(out),
+            OpCode::I64Eq => 0x51u8.output(out),
+            OpCode::I64Ne => 0x52u8.output(out),
+            OpCode::I64LtS => 0x53u8.output(out),
+            OpCode::I64LtU => 0x54u8.output(out),
+            OpCode::I64GtS => 0x55u8.output(out),
+            OpCode::I64GtU => 0x56u8.output(out),
+            OpCode::I64LeS => 0x57u8.output(out),
+            OpCode::I64LeU => 0x58u8.output(out),
+            OpCode::I64GeS => 0x59u8.output(out),
+            OpCode::I64GeU => 0x5Au8.output(out),
+            OpCode::I32Clz => 0x67u8.output(out),
+            OpCode::I32Ctz => 0x68u8.output(out),
+            OpCode::I32Popcnt => 0x69u8.output(out),
+            OpCode::I32Add => 0x6Au8.output(out),
+            OpCode::I32Sub => 0x6Bu8.output(out),
+            OpCode::I32Mul => 0x6Cu8.output(out),
+            OpCode::I32DivS => 0x6Du8.output(out),
+            OpCode::I32DivU => 0x6Eu8.output(out),
+            OpCode::I32RemS => 0x6Fu8.output(out),
+            OpCode::I32RemU => 0x70u8.output(out),
+            OpCode::I32And => 0x71u8.output(out),
+            OpCode::I32Or => 0x72u8.output(out),
+            OpCode::I32Xor => 0x73u8.output(out),
+            OpCode::I32Shl => 0x74u8.output(out),
+            OpCode::I32ShrS => 0x75u8.output(out),
+            OpCode::I32ShrU => 0x76u8.output(out),
+            OpCode::I32Rotl => 0x77u8.output(out),
+            OpCode::I32Rotr => 0x78u8.output(out),
+            OpCode::I64Clz => 0x79u8.output(out),
+            OpCode::I64Ctz => 0x7Au8.output(out),
+            OpCode::I64Popcnt => 0x7Bu8.output(out),
+            OpCode::I64Add => 0x7Cu8.output(out),
+            OpCode::I64Sub => 0x7Du8.output(out),
+            OpCode::I64Mul => 0x7Eu8.output(out),
+            OpCode::I64DivS => 0x7Fu8.output(out),
+            OpCode::I64DivU => 0x80u8.output(out),
+            OpCode::I64RemS => 0x81u8.output(out),
+            OpCode::I64RemU => 0x82u8.output(out),
+            OpCode::I64And => 0x83u8.output(out),
+            OpCode::I64Or => 0x84u8.output(out),
+            OpCode::I64Xor => 0x85u8.output(out),
+            OpCode::I64Shl => 0x86u8.output(out),
+            OpCode::I64ShrS => 0x87u8.output(out),
+            OpCode::I64ShrU => 0x88u8.output(out),
+            OpCode::I64Rotl => 0x89u8.output(out),
+            OpCode::I64Rotr => 0x8Au8.output(out),
+            OpCode::I32WrapI64 => 0xA7u8.output(out),
+            OpCode::I64ExtendI32S => 0xACu8.output(out),
+            OpCode::I64ExtendI32U => 0xADu8.output(out),
+            OpCode::I32Extend8S => 0xC0u8.output(out),
+            OpCode::I32Extend16S => 0xC1u8.output(out),
+            OpCode::I64Extend8S => 0xC2u8.output(out),
+            OpCode::I64Extend16S => 0xC3u8.output(out),
+            OpCode::I64Extend32S => 0xC4u8.output(out),
+        }
+    }
+}
+
+/// Output an instruction byte followed by its immediate argument.
+fn output_with_immediate(
+    opcode: Byte,
+    immediate: &impl Output,
+    out: &mut impl Write,
+) -> OutResult<()> {
+    opcode.output(out)?;
+    immediate.output(out)
+}
+
+/// The body of the function is prefixed by its size in bytes. The instruction
+/// sequence of a parsed function already contains the terminating `end`
+/// instruction, so it is not added here.
+impl Output for Code {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        let mut body = Vec::new();
+        self.locals.output(&mut body)?;
+        for instr in self.expr.instrs.iter() {
+            instr.output(&mut body)?;
+        }
+        let size: u32 = body.len().try_into()?;
+        size.output(out)?;
+        out.write_all(&body)?;
+        Ok(())
+    }
+}
+
+/// Output a section with the given id. The contents are first written into a
+/// temporary buffer so that the length of the section can be determined.
+fn output_section(
+    out: &mut impl Write,
+    section_id: SectionId,
+    contents: impl FnOnce(&mut Vec<u8>) -> OutResult<()>,
+) -> OutResult<()> {
+    let mut bytes = Vec::new();
+    contents(&mut bytes)?;
+    out.write_all(&[section_id as u8])?;
+    let len = u32::try_from(bytes.len())?;
+    len.output(out)?;
+    out.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Write out the module in the Wasm binary format. Sections are written in the
+/// canonical order, and empty sections are omitted. Since the module does not
+/// retain custom sections, they are not part of the output.
+///
+/// The output is a valid Wasm module, but it is not necessarily byte-for-byte
+/// equal to the module that was parsed. Integers are written in the minimal
+/// LEB128 encoding, and global references in offset expressions of elements
+/// and data segments are replaced by the constants they refer to.
+impl Output for Module {
+    fn output(&self, out: &mut impl Write) -> OutResult<()> {
+        out.write_all(&MAGIC_HASH)?;
+        out.write_all(&VERSION)?;
+        if !self.ty.types.is_empty() {
+            output_section(out, SectionId::Type, |o| self.ty.types.output(o))?;
+        }
+        if !self.import.imports.is_empty() {
+            output_section(out, SectionId::Import, |o| self.import.imports.output(o))?;
+        }
+        if !self.func.types.is_empty() {
+            output_section(out, SectionId::Function, |o| self.func.types.output(o))?;
+        }
+        if let Some(ref table_type) = self.table.table_type {
+            output_section(out, SectionId::Table, |o| std::slice::from_ref(table_type).output(o))?;
+        }
+        if let Some(memory_type) = self.memory.memory_type {
+            output_section(out, SectionId::Memory, |o| {
+                let mut memory_types = vec![memory_type];
+                memory_types.extend_from_slice(&self.memory.additional_memory_types);
+                memory_types.output(o)
+            })?;
+        }
+        if !self.global.globals.is_empty() {
+            output_section(out, SectionId::Global, |o| self.global.globals.output(o))?;
+        }
+        if !self.export.exports.is_empty() {
+            output_section(out, SectionId::Export, |o| self.export.exports.output(o))?;
+        }
+        // Start sections are not supported, so the start section is never written.
+        if !self.element.elements.is_empty() {
+            output_section(out, SectionId::Element, |o| self.element.elements.output(o))?;
+        }
+        if !self.code.impls.is_empty() {
+            output_section(out, SectionId::Code, |o| self.code.impls.output(o))?;
+        }
+        if !self.data.sections.is_empty() {
+            output_section(out, SectionId::Data, |o| self.data.sections.output(o))?;
+        }
+        Ok(())
+    }
+}
+
 /// Output a custom section into the given writer.
 /// If the writer already contains a valid Wasm module then a custom section can
 /// always be appended, and it will result in another valid Wasm module.
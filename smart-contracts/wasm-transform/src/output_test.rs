@@ -0,0 +1,93 @@
+//! Tests for outputting parsed modules back into the Wasm binary format.
+use crate::{
+    output::Output,
+    parse::parse_skeleton,
+    types::{FunctionType, Module, Name},
+    validate::{validate_module, ValidateImportExport},
+};
+
+/// Allow all imports and exports so that the tests only exercise the
+/// structure of the module.
+pub(crate) struct AllowAll;
+
+impl ValidateImportExport for AllowAll {
+    fn validate_import_function(
+        &self,
+        _duplicate: bool,
+        _mod_name: &Name,
+        _item_name: &Name,
+        _ty: &FunctionType,
+    ) -> bool {
+        true
+    }
+
+    fn validate_export_function(&self, _item_name: &Name, _ty: &FunctionType) -> bool { true }
+}
+
+/// A canonically encoded module with all the sections we support, apart from
+/// the start and custom sections.
+pub(crate) const MODULE: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // magic hash and version
+    0x01, 0x09, 0x02, 0x60, 0x01, 0x7F, 0x01, 0x7F, 0x60, 0x00, 0x00, // types
+    0x02, 0x07, 0x01, 0x01, 0x6D, 0x01, 0x66, 0x00, 0x01, // imports
+    0x03, 0x02, 0x01, 0x00, // functions
+    0x04, 0x04, 0x01, 0x70, 0x00, 0x01, // table
+    0x05, 0x04, 0x01, 0x01, 0x01, 0x02, // memory
+    0x06, 0x06, 0x01, 0x7F, 0x01, 0x41, 0x2A, 0x0B, // globals
+    0x07, 0x0B, 0x02, 0x01, 0x67, 0x00, 0x01, 0x03, 0x6D, 0x65, 0x6D, 0x02, 0x00, // exports
+    0x09, 0x07, 0x01, 0x00, 0x41, 0x00, 0x0B, 0x01, 0x01, // elements
+    0x0A, 0x0B, 0x01, 0x09, 0x01, 0x01, 0x7E, 0x20, 0x00, 0x41, 0x01, 0x6A, 0x0B, // code
+    0x0B, 0x08, 0x01, 0x00, 0x41, 0x00, 0x0B, 0x02, 0x01, 0x02, // data
+];
+
+pub(crate) fn parse_module(bytes: &[u8]) -> Module {
+    let skeleton = parse_skeleton(bytes).expect("Module should parse.");
+    validate_module(&AllowAll, &skeleton).expect("Module should validate.")
+}
+
+#[test]
+fn module_output_roundtrip() {
+    let module = parse_module(MODULE);
+    let mut out = Vec::new();
+    module.output(&mut out).expect("Outputting to a vector should succeed.");
+    assert_eq!(out, MODULE, "Outputting a parsed module should give back the original bytes.");
+}
+
+#[test]
+fn module_output_metered() {
+    // Outputting a module with injected metering should give a module that
+    // can be parsed and validated again, and output to the same bytes.
+    let mut module = parse_module(MODULE);
+    module.inject_metering().expect("Metering injection should succeed.");
+    let mut out = Vec::new();
+    module.output(&mut out).expect("Outputting to a vector should succeed.");
+    let reparsed = parse_module(&out);
+    let mut out_again = Vec::new();
+    reparsed.output(&mut out_again).expect("Outputting to a vector should succeed.");
+    assert_eq!(out, out_again, "Outputting the reparsed module should give the same bytes.");
+}
+
+#[test]
+fn module_to_wat() {
+    let module = parse_module(MODULE);
+    let wat = module.to_wat();
+    assert!(wat.starts_with("(module\n"), "Module should be printed as a module: {}", wat);
+    assert!(
+        wat.contains("(import \"m\" \"f\" (func (;0;) (type 1)))"),
+        "Imports should be printed: {}",
+        wat
+    );
+    assert!(
+        wat.contains(
+            "(func (;1;) (type 0) (param i32) (result i32)\n    (local i64)\n    local.get 0\n    \
+             i32.const 1\n    i32.add)"
+        ),
+        "Function bodies should be printed: {}",
+        wat
+    );
+    assert!(
+        wat.contains("(data (;0;) (i32.const 0) \"\\01\\02\")"),
+        "Data should be printed: {}",
+        wat
+    );
+}
@@ -15,7 +15,7 @@
 //!
 //! In the second stage each section can be parsed into a proper structure.
 use crate::{constants::*, types::*};
-use anyhow::{bail, ensure};
+use anyhow::{bail, ensure, Context};
 use std::{
     convert::TryFrom,
     io::{Cursor, Read, Seek, SeekFrom},
@@ -31,8 +31,14 @@ pub type Byte = u8;
 /// the length. The lifetime is the lifetime of the original byte array this
 /// section was carved from.
 pub struct UnparsedSection<'a> {
-    pub section_id: SectionId,
-    pub bytes:      &'a [u8],
+    pub section_id:     SectionId,
+    pub bytes:          &'a [u8],
+    /// Position of the section ID byte in the input the section was parsed
+    /// from.
+    pub start_offset:   usize,
+    /// Position of the first byte of the section contents, i.e., of `bytes`,
+    /// in the input the section was parsed from.
+    pub payload_offset: usize,
 }
 
 #[derive(Ord, PartialOrd, Eq, PartialEq, Clone, Copy, Debug)]
@@ -80,9 +86,53 @@ pub struct Skeleton<'a> {
     pub data:    Option<UnparsedSection<'a>>,
     /// A list of custom sections in the order they appeared in the input.
     pub custom:  Vec<UnparsedSection<'a>>,
+    /// The limits the module was parsed with. These are also used when parsing
+    /// the individual sections.
+    config:      ParseConfig,
 }
 
-/// Auxiliary type alias used by all the parsing functions.
+impl<'a> Skeleton<'a> {
+    /// The limits the module was parsed with. These are also used when parsing
+    /// the individual sections.
+    pub fn config(&self) -> &ParseConfig { &self.config }
+
+    /// Get the contents of the section with the given ID, if it is present.
+    /// The returned bytes **do not** include the section ID and the length
+    /// prefix. Since there can be any number of custom sections this always
+    /// returns `None` for [`SectionId::Custom`].
+    pub fn section_bytes(&self, id: SectionId) -> Option<&'a [u8]> {
+        let section = match id {
+            SectionId::Custom => return None,
+            SectionId::Type => &self.ty,
+            SectionId::Import => &self.import,
+            SectionId::Function => &self.func,
+            SectionId::Table => &self.table,
+            SectionId::Memory => &self.memory,
+            SectionId::Global => &self.global,
+            SectionId::Export => &self.export,
+            SectionId::Start => &self.start,
+            SectionId::Element => &self.element,
+            SectionId::Code => &self.code,
+            SectionId::Data => &self.data,
+        };
+        section.as_ref().map(|s| s.bytes)
+    }
+
+    /// Feed the contents of the section with the given ID, as returned by
+    /// [`section_bytes`](Self::section_bytes), to the hasher. Returns whether
+    /// the section was present. If it was not, the hasher is not updated.
+    pub fn hash_section<H: std::hash::Hasher>(&self, id: SectionId, hasher: &mut H) -> bool {
+        if let Some(bytes) = self.section_bytes(id) {
+            hasher.write(bytes);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Auxiliary type alias used by all the parsing functions. Errors specific to
+/// the Wasm format are reported as [`ParseError`] values.
 pub type ParseResult<A> = anyhow::Result<A>;
 
 /// A trait for parsing data. The lifetime and context are useful when we want
@@ -101,6 +151,28 @@ pub trait Parseable<'a, Ctx>: Sized {
 /// An empty context used when we can parse data without an additional context.
 pub(crate) const EMPTY_CTX: () = ();
 
+/// A context for parsing, which determines how much memory may be preallocated
+/// for vectors whose length is declared in the input.
+pub trait ParseContext: Copy {
+    /// Maximum number of bytes to preallocate for a vector.
+    fn max_prealloc_bytes(self) -> usize;
+}
+
+/// The empty context uses the default bound of [`MAX_PREALLOCATED_BYTES`].
+impl ParseContext for () {
+    fn max_prealloc_bytes(self) -> usize { MAX_PREALLOCATED_BYTES }
+}
+
+impl<'b> ParseContext for &'b ParseConfig {
+    fn max_prealloc_bytes(self) -> usize { self.max_prealloc_bytes }
+}
+
+/// The context for parsing element and data segments, whose offsets may refer
+/// to globals.
+impl<'b> ParseContext for (&'b GlobalSection, &'b ParseConfig) {
+    fn max_prealloc_bytes(self) -> usize { self.1.max_prealloc_bytes }
+}
+
 /// A helper trait for more convenient use. The difference from [`Parseable`] is
 /// that typically the result type is determined by the context, which we take
 /// advantage of to reduce the need for typing annotations which would be needed
@@ -132,7 +204,7 @@ impl<'a, Ctx, A: Parseable<'a, Ctx>> GetParseable<A, Ctx> for &'a [u8] {
     fn next(self, ctx: Ctx) -> ParseResult<A> {
         let mut cursor = Cursor::new(self);
         let res = A::parse(ctx, &mut cursor)?;
-        ensure!(cursor.position() == self.len() as u64, "Not all of the contents was consumed.");
+        ensure!(cursor.position() == self.len() as u64, ParseError::DeclaredSizeMismatch);
         Ok(res)
     }
 }
@@ -200,7 +272,9 @@ impl<'a, Ctx> Parseable<'a, Ctx> for SectionId {
             9 => Ok(Element),
             10 => Ok(Code),
             11 => Ok(Data),
-            id => bail!("Unknown section id {}", id),
+            id => bail!(ParseError::UnknownSectionId {
+                id
+            }),
         }
     }
 }
@@ -208,11 +282,13 @@ impl<'a, Ctx> Parseable<'a, Ctx> for SectionId {
 /// Parse a vector of elements according to the Wasm specification.
 /// Specifically this is parsed by reading the length as a u32 and then reading
 /// that many elements.
-impl<'a, Ctx: Copy, A: Parseable<'a, Ctx>> Parseable<'a, Ctx> for Vec<A> {
+///
+/// Preallocation is bounded by the context, see [`ParseContext`].
+impl<'a, Ctx: ParseContext, A: Parseable<'a, Ctx>> Parseable<'a, Ctx> for Vec<A> {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let len = u32::parse(ctx, cursor)?;
         let max_initial_capacity =
-            MAX_PREALLOCATED_BYTES / std::cmp::max(1, std::mem::size_of::<A>());
+            ctx.max_prealloc_bytes() / std::cmp::max(1, std::mem::size_of::<A>());
         let mut out = Vec::with_capacity(std::cmp::min(len as usize, max_initial_capacity));
         for _ in 0..len {
             out.push(cursor.next(ctx)?)
@@ -228,19 +304,21 @@ impl<'a, Ctx> Parseable<'a, Ctx> for &'a [u8] {
         let len = u32::parse(ctx, cursor)?;
         let pos = cursor.position() as usize;
         let end = pos + len as usize;
-        ensure!(end <= cursor.get_ref().len(), "Malformed byte array");
+        ensure!(end <= cursor.get_ref().len(), ParseError::MalformedByteArray);
         cursor.seek(SeekFrom::Current(i64::from(len)))?;
         Ok(&cursor.get_ref()[pos..end])
     }
 }
 
 /// Special case of a vector where we only expect 0 or 1 elements.
-impl<'a, Ctx: Copy, A: Parseable<'a, Ctx>> Parseable<'a, Ctx> for Option<A> {
+impl<'a, Ctx: ParseContext, A: Parseable<'a, Ctx>> Parseable<'a, Ctx> for Option<A> {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         match Byte::parse(ctx, cursor)? {
             0u8 => Ok(None),
             1u8 => Ok(Some(cursor.next(ctx)?)),
-            tag => bail!("Unsupported option tag: {:#04x}", tag),
+            tag => bail!(ParseError::UnsupportedOptionTag {
+                tag
+            }),
         }
     }
 }
@@ -252,7 +330,7 @@ impl<'a, Ctx> Parseable<'a, Ctx> for &'a [ValueType] {
         let len = u32::parse(ctx, cursor)?;
         let pos = cursor.position() as usize;
         let end = pos + len as usize;
-        ensure!(end <= cursor.get_ref().len(), "Malformed byte array");
+        ensure!(end <= cursor.get_ref().len(), ParseError::MalformedByteArray);
         cursor.seek(SeekFrom::Current(i64::from(len)))?;
         let bytes = &cursor.get_ref()[pos..end];
         for &byte in bytes {
@@ -268,17 +346,60 @@ impl<'a, Ctx> Parseable<'a, Ctx> for &'a [ValueType] {
 
 /// Parse a section skeleton, which consists of parsing the section ID
 /// and recording the boundaries of it.
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for UnparsedSection<'a> {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for UnparsedSection<'a> {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
+        let start_offset = cursor.position() as usize;
         let section_id = cursor.next(ctx)?;
-        let bytes = cursor.next(ctx)?;
+        let bytes: &'a [u8] = cursor.next(ctx)?;
+        // The cursor is now positioned just after the section contents.
+        let payload_offset = cursor.position() as usize - bytes.len();
         Ok(UnparsedSection {
             section_id,
             bytes,
+            start_offset,
+            payload_offset,
         })
     }
 }
 
+#[derive(Debug, Clone, Copy)]
+/// Limits applied when parsing a module into a [`Skeleton`] or an
+/// [`OwnedSkeleton`].
+///
+/// The [`Default`] instance uses [`MAX_PREALLOCATED_BYTES`] and imposes no
+/// limit on the number of custom sections.
+pub struct ParseConfig {
+    /// Maximum number of bytes to preallocate when buffering a section, or
+    /// parsing a vector, whose length is declared in the input.
+    pub max_prealloc_bytes: usize,
+    /// Maximum number of custom sections, if any. Since custom sections can be
+    /// arbitrarily small and appear anywhere this bounds the memory used for
+    /// recording them.
+    pub max_sections:       Option<usize>,
+}
+
+impl Default for ParseConfig {
+    fn default() -> Self {
+        Self {
+            max_prealloc_bytes: MAX_PREALLOCATED_BYTES,
+            max_sections:       None,
+        }
+    }
+}
+
+impl ParseConfig {
+    /// Ensure that another custom section can be recorded, given the number of
+    /// custom sections already recorded.
+    fn check_custom_sections(&self, num_custom: usize) -> ParseResult<()> {
+        if let Some(max) = self.max_sections {
+            ensure!(num_custom < max, ParseError::TooManyCustomSections {
+                max
+            });
+        }
+        Ok(())
+    }
+}
+
 /// Try to parse the input as a Wasm module in binary format. This function
 /// ensures
 ///
@@ -286,17 +407,24 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for UnparsedSection<'a> {
 /// - version is correct
 /// - sections are in the correct order
 /// - all input is consumed.
+///
+/// This is [`parse_skeleton_with`] with the default [`ParseConfig`].
 pub fn parse_skeleton(input: &[u8]) -> ParseResult<Skeleton<'_>> {
+    parse_skeleton_with(input, &ParseConfig::default())
+}
+
+/// Same as [`parse_skeleton`], but with the limits given by the configuration.
+pub fn parse_skeleton_with<'a>(input: &'a [u8], config: &ParseConfig) -> ParseResult<Skeleton<'a>> {
     let cursor = &mut Cursor::new(input);
     {
         // check magic hash and version
         let mut buf = [0u8; 4];
         cursor.read_exact(&mut buf)?;
         // ensure magic hash
-        ensure!(buf == MAGIC_HASH, "Unknown magic hash");
+        ensure!(buf == MAGIC_HASH, ParseError::UnknownMagicHash);
         cursor.read_exact(&mut buf)?;
         // ensure module version.
-        ensure!(buf == VERSION, "Unsupported version.");
+        ensure!(buf == VERSION, ParseError::UnsupportedVersion);
     }
     let mut last_section = SectionId::Custom;
 
@@ -317,15 +445,12 @@ pub fn parse_skeleton(input: &[u8]) -> ParseResult<Skeleton<'_>> {
     // terminate
     while cursor.position() < input.len() as u64 {
         let section = UnparsedSection::parse(EMPTY_CTX, cursor)?;
-        ensure!(
-            section.section_id == SectionId::Custom || section.section_id > last_section,
-            "Section out of place."
-        );
-        if section.section_id != SectionId::Custom {
-            last_section = section.section_id
-        }
+        check_section_order(&mut last_section, section.section_id)?;
         match section.section_id {
-            SectionId::Custom => custom.push(section),
+            SectionId::Custom => {
+                config.check_custom_sections(custom.len())?;
+                custom.push(section)
+            }
             SectionId::Type => ty = Some(section),
             SectionId::Import => import = Some(section),
             SectionId::Function => func = Some(section),
@@ -340,7 +465,7 @@ pub fn parse_skeleton(input: &[u8]) -> ParseResult<Skeleton<'_>> {
         }
     }
     // make sure we've read all the input
-    ensure!(cursor.position() as usize == input.len(), "Leftover bytes.");
+    ensure!(cursor.position() as usize == input.len(), ParseError::TrailingBytes);
     Ok(Skeleton {
         ty,
         import,
@@ -354,6 +479,218 @@ pub fn parse_skeleton(input: &[u8]) -> ParseResult<Skeleton<'_>> {
         code,
         data,
         custom,
+        config: *config,
+    })
+}
+
+/// Ensure that a section with the given ID may follow the last non-custom
+/// section, and record it as the last section if it is not a custom one.
+/// Custom sections may appear anywhere.
+fn check_section_order(last_section: &mut SectionId, section_id: SectionId) -> ParseResult<()> {
+    ensure!(
+        section_id == SectionId::Custom || section_id > *last_section,
+        ParseError::SectionOutOfOrder
+    );
+    if section_id != SectionId::Custom {
+        *last_section = section_id
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+/// An owned variant of [`UnparsedSection`], produced when parsing from a
+/// [reader](Read) as opposed to a byte array.
+pub struct OwnedUnparsedSection {
+    pub section_id:     SectionId,
+    pub bytes:          Vec<u8>,
+    /// Position of the section ID byte in the input the section was read from.
+    pub start_offset:   usize,
+    /// Position of the first byte of the section contents in the input the
+    /// section was read from.
+    pub payload_offset: usize,
+}
+
+impl OwnedUnparsedSection {
+    /// Get a borrowed view of the section, which can be used with the rest of
+    /// the parsing and validation functions.
+    pub fn borrow(&self) -> UnparsedSection<'_> {
+        UnparsedSection {
+            section_id:     self.section_id,
+            bytes:          &self.bytes,
+            start_offset:   self.start_offset,
+            payload_offset: self.payload_offset,
+        }
+    }
+}
+
+#[derive(Debug)]
+/// An owned variant of [`Skeleton`], produced by [`parse_skeleton_reader`].
+pub struct OwnedSkeleton {
+    pub ty:      Option<OwnedUnparsedSection>,
+    pub import:  Option<OwnedUnparsedSection>,
+    pub func:    Option<OwnedUnparsedSection>,
+    pub table:   Option<OwnedUnparsedSection>,
+    pub memory:  Option<OwnedUnparsedSection>,
+    pub global:  Option<OwnedUnparsedSection>,
+    pub export:  Option<OwnedUnparsedSection>,
+    pub start:   Option<OwnedUnparsedSection>,
+    pub element: Option<OwnedUnparsedSection>,
+    pub code:    Option<OwnedUnparsedSection>,
+    pub data:    Option<OwnedUnparsedSection>,
+    /// A list of custom sections in the order they appeared in the input.
+    pub custom:  Vec<OwnedUnparsedSection>,
+    /// The limits the module was parsed with.
+    config:      ParseConfig,
+}
+
+impl OwnedSkeleton {
+    /// The limits the module was parsed with.
+    pub fn config(&self) -> &ParseConfig { &self.config }
+
+    /// Get a borrowed view of the skeleton, which can be passed to, e.g.,
+    /// [`validate_module`](crate::validate::validate_module).
+    pub fn borrow(&self) -> Skeleton<'_> {
+        Skeleton {
+            ty:      self.ty.as_ref().map(OwnedUnparsedSection::borrow),
+            import:  self.import.as_ref().map(OwnedUnparsedSection::borrow),
+            func:    self.func.as_ref().map(OwnedUnparsedSection::borrow),
+            table:   self.table.as_ref().map(OwnedUnparsedSection::borrow),
+            memory:  self.memory.as_ref().map(OwnedUnparsedSection::borrow),
+            global:  self.global.as_ref().map(OwnedUnparsedSection::borrow),
+            export:  self.export.as_ref().map(OwnedUnparsedSection::borrow),
+            start:   self.start.as_ref().map(OwnedUnparsedSection::borrow),
+            element: self.element.as_ref().map(OwnedUnparsedSection::borrow),
+            code:    self.code.as_ref().map(OwnedUnparsedSection::borrow),
+            data:    self.data.as_ref().map(OwnedUnparsedSection::borrow),
+            custom:  self.custom.iter().map(OwnedUnparsedSection::borrow).collect(),
+            config:  self.config,
+        }
+    }
+}
+
+/// Read the header of a section, i.e., its ID and the length of its contents,
+/// from the reader. Returns `None` if the reader is at the end of input.
+/// The header bytes are buffered so that the [`Parseable`] instances can be
+/// used to decode them. The last component of the result is the number of
+/// bytes that were read.
+fn read_section_header(input: &mut impl Read) -> ParseResult<Option<(SectionId, u32, usize)>> {
+    // 1 byte for the section ID, and at most 5 for the u32 length.
+    let mut header = [0u8; 6];
+    loop {
+        match input.read(&mut header[..1]) {
+            Ok(0) => return Ok(None),
+            Ok(_) => break,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+    let mut header_len = 1;
+    loop {
+        ensure!(header_len < header.len(), ParseError::InvalidSectionLength);
+        input.read_exact(&mut header[header_len..header_len + 1])?;
+        header_len += 1;
+        if header[header_len - 1] & 0x80 == 0 {
+            break;
+        }
+    }
+    let cursor = &mut Cursor::new(&header[..header_len]);
+    let section_id = cursor.next(EMPTY_CTX)?;
+    let len = cursor.next(EMPTY_CTX)?;
+    Ok(Some((section_id, len, header_len)))
+}
+
+/// Same as [`parse_skeleton`], except that the module is read from the given
+/// reader, and the resulting skeleton owns the section contents. Each section
+/// is buffered, and the same checks as in [`parse_skeleton`] are performed.
+///
+/// Callers that already have the module in memory should use
+/// [`parse_skeleton`] since it avoids copying the sections.
+///
+/// This is [`parse_skeleton_reader_with`] with the default [`ParseConfig`].
+pub fn parse_skeleton_reader<R: Read>(input: R) -> ParseResult<OwnedSkeleton> {
+    parse_skeleton_reader_with(input, &ParseConfig::default())
+}
+
+/// Same as [`parse_skeleton_reader`], but with the limits given by the
+/// configuration.
+pub fn parse_skeleton_reader_with<R: Read>(
+    mut input: R,
+    config: &ParseConfig,
+) -> ParseResult<OwnedSkeleton> {
+    {
+        // check magic hash and version
+        let mut buf = [0u8; 4];
+        input.read_exact(&mut buf)?;
+        // ensure magic hash
+        ensure!(buf == MAGIC_HASH, ParseError::UnknownMagicHash);
+        input.read_exact(&mut buf)?;
+        // ensure module version.
+        ensure!(buf == VERSION, ParseError::UnsupportedVersion);
+    }
+    let mut position = MAGIC_HASH.len() + VERSION.len();
+    let mut last_section = SectionId::Custom;
+
+    let mut ty = None;
+    let mut import = None;
+    let mut func = None;
+    let mut table = None;
+    let mut memory = None;
+    let mut global = None;
+    let mut export = None;
+    let mut start = None;
+    let mut element = None;
+    let mut code = None;
+    let mut data = None;
+    let mut custom = Vec::new();
+
+    // The loop terminates when the reader has no more input, so all the input
+    // is consumed if parsing succeeds.
+    while let Some((section_id, len, header_len)) = read_section_header(&mut input)? {
+        check_section_order(&mut last_section, section_id)?;
+        // Do not preallocate based on the claimed length, since the input
+        // might be shorter.
+        let mut bytes = Vec::with_capacity(std::cmp::min(len as usize, config.max_prealloc_bytes));
+        (&mut input).take(u64::from(len)).read_to_end(&mut bytes)?;
+        ensure!(bytes.len() == len as usize, ParseError::MalformedByteArray);
+        let section = OwnedUnparsedSection {
+            section_id,
+            bytes,
+            start_offset: position,
+            payload_offset: position + header_len,
+        };
+        position += header_len + len as usize;
+        match section_id {
+            SectionId::Custom => {
+                config.check_custom_sections(custom.len())?;
+                custom.push(section)
+            }
+            SectionId::Type => ty = Some(section),
+            SectionId::Import => import = Some(section),
+            SectionId::Function => func = Some(section),
+            SectionId::Table => table = Some(section),
+            SectionId::Memory => memory = Some(section),
+            SectionId::Global => global = Some(section),
+            SectionId::Export => export = Some(section),
+            SectionId::Start => start = Some(section),
+            SectionId::Element => element = Some(section),
+            SectionId::Code => code = Some(section),
+            SectionId::Data => data = Some(section),
+        }
+    }
+    Ok(OwnedSkeleton {
+        ty,
+        import,
+        func,
+        table,
+        memory,
+        global,
+        export,
+        start,
+        element,
+        code,
+        data,
+        custom,
+        config: *config,
     })
 }
 
@@ -409,7 +746,7 @@ impl<'a, Ctx> Parseable<'a, Ctx> for ValueType {
 
 /// Parse a limit, and additionally ensure that, if given, the upper bound is
 /// no less than lower bound.
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Limits {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for Limits {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         match Byte::parse(ctx, cursor)? {
             0x00 => {
@@ -422,13 +759,18 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Limits {
             0x01 => {
                 let min = cursor.next(ctx)?;
                 let mmax = cursor.next(ctx)?;
-                ensure!(min <= mmax, "Lower limit must be no greater than the upper limit.");
+                ensure!(min <= mmax, ParseError::InvalidLimits {
+                    min,
+                    max: mmax,
+                });
                 Ok(Limits {
                     min,
                     max: Some(mmax),
                 })
             }
-            tag => bail!("Incorrect limits tag {:#04x}.", tag),
+            tag => bail!(ParseError::IncorrectLimitsTag {
+                tag
+            }),
         }
     }
 }
@@ -437,13 +779,16 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Limits {
 /// match.
 fn expect_byte(cursor: &mut Cursor<&[u8]>, byte: Byte) -> ParseResult<()> {
     let b = Byte::parse(EMPTY_CTX, cursor)?;
-    ensure!(b == byte, "Unexpected byte {:#04x}. Expected {:#04x}", b, byte);
+    ensure!(b == byte, ParseError::UnexpectedByte {
+        found:    b,
+        expected: byte,
+    });
     Ok(())
 }
 
 /// Parse a function type. Since we do not support multiple return values we
 /// ensure at parse time that there are no more than one return values.
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for FunctionType {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for FunctionType {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         expect_byte(cursor, 0x60)?;
         let parameters = cursor.next(ctx)?;
@@ -461,11 +806,13 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for FunctionType {
 /// the funcref, so this only records the resulting table limits.
 /// This instance additionally ensures that the limits are valid, i.e., in range
 /// 2^32. Since the bounds are 32-bit integers, this is true by default.
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for TableType {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for TableType {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         expect_byte(cursor, 0x70)?;
         let limits = Limits::parse(ctx, cursor)?;
-        ensure!(limits.min <= MAX_INIT_TABLE_SIZE, "Initial table size exceeds allowed limits.");
+        ensure!(limits.min <= MAX_INIT_TABLE_SIZE, ParseError::InitialTableTooLarge {
+            size: limits.min,
+        });
         Ok(TableType {
             limits,
         })
@@ -474,18 +821,15 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for TableType {
 
 /// Memory types are just limits on the size of the memory.
 /// This also ensures that limits are within range 2^16.
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for MemoryType {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for MemoryType {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let limits = Limits::parse(ctx, cursor)?;
-        ensure!(
-            limits.min <= MAX_INIT_MEMORY_SIZE,
-            "Initial memory allocation of {} pages exceeds maximum of {}.",
-            limits.min,
-            MAX_INIT_MEMORY_SIZE
-        );
+        ensure!(limits.min <= MAX_INIT_MEMORY_SIZE, ParseError::InitialMemoryTooLarge {
+            pages: limits.min,
+        });
         match limits.max {
-            Some(x) => ensure!(x <= 1 << 16, "Memory limits must be in range 2^16."),
-            None => ensure!(limits.min <= 1 << 16, "Memory limits must be in range 2^16."),
+            Some(x) => ensure!(x <= 1 << 16, ParseError::MemoryLimitsOutOfRange),
+            None => ensure!(limits.min <= 1 << 16, ParseError::MemoryLimitsOutOfRange),
         }
         Ok(MemoryType {
             limits,
@@ -499,7 +843,7 @@ impl<'a, Ctx, X: Parseable<'a, Ctx>> Parseable<'a, Ctx> for Rc<X> {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for TypeSection {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for TypeSection {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let types = cursor.next(ctx)?;
         Ok(TypeSection {
@@ -508,7 +852,7 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for TypeSection {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ImportDescription {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ImportDescription {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         match Byte::parse(ctx, cursor)? {
             0x00 => {
@@ -524,7 +868,7 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ImportDescription {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Import {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for Import {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let mod_name = cursor.next(ctx)?;
         let item_name = cursor.next(ctx)?;
@@ -537,7 +881,7 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Import {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ImportSection {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ImportSection {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let imports = cursor.next(ctx)?;
         Ok(ImportSection {
@@ -546,7 +890,7 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ImportSection {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for FunctionSection {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for FunctionSection {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let types = cursor.next(ctx)?;
         Ok(FunctionSection {
@@ -579,60 +923,69 @@ fn read_constant_expr<'a>(
     let instr = decode_opcode(cursor)?;
     let res = match instr {
         OpCode::I32Const(n) => {
-            ensure!(ty == ValueType::I32, "Constant instruction of type I64, but I32 expected.");
+            ensure!(ty == ValueType::I32, ParseError::ConstantExprTypeMismatch {
+                expected: ty,
+                found:    ValueType::I32,
+            });
             GlobalInit::I32(n)
         }
         OpCode::I64Const(n) => {
-            ensure!(ty == ValueType::I64, "Constant instruction of type I32, but I64 expected.");
+            ensure!(ty == ValueType::I64, ParseError::ConstantExprTypeMismatch {
+                expected: ty,
+                found:    ValueType::I64,
+            });
             GlobalInit::I64(n)
         }
         OpCode::GlobalGet(idx) => match globals_allowed {
-            None => bail!("GlobalGet not allowed in this constant expression."),
+            None => bail!(ParseError::GlobalGetNotAllowed),
             Some(globals) => {
-                let global = globals.get(idx).ok_or_else(|| {
-                    anyhow::anyhow!("Reference to non-existent global in constant expression.")
+                let global = globals.get(idx).ok_or(ParseError::UnknownGlobal {
+                    index: idx,
                 })?;
-                ensure!(
-                    global.init.ty() == ty,
-                    "Global in constant expression of incorrect type: {:?} != {:?}",
-                    global.init.ty(),
-                    ty
-                );
-                ensure!(
-                    !global.mutable,
-                    "Only references to constant globals can appear in constant expressions."
-                );
+                ensure!(global.init.ty() == ty, ParseError::ConstantExprTypeMismatch {
+                    expected: ty,
+                    found:    global.init.ty(),
+                });
+                ensure!(!global.mutable, ParseError::MutableGlobalInConstantExpr);
                 global.init
             }
         },
-        _ => bail!("Not a constant instruction {:?}.", instr),
+        _ => bail!(ParseError::NotAConstantInstruction {
+            instr
+        }),
     };
     // end parsing the expression
     expect_byte(cursor, END)?;
     Ok(res)
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for TableSection {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for TableSection {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let table_type_vec: Vec<TableType> = cursor.next(ctx)?;
-        ensure!(table_type_vec.len() <= 1, "Only table with index 0 is supported.");
+        ensure!(table_type_vec.len() <= 1, ParseError::OnlyTableIndexZero);
         Ok(TableSection {
             table_type: table_type_vec.first().copied(),
         })
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for MemorySection {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for MemorySection {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let memory_types_vec: Vec<MemoryType> = cursor.next(ctx)?;
-        ensure!(memory_types_vec.len() <= 1, "Only memory with index 1 is supported.");
+        #[cfg(not(feature = "multi-memory"))]
+        ensure!(memory_types_vec.len() <= 1, ParseError::OnlyMemoryIndexZero);
+        #[cfg(feature = "multi-memory")]
+        ensure!(memory_types_vec.len() <= MAX_NUM_MEMORIES, ParseError::TooManyMemories {
+            max: MAX_NUM_MEMORIES,
+        });
         Ok(MemorySection {
-            memory_type: memory_types_vec.first().copied(),
+            memory_type:             memory_types_vec.first().copied(),
+            additional_memory_types: memory_types_vec.iter().skip(1).copied().collect(),
         })
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ExportDescription {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ExportDescription {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         match Byte::parse(ctx, cursor)? {
             0x00 => {
@@ -643,12 +996,12 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ExportDescription {
             }
             0x01 => {
                 let index = TableIndex::parse(ctx, cursor)?;
-                ensure!(index == 0, "Only table with index 0 is supported.");
+                ensure!(index == 0, ParseError::OnlyTableIndexZero);
                 Ok(ExportDescription::Table)
             }
             0x02 => {
                 let index = MemIndex::parse(ctx, cursor)?;
-                ensure!(index == 0, "Only memory with index 0 is supported.");
+                ensure!(index == 0, ParseError::OnlyMemoryIndexZero);
                 Ok(ExportDescription::Memory)
             }
             0x03 => {
@@ -657,12 +1010,14 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ExportDescription {
                     index,
                 })
             }
-            byte => bail!("Unsupported export tag {:#04x}.", byte),
+            tag => bail!(ParseError::UnsupportedExportTag {
+                tag
+            }),
         }
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Export {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for Export {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let name: Name = cursor.next(ctx)?;
         let description = cursor.next(ctx)?;
@@ -684,7 +1039,7 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Export {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ExportSection {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for ExportSection {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let exports = cursor.next(ctx)?;
         Ok(ExportSection {
@@ -695,18 +1050,22 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for ExportSection {
 
 impl<'a, Ctx> Parseable<'a, Ctx> for StartSection {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
-        // We deliberately try to parse the index before failing
-        // in order that the error message is more precise.
+        // A start section consists of exactly one function index. We
+        // deliberately try to parse the index before failing so that a
+        // malformed section is reported as such.
         let _idxs: FuncIndex = cursor.next(ctx)?;
         bail!(ParseError::StartFunctionsNotSupported);
     }
 }
 
-impl<'a> Parseable<'a, &GlobalSection> for Element {
-    fn parse(ctx: &GlobalSection, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
+impl<'a, 'b> Parseable<'a, (&'b GlobalSection, &'b ParseConfig)> for Element {
+    fn parse(
+        ctx: (&'b GlobalSection, &'b ParseConfig),
+        cursor: &mut Cursor<&'a [u8]>,
+    ) -> ParseResult<Self> {
         let table_index = TableIndex::parse(ctx, cursor)?;
-        ensure!(table_index == 0, "Only table index 0 is supported.");
-        let offset = read_constant_expr(cursor, ValueType::I32, Some(ctx))?;
+        ensure!(table_index == 0, ParseError::OnlyTableIndexZero);
+        let offset = read_constant_expr(cursor, ValueType::I32, Some(ctx.0))?;
         let inits = cursor.next(ctx)?;
         if let GlobalInit::I32(offset) = offset {
             Ok(Element {
@@ -714,13 +1073,19 @@ impl<'a> Parseable<'a, &GlobalSection> for Element {
                 inits,
             })
         } else {
-            bail!("Internal error, parsed a constant of type I32 that is not an I32.");
+            bail!(ParseError::ConstantExprTypeMismatch {
+                expected: ValueType::I32,
+                found:    offset.ty(),
+            });
         }
     }
 }
 
-impl<'a> Parseable<'a, &GlobalSection> for ElementSection {
-    fn parse(ctx: &GlobalSection, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
+impl<'a, 'b> Parseable<'a, (&'b GlobalSection, &'b ParseConfig)> for ElementSection {
+    fn parse(
+        ctx: (&'b GlobalSection, &'b ParseConfig),
+        cursor: &mut Cursor<&'a [u8]>,
+    ) -> ParseResult<Self> {
         let elements = cursor.next(ctx)?;
         Ok(ElementSection {
             elements,
@@ -728,13 +1093,15 @@ impl<'a> Parseable<'a, &GlobalSection> for ElementSection {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Global {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for Global {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let ty = cursor.next(ctx)?;
         let mutable = match Byte::parse(ctx, cursor)? {
             0x00 => false,
             0x01 => true,
-            flag => bail!("Unsupported mutability flag {:#04x}", flag),
+            flag => bail!(ParseError::UnsupportedMutabilityFlag {
+                flag
+            }),
         };
         // Globals initialization expressions cannot refer to other (in-module) globals.
         let init = read_constant_expr(cursor, ty, None)?;
@@ -745,7 +1112,7 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Global {
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for GlobalSection {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for GlobalSection {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let globals = cursor.next(ctx)?;
         Ok(GlobalSection {
@@ -765,23 +1132,37 @@ impl<'a, Ctx> Parseable<'a, Ctx> for BlockType {
             0x40 => Ok(BlockType::EmptyType),
             0x7F => Ok(BlockType::ValueType(ValueType::I32)),
             0x7E => Ok(BlockType::ValueType(ValueType::I64)),
-            x => bail!("Unsupported block type {}", x),
+            byte => bail!(ParseError::UnsupportedBlockType {
+                byte
+            }),
         }
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for MemArg {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for MemArg {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
-        let align = cursor.next(ctx)?;
+        let align: u32 = cursor.next(ctx)?;
+        // In the multi-memory proposal bit 6 of the alignment indicates that an
+        // explicit memory index follows. Whether the memory exists is checked
+        // during validation.
+        #[cfg(feature = "multi-memory")]
+        let (align, memory): (u32, MemIndex) = if align & 0x40 != 0 {
+            (align & !0x40, cursor.next(ctx)?)
+        } else {
+            (align, 0)
+        };
+        #[cfg(not(feature = "multi-memory"))]
+        let memory = 0;
         let offset = cursor.next(ctx)?;
         Ok(MemArg {
             offset,
             align,
+            memory,
         })
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Local {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for Local {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let multiplicity = cursor.next(ctx)?;
         let ty = cursor.next(ctx)?;
@@ -792,25 +1173,37 @@ impl<'a, Ctx: Copy> Parseable<'a, Ctx> for Local {
     }
 }
 
-impl<'a> Parseable<'a, &GlobalSection> for Data {
-    fn parse(ctx: &GlobalSection, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
-        let index = u32::parse(ctx, cursor)?;
-        ensure!(index == 0, "Only memory index 0 is supported.");
-        let offset = read_constant_expr(cursor, ValueType::I32, Some(ctx))?;
+impl<'a, 'b> Parseable<'a, (&'b GlobalSection, &'b ParseConfig)> for Data {
+    fn parse(
+        ctx: (&'b GlobalSection, &'b ParseConfig),
+        cursor: &mut Cursor<&'a [u8]>,
+    ) -> ParseResult<Self> {
+        // Whether the memory exists is checked during validation.
+        let memory = MemIndex::parse(ctx, cursor)?;
+        #[cfg(not(feature = "multi-memory"))]
+        ensure!(memory == 0, ParseError::OnlyMemoryIndexZero);
+        let offset = read_constant_expr(cursor, ValueType::I32, Some(ctx.0))?;
         let init = cursor.next(ctx)?;
         if let GlobalInit::I32(offset) = offset {
             Ok(Data {
+                memory,
                 offset,
                 init,
             })
         } else {
-            bail!("Internal error, a constant expression of type I32 is not an I32");
+            bail!(ParseError::ConstantExprTypeMismatch {
+                expected: ValueType::I32,
+                found:    offset.ty(),
+            });
         }
     }
 }
 
-impl<'a> Parseable<'a, &GlobalSection> for DataSection {
-    fn parse(ctx: &GlobalSection, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
+impl<'a, 'b> Parseable<'a, (&'b GlobalSection, &'b ParseConfig)> for DataSection {
+    fn parse(
+        ctx: (&'b GlobalSection, &'b ParseConfig),
+        cursor: &mut Cursor<&'a [u8]>,
+    ) -> ParseResult<Self> {
         let sections = cursor.next(ctx)?;
         Ok(DataSection {
             sections,
@@ -830,7 +1223,85 @@ pub(crate) fn parse_sec_with_default<'a, Ctx, A: Parseable<'a, Ctx> + Default>(
 
 #[derive(Debug)]
 /// An error that can occur during parsing of Wasm code.
+///
+/// Parsing functions return [`ParseResult`], so that errors from the
+/// underlying readers and integer conversions can be propagated directly. The
+/// errors that are specific to the Wasm format are raised as values of this
+/// type, and can be recovered with
+/// [`downcast_ref`](anyhow::Error::downcast_ref).
 pub enum ParseError {
+    UnknownMagicHash,
+    UnsupportedVersion,
+    UnknownSectionId {
+        id: Byte,
+    },
+    /// A non-custom section appears after a section that must follow it, or
+    /// it appears twice.
+    SectionOutOfOrder,
+    /// There is input left over after the last section.
+    TrailingBytes,
+    /// The length of a section is not encoded as a valid `u32`.
+    InvalidSectionLength,
+    /// There are more custom sections than allowed by the
+    /// [`ParseConfig`].
+    TooManyCustomSections {
+        max: usize,
+    },
+    /// Parsing a section (or another length-prefixed item) did not use exactly
+    /// the declared number of bytes.
+    DeclaredSizeMismatch,
+    /// The declared length of a byte array exceeds the remaining input.
+    MalformedByteArray,
+    /// The declaration of the locals of a function body uses more bytes than
+    /// the declared size of the body.
+    LocalsExceedDeclaredSize {
+        size:     u32,
+        consumed: u64,
+    },
+    /// The declared size of a function body exceeds the remaining input.
+    DeclaredSizeExceedsInput {
+        size:      u32,
+        remaining: u64,
+    },
+    UnsupportedOptionTag {
+        tag: Byte,
+    },
+    IncorrectLimitsTag {
+        tag: Byte,
+    },
+    /// The lower bound of limits is greater than the upper bound.
+    InvalidLimits {
+        min: u32,
+        max: u32,
+    },
+    /// The initial size of the table exceeds [`MAX_INIT_TABLE_SIZE`].
+    InitialTableTooLarge {
+        size: u32,
+    },
+    /// The initial number of memory pages exceeds [`MAX_INIT_MEMORY_SIZE`].
+    InitialMemoryTooLarge {
+        pages: u32,
+    },
+    /// The limits of a memory are not in range 2^16.
+    MemoryLimitsOutOfRange,
+    UnexpectedByte {
+        found:    Byte,
+        expected: Byte,
+    },
+    UnsupportedExportTag {
+        tag: Byte,
+    },
+    UnsupportedMutabilityFlag {
+        flag: Byte,
+    },
+    UnsupportedBlockType {
+        byte: Byte,
+    },
+    /// A floating point instruction. These are not supported to ensure
+    /// determinism.
+    FloatingPointUnsupported {
+        opcode: Byte,
+    },
     UnsupportedInstruction {
         opcode: Byte,
     },
@@ -840,6 +1311,33 @@ pub enum ParseError {
     UnsupportedImportType {
         tag: Byte,
     },
+    /// A constant expression has a different type than required by its
+    /// context.
+    ConstantExprTypeMismatch {
+        expected: ValueType,
+        found:    ValueType,
+    },
+    /// A `global.get` in a constant expression that does not allow them, i.e.,
+    /// a global initializer.
+    GlobalGetNotAllowed,
+    /// A constant expression refers to a global that is not defined.
+    UnknownGlobal {
+        index: GlobalIndex,
+    },
+    /// A constant expression refers to a mutable global.
+    MutableGlobalInConstantExpr,
+    /// An instruction that may not appear in a constant expression.
+    NotAConstantInstruction {
+        instr: OpCode,
+    },
+    /// A table other than the one with index 0 is defined or referenced.
+    OnlyTableIndexZero,
+    /// A memory other than the one with index 0 is defined or referenced.
+    OnlyMemoryIndexZero,
+    /// More memories are defined than supported by the `multi-memory` feature.
+    TooManyMemories {
+        max: usize,
+    },
     OnlySingleReturn,
     OnlyASCIINames,
     NameTooLong,
@@ -850,6 +1348,80 @@ pub enum ParseError {
 impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            ParseError::UnknownMagicHash => write!(f, "Unknown magic hash"),
+            ParseError::UnsupportedVersion => write!(f, "Unsupported version."),
+            ParseError::UnknownSectionId {
+                id,
+            } => write!(f, "Unknown section id {}", id),
+            ParseError::SectionOutOfOrder => write!(f, "Section out of place."),
+            ParseError::TrailingBytes => write!(f, "Leftover bytes."),
+            ParseError::InvalidSectionLength => write!(f, "Section length is not a valid u32."),
+            ParseError::TooManyCustomSections {
+                max,
+            } => write!(f, "Number of custom sections exceeds the maximum of {}.", max),
+            ParseError::DeclaredSizeMismatch => write!(f, "Not all of the contents was consumed."),
+            ParseError::MalformedByteArray => write!(f, "Malformed byte array"),
+            ParseError::LocalsExceedDeclaredSize {
+                size,
+                consumed,
+            } => write!(
+                f,
+                "Declared size {} but the declaration of locals already consumed {} bytes.",
+                size, consumed
+            ),
+            ParseError::DeclaredSizeExceedsInput {
+                size,
+                remaining,
+            } => write!(
+                f,
+                "Declared size {} but only {} bytes remain in the input.",
+                size, remaining
+            ),
+            ParseError::UnsupportedOptionTag {
+                tag,
+            } => write!(f, "Unsupported option tag: {:#04x}", tag),
+            ParseError::IncorrectLimitsTag {
+                tag,
+            } => write!(f, "Incorrect limits tag {:#04x}.", tag),
+            ParseError::InvalidLimits {
+                min,
+                max,
+            } => write!(f, "Lower limit {} must be no greater than the upper limit {}.", min, max),
+            ParseError::InitialTableTooLarge {
+                size,
+            } => write!(
+                f,
+                "Initial table size {} exceeds the maximum of {}.",
+                size, MAX_INIT_TABLE_SIZE
+            ),
+            ParseError::InitialMemoryTooLarge {
+                pages,
+            } => write!(
+                f,
+                "Initial memory allocation of {} pages exceeds maximum of {}.",
+                pages, MAX_INIT_MEMORY_SIZE
+            ),
+            ParseError::MemoryLimitsOutOfRange => write!(f, "Memory limits must be in range 2^16."),
+            ParseError::UnexpectedByte {
+                found,
+                expected,
+            } => write!(f, "Unexpected byte {:#04x}. Expected {:#04x}", found, expected),
+            ParseError::UnsupportedExportTag {
+                tag,
+            } => write!(f, "Unsupported export tag {:#04x}.", tag),
+            ParseError::UnsupportedMutabilityFlag {
+                flag,
+            } => write!(f, "Unsupported mutability flag {:#04x}", flag),
+            ParseError::UnsupportedBlockType {
+                byte,
+            } => write!(f, "Unsupported block type {}", byte),
+            ParseError::FloatingPointUnsupported {
+                opcode,
+            } => write!(
+                f,
+                "Unsupported instruction {:#04x}. Floating point instructions are not supported.",
+                opcode
+            ),
             ParseError::UnsupportedInstruction {
                 opcode,
             } => write!(f, "Unsupported instruction {:#04x}", opcode),
@@ -859,6 +1431,27 @@ impl std::fmt::Display for ParseError {
             ParseError::UnsupportedImportType {
                 tag,
             } => write!(f, "Unsupported import type {:#04x}. Only functions can be imported.", tag),
+            ParseError::ConstantExprTypeMismatch {
+                expected,
+                found,
+            } => write!(f, "Constant expression of type {:?}, but {:?} expected.", found, expected),
+            ParseError::GlobalGetNotAllowed => {
+                write!(f, "GlobalGet not allowed in this constant expression.")
+            }
+            ParseError::UnknownGlobal {
+                index,
+            } => write!(f, "Reference to non-existent global {} in constant expression.", index),
+            ParseError::MutableGlobalInConstantExpr => {
+                write!(f, "Only references to constant globals can appear in constant expressions.")
+            }
+            ParseError::NotAConstantInstruction {
+                instr,
+            } => write!(f, "Not a constant instruction {:?}.", instr),
+            ParseError::OnlyTableIndexZero => write!(f, "Only table with index 0 is supported."),
+            ParseError::OnlyMemoryIndexZero => write!(f, "Only memory with index 0 is supported."),
+            ParseError::TooManyMemories {
+                max,
+            } => write!(f, "At most {} memories are supported.", max),
             ParseError::OnlySingleReturn => write!(f, "Only single return value is supported."),
             ParseError::OnlyASCIINames => write!(f, "Only ASCII names are allowed."),
             ParseError::NameTooLong => write!(f, "Names are limited to {} bytes.", MAX_NAME_SIZE),
@@ -868,12 +1461,24 @@ impl std::fmt::Display for ParseError {
                 concordium_contracts_common::constants::MAX_FUNC_NAME_SIZE
             ),
             ParseError::StartFunctionsNotSupported => {
-                write!(f, "Start functions are not supported.")
+                write!(f, "Start sections are not supported on Concordium.")
             }
         }
     }
 }
 
+impl std::error::Error for ParseError {}
+
+/// Whether the byte is the opcode of a floating point instruction in the Wasm
+/// specification. These are loads, stores, constants, and the numeric
+/// instructions, including conversions, on `f32` and `f64`.
+fn is_floating_point_opcode(byte: Byte) -> bool {
+    matches!(
+        byte,
+        0x2A..=0x2B | 0x38..=0x39 | 0x43..=0x44 | 0x5B..=0x66 | 0x8B..=0xA6 | 0xA8..=0xAB | 0xAE..=0xBF
+    )
+}
+
 /// Decode the next opcode directly from the cursor.
 pub(crate) fn decode_opcode(cursor: &mut Cursor<&[u8]>) -> ParseResult<OpCode> {
     match Byte::parse(EMPTY_CTX, cursor)? {
@@ -1106,6 +1711,15 @@ pub(crate) fn decode_opcode(cursor: &mut Cursor<&[u8]>) -> ParseResult<OpCode> {
 
         0xAC => Ok(OpCode::I64ExtendI32S),
         0xAD => Ok(OpCode::I64ExtendI32U),
+
+        0xC0 => Ok(OpCode::I32Extend8S),
+        0xC1 => Ok(OpCode::I32Extend16S),
+        0xC2 => Ok(OpCode::I64Extend8S),
+        0xC3 => Ok(OpCode::I64Extend16S),
+        0xC4 => Ok(OpCode::I64Extend32S),
+        byte if is_floating_point_opcode(byte) => bail!(ParseError::FloatingPointUnsupported {
+            opcode: byte,
+        }),
         byte => bail!(ParseError::UnsupportedInstruction {
             opcode: byte,
         }),
@@ -1139,6 +1753,8 @@ impl<'a> Iterator for OpCodeIterator<'a> {
 #[derive(Debug)]
 /// The body of a function.
 pub(crate) struct CodeSkeleton<'a> {
+    /// The declared size of the body in bytes.
+    pub size:       u32,
     /// Declaration of the locals.
     pub locals:     Vec<Local>,
     /// And uninterpreted instructions.
@@ -1153,33 +1769,50 @@ pub(crate) struct CodeSkeletonSection<'a> {
     pub impls: Vec<CodeSkeleton<'a>>,
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for CodeSkeleton<'a> {
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for CodeSkeleton<'a> {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
         let size: u32 = cursor.next(ctx)?;
         let cur_pos = cursor.position();
         let locals = cursor.next(ctx)?;
         let end_pos = cursor.position();
-        ensure!(
-            u64::from(size) >= end_pos - cur_pos,
-            "We've already read too many bytes, module is malformed."
-        );
+        ensure!(u64::from(size) >= end_pos - cur_pos, ParseError::LocalsExceedDeclaredSize {
+            size,
+            consumed: end_pos - cur_pos,
+        });
         let remaining = u64::from(size) - (end_pos - cur_pos);
         ensure!(
             ((end_pos + remaining) as usize) <= cursor.get_ref().len(),
-            "We would need to read beyond the end of the input."
+            ParseError::DeclaredSizeExceedsInput {
+                size,
+                remaining: cursor.get_ref().len() as u64 - cur_pos,
+            }
         );
         let expr_bytes = &cursor.get_ref()[end_pos as usize..(end_pos + remaining) as usize];
         cursor.set_position(end_pos + remaining);
         Ok(CodeSkeleton {
+            size,
             locals,
             expr_bytes,
         })
     }
 }
 
-impl<'a, Ctx: Copy> Parseable<'a, Ctx> for CodeSkeletonSection<'a> {
+/// This is the same as parsing a vector of [`CodeSkeleton`]s, except that
+/// errors are annotated with the index of the function body and its offset
+/// in the code section.
+impl<'a, Ctx: ParseContext> Parseable<'a, Ctx> for CodeSkeletonSection<'a> {
     fn parse(ctx: Ctx, cursor: &mut Cursor<&'a [u8]>) -> ParseResult<Self> {
-        let impls = cursor.next(ctx)?;
+        let len = u32::parse(ctx, cursor)?;
+        let max_initial_capacity =
+            ctx.max_prealloc_bytes() / std::cmp::max(1, std::mem::size_of::<CodeSkeleton<'a>>());
+        let mut impls = Vec::with_capacity(std::cmp::min(len as usize, max_initial_capacity));
+        for i in 0..len {
+            let offset = cursor.position();
+            let body = CodeSkeleton::parse(ctx, cursor).with_context(|| {
+                format!("Function body #{} at offset {:#x} in the code section", i, offset)
+            })?;
+            impls.push(body);
+        }
         Ok(CodeSkeletonSection {
             impls,
         })
@@ -0,0 +1,268 @@
+//! Tests for parsing modules into skeletons.
+use crate::{
+    output::Output,
+    output_test::{AllowAll, MODULE},
+    parse::{
+        parse_skeleton, parse_skeleton_reader, parse_skeleton_reader_with, parse_skeleton_with,
+        OpCodeIterator, ParseConfig, ParseError, Parseable, SectionId, Skeleton,
+    },
+    types::OpCode,
+    validate::validate_module,
+};
+use std::io::{Cursor, Read};
+
+#[test]
+fn skeleton_reader_roundtrip() {
+    // Reading the module from a reader should give the same sections as parsing
+    // it from a byte array.
+    let owned = parse_skeleton_reader(MODULE).expect("Module should parse from a reader.");
+    let skeleton = parse_skeleton(MODULE).expect("Module should parse.");
+    let code = owned.code.as_ref().expect("Module has a code section.");
+    let expected_code = skeleton.code.as_ref().expect("Module has a code section.");
+    assert_eq!(code.bytes, expected_code.bytes, "Code sections should match.");
+    assert_eq!(code.start_offset, expected_code.start_offset, "Section offsets should match.");
+    assert_eq!(code.payload_offset, expected_code.payload_offset, "Payload offsets should match.");
+    let module = validate_module(&AllowAll, &owned.borrow()).expect("Module should validate.");
+    let mut out = Vec::new();
+    module.output(&mut out).expect("Outputting to a vector should succeed.");
+    assert_eq!(out, MODULE, "Outputting a parsed module should give back the original bytes.");
+}
+
+/// A reader that is interrupted before every successful read.
+struct InterruptingReader<'a> {
+    input:       &'a [u8],
+    interrupted: bool,
+}
+
+impl<'a> Read for InterruptingReader<'a> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.interrupted = !self.interrupted;
+        if self.interrupted {
+            Err(std::io::ErrorKind::Interrupted.into())
+        } else {
+            self.input.read(buf)
+        }
+    }
+}
+
+#[test]
+fn skeleton_reader_retries_interrupted_reads() {
+    let reader = InterruptingReader {
+        input:       MODULE,
+        interrupted: false,
+    };
+    let owned = parse_skeleton_reader(reader).expect("Interrupted reads should be retried.");
+    let skeleton = parse_skeleton(MODULE).expect("Module should parse.");
+    assert_eq!(
+        owned.code.as_ref().map(|c| c.bytes.as_slice()),
+        skeleton.code.as_ref().map(|c| c.bytes),
+        "Code sections should match."
+    );
+}
+
+#[test]
+fn memory_limits_min_above_max() {
+    // A memory section with a single memory with limits min 2, max 1.
+    let bytes =
+        [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x05, 0x04, 0x01, 0x01, 0x02, 0x01];
+    let skeleton = parse_skeleton(&bytes).expect("Module should parse.");
+    let err = validate_module(&AllowAll, &skeleton).expect_err("Limits should be rejected.");
+    assert!(
+        matches!(
+            err.downcast_ref::<ParseError>(),
+            Some(ParseError::InvalidLimits {
+                min: 2,
+                max: 1,
+            })
+        ),
+        "Unexpected error {}.",
+        err
+    );
+}
+
+/// A module with two memories of one page each. A data segment initializes
+/// the first word of memory 1 to 1. The exported function `f` stores 42 at
+/// address 4 of memory 1, and returns the sum of the words at addresses 4 and 0
+/// of memory 1 and the word at address 4 of memory 0, i.e., 43.
+#[cfg(feature = "multi-memory")]
+pub(crate) const TWO_MEMORIES: &[u8] = &[
+    0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // magic hash and version
+    0x01, 0x05, 0x01, 0x60, 0x00, 0x01, 0x7F, // types
+    0x03, 0x02, 0x01, 0x00, // functions
+    0x05, 0x05, 0x02, 0x00, 0x01, 0x00, 0x01, // memory
+    0x07, 0x05, 0x01, 0x01, 0x66, 0x00, 0x00, // exports
+    0x0A, 0x1F, 0x01, 0x1D, 0x00, // code, with no locals
+    0x41, 0x04, 0x41, 0x2A, 0x36, 0x42, 0x01, 0x00, // i32.store 1 at 4
+    0x41, 0x04, 0x28, 0x42, 0x01, 0x00, // i32.load 1 from 4
+    0x41, 0x00, 0x28, 0x42, 0x01, 0x00, 0x6A, // i32.load 1 from 0, i32.add
+    0x41, 0x04, 0x28, 0x02, 0x00, 0x6A, 0x0B, // i32.load from 4, i32.add, end
+    0x0B, 0x0A, 0x01, 0x01, 0x41, 0x00, 0x0B, 0x04, 0x01, 0x00, 0x00, 0x00, // data
+];
+
+#[test]
+#[cfg(feature = "multi-memory")]
+fn multiple_memories_allowed() {
+    let module = crate::output_test::parse_module(TWO_MEMORIES);
+    assert_eq!(module.memory.additional_memory_types.len(), 1, "The second memory should be kept.");
+    assert_eq!(module.data.sections[0].memory, 1, "The data segment is for memory 1.");
+    let mut out = Vec::new();
+    module.output(&mut out).expect("Outputting to a vector should succeed.");
+    assert_eq!(out, TWO_MEMORIES, "Outputting should retain both memories and the indices.");
+    let wat = module.to_wat();
+    assert!(wat.contains("(memory (;1;) 1)"), "Both memories should be printed: {}", wat);
+    assert!(wat.contains("i32.load 1 align=4"), "Memory indices should be printed: {}", wat);
+    assert!(
+        wat.contains("(data (;0;) (memory 1) (i32.const 0)"),
+        "Data should be printed: {}",
+        wat
+    );
+}
+
+#[test]
+#[cfg(not(feature = "multi-memory"))]
+fn second_memory_rejected() {
+    // A memory section with two memories without a maximum.
+    let bytes =
+        [0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, 0x05, 0x05, 0x02, 0x00, 0x01, 0x00, 0x01];
+    let skeleton = parse_skeleton(&bytes).expect("Module should parse.");
+    let err = validate_module(&AllowAll, &skeleton).expect_err("Second memory should be rejected.");
+    assert!(
+        matches!(err.downcast_ref::<ParseError>(), Some(ParseError::OnlyMemoryIndexZero)),
+        "Unexpected error {}.",
+        err
+    );
+}
+
+#[test]
+fn sign_extension_decoding() {
+    let bytes = [0xC0, 0xC1, 0xC2, 0xC3, 0xC4];
+    let opcodes = OpCodeIterator::new(&bytes)
+        .collect::<Result<Vec<_>, _>>()
+        .expect("Sign-extension instructions should decode.");
+    assert!(
+        matches!(opcodes.as_slice(), [
+            OpCode::I32Extend8S,
+            OpCode::I32Extend16S,
+            OpCode::I64Extend8S,
+            OpCode::I64Extend16S,
+            OpCode::I64Extend32S
+        ]),
+        "Unexpected instructions {:?}.",
+        opcodes
+    );
+}
+
+#[test]
+fn max_sections_limits_custom_sections() {
+    let custom_section: &[u8] = &[0x00, 0x03, 0x01, 0x78, 0x2A];
+    let input = [MODULE, custom_section, custom_section].concat();
+    let config = |max_sections| ParseConfig {
+        max_sections,
+        ..ParseConfig::default()
+    };
+    assert!(
+        parse_skeleton_with(&input, &config(None)).is_ok(),
+        "By default the number of custom sections is not limited."
+    );
+    assert!(
+        parse_skeleton_with(&input, &config(Some(2))).is_ok(),
+        "Custom sections up to the limit should be accepted."
+    );
+    let err = parse_skeleton_with(&input, &config(Some(1)))
+        .expect_err("Custom sections beyond the limit should be rejected.");
+    assert!(
+        matches!(
+            err.downcast_ref::<ParseError>(),
+            Some(ParseError::TooManyCustomSections {
+                max: 1,
+            })
+        ),
+        "Unexpected error {}.",
+        err
+    );
+    let reader_config = config(Some(1));
+    assert!(
+        parse_skeleton_reader_with(input.as_slice(), &reader_config).is_err(),
+        "Custom sections beyond the limit should be rejected when reading."
+    );
+}
+
+#[test]
+fn max_prealloc_bytes_bounds_vectors() {
+    // A vector of 10 u32 values.
+    let bytes = [0x0A, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+    let parse = |config: &ParseConfig| {
+        Vec::<u32>::parse(config, &mut Cursor::new(&bytes[..])).expect("Vector should parse.")
+    };
+    let v = parse(&ParseConfig::default());
+    assert_eq!(v, (0..10).collect::<Vec<u32>>());
+    assert_eq!(v.capacity(), 10, "Within the bound the whole vector is preallocated.");
+    let v = parse(&ParseConfig {
+        max_prealloc_bytes: 8,
+        ..ParseConfig::default()
+    });
+    assert_eq!(v, (0..10).collect::<Vec<u32>>());
+    // Only two elements are preallocated, and the vector then grows by doubling.
+    assert_eq!(v.capacity(), 16, "Preallocation should be bounded by the configuration.");
+
+    let config = ParseConfig {
+        max_prealloc_bytes: 8,
+        ..ParseConfig::default()
+    };
+    let skeleton = parse_skeleton_with(MODULE, &config).expect("Module should parse.");
+    assert_eq!(
+        skeleton.config().max_prealloc_bytes,
+        8,
+        "The skeleton should retain the configuration for parsing its sections."
+    );
+    validate_module(&AllowAll, &skeleton).expect("Module should validate with a small bound.");
+}
+
+#[test]
+fn corrupt_body_size_reports_function() {
+    let bytes = [
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // magic hash and version
+        0x01, 0x04, 0x01, 0x60, 0x00, 0x00, // types
+        0x03, 0x03, 0x02, 0x00, 0x00, // functions
+        // code, where the second body declares more bytes than remain
+        0x0A, 0x07, 0x02, 0x02, 0x00, 0x0B, 0x7F, 0x00, 0x0B,
+    ];
+    let skeleton = parse_skeleton(&bytes).expect("Module should parse.");
+    let err = validate_module(&AllowAll, &skeleton).expect_err("Body size should be rejected.");
+    assert_eq!(err.to_string(), "Function body #1 at offset 0x4 in the code section");
+    assert!(
+        err.chain().any(|e| e.to_string().starts_with("Declared size 127")),
+        "The cause should be reported: {:#}",
+        err
+    );
+}
+
+#[test]
+fn section_hash_ignores_custom_sections() {
+    use std::{collections::hash_map::DefaultHasher, hash::Hasher};
+    let custom_section: &[u8] = &[0x00, 0x03, 0x01, 0x78, 0x2A];
+    let with_custom = [MODULE, custom_section].concat();
+    let skeleton = parse_skeleton(MODULE).expect("Module should parse.");
+    let skeleton_with_custom = parse_skeleton(&with_custom).expect("Module should parse.");
+    let hash = |skeleton: &Skeleton<'_>| {
+        let mut hasher = DefaultHasher::new();
+        assert!(skeleton.hash_section(SectionId::Code, &mut hasher), "Module has a code section.");
+        hasher.finish()
+    };
+    assert_eq!(
+        hash(&skeleton),
+        hash(&skeleton_with_custom),
+        "Custom sections should not affect the hash of the code section."
+    );
+    assert_eq!(
+        skeleton.section_bytes(SectionId::Code),
+        Some(&MODULE[MODULE.len() - 21..MODULE.len() - 10]),
+        "Section bytes should exclude the section ID and length."
+    );
+    assert_eq!(skeleton.section_bytes(SectionId::Start), None, "Module has no start section.");
+    assert_eq!(
+        skeleton.section_bytes(SectionId::Custom),
+        None,
+        "Custom sections are never returned."
+    );
+}
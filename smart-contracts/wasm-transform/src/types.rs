@@ -261,8 +261,12 @@ pub struct CodeSection {
 }
 
 #[derive(Debug)]
-/// The initialization of memory. The memory index is implicitly 0.
+/// The initialization of memory. The memory index is only encoded explicitly
+/// in the multi-memory proposal, otherwise it is implicitly 0.
 pub struct Data {
+    /// The memory to initialize. This is always 0 unless the `multi-memory`
+    /// feature is enabled.
+    pub memory: MemIndex,
     /// Where to start initializing.
     pub offset: i32,
     /// The bytes to initialize with.
@@ -286,7 +290,27 @@ pub struct DataSection {
 pub struct MemorySection {
     /// Since we only support the memory with index 0 we use an Option as
     /// opposed to a vector. In the version of Wasm we support
-    pub memory_type: Option<MemoryType>,
+    pub memory_type:             Option<MemoryType>,
+    /// Memories with index 1 and above. This is always empty unless the
+    /// `multi-memory` feature is enabled, and it is only non-empty if
+    /// `memory_type` is present.
+    pub additional_memory_types: Vec<MemoryType>,
+}
+
+impl MemorySection {
+    /// Get the type of the memory with the given index, if it is declared.
+    pub fn get(&self, index: MemIndex) -> Option<&MemoryType> {
+        if index == 0 {
+            self.memory_type.as_ref()
+        } else {
+            self.additional_memory_types.get(index as usize - 1)
+        }
+    }
+
+    /// The number of declared memories.
+    pub fn num_memories(&self) -> usize {
+        self.memory_type.map_or(0, |_| 1 + self.additional_memory_types.len())
+    }
 }
 
 #[derive(Debug)]
@@ -416,14 +440,17 @@ pub struct MemoryType {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
-/// The immediate memory argument. Since all operations are on memory index 0
-/// the index is implicit.
+/// The immediate memory argument. The memory index is only encoded explicitly
+/// in the multi-memory proposal, otherwise it is implicitly 0.
 pub struct MemArg {
     /// The offest into the linear memory.
     pub offset: u32,
     /// Alignment. This is ignored by the Wasm semantics, but may be used as a
     /// hint. We will simply ignore it.
     pub align:  u32,
+    /// The memory that is accessed. This is always 0 unless the
+    /// `multi-memory` feature is enabled.
+    pub memory: MemIndex,
 }
 
 #[derive(Debug, Copy, Clone)]
@@ -584,4 +611,107 @@ pub enum OpCode {
     I32WrapI64,
     I64ExtendI32S,
     I64ExtendI32U,
+
+    I32Extend8S,
+    I32Extend16S,
+    I64Extend8S,
+    I64Extend16S,
+    I64Extend32S,
+}
+
+/// A visitor over instruction sequences. Instructions are stored in a flat
+/// sequence, with nested blocks delimited by the [`OpCode::Block`],
+/// [`OpCode::Loop`], [`OpCode::If`], [`OpCode::Else`], and [`OpCode::End`]
+/// instructions. The enter and leave hooks make this nesting explicit so that
+/// visitors do not have to keep track of it themselves.
+pub trait InstrVisitor {
+    /// Called for every instruction, in order, including the delimiters of
+    /// nested sequences.
+    fn visit(&mut self, instr: &OpCode);
+
+    /// Called after visiting a `block`, `loop`, or `if` instruction, before
+    /// any instructions in the nested sequence.
+    fn enter(&mut self, _block_type: BlockType) {}
+
+    /// Called after visiting an `else` instruction, when the `then` branch is
+    /// complete and before the instructions of the `else` branch.
+    fn enter_else(&mut self) {}
+
+    /// Called after visiting the `end` instruction that closes a nested
+    /// sequence. This is not called for the `end` that terminates the
+    /// outermost sequence, e.g., the body of a function.
+    fn leave(&mut self) {}
+}
+
+/// Types that contain instructions which can be traversed by an
+/// [`InstrVisitor`].
+///
+/// For example, to collect all the functions called by a function body
+///
+/// ```
+/// # use concordium_wasm::types::*;
+/// struct Calls(Vec<FuncIndex>);
+///
+/// impl InstrVisitor for Calls {
+///     fn visit(&mut self, instr: &OpCode) {
+///         if let OpCode::Call(idx) = instr {
+///             self.0.push(*idx)
+///         }
+///     }
+/// }
+///
+/// let expr = Expression::from(vec![
+///     OpCode::Block(BlockType::EmptyType),
+///     OpCode::Call(3),
+///     OpCode::End,
+///     OpCode::Call(1),
+///     OpCode::End,
+/// ]);
+/// let mut calls = Calls(Vec::new());
+/// expr.walk(&mut calls);
+/// assert_eq!(calls.0, vec![3, 1]);
+/// ```
+pub trait Walk {
+    /// Visit all the instructions in order.
+    fn walk<V: InstrVisitor>(&self, visitor: &mut V);
+}
+
+impl Walk for [OpCode] {
+    fn walk<V: InstrVisitor>(&self, visitor: &mut V) {
+        // Number of currently open nested sequences.
+        let mut depth = 0usize;
+        for instr in self.iter() {
+            visitor.visit(instr);
+            match instr {
+                OpCode::Block(ty)
+                | OpCode::Loop(ty)
+                | OpCode::If {
+                    ty,
+                } => {
+                    depth += 1;
+                    visitor.enter(*ty);
+                }
+                OpCode::Else => visitor.enter_else(),
+                OpCode::End => {
+                    if depth > 0 {
+                        depth -= 1;
+                        visitor.leave();
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+impl Walk for InstrSeq {
+    fn walk<V: InstrVisitor>(&self, visitor: &mut V) { self.as_slice().walk(visitor) }
+}
+
+impl Walk for Expression {
+    fn walk<V: InstrVisitor>(&self, visitor: &mut V) { self.instrs.walk(visitor) }
+}
+
+impl Walk for Code {
+    fn walk<V: InstrVisitor>(&self, visitor: &mut V) { self.expr.walk(visitor) }
 }
@@ -0,0 +1,74 @@
+//! Tests for traversing instruction sequences.
+use crate::types::{BlockType, Expression, InstrVisitor, OpCode, ValueType, Walk};
+
+/// The hooks called by [`Walk::walk`], in the order they were called.
+#[derive(Debug, Eq, PartialEq)]
+enum Event {
+    Visit(OpCode),
+    Enter(BlockType),
+    EnterElse,
+    Leave,
+}
+
+#[derive(Default)]
+struct Recorder(Vec<Event>);
+
+impl InstrVisitor for Recorder {
+    fn visit(&mut self, instr: &OpCode) { self.0.push(Event::Visit(instr.clone())) }
+
+    fn enter(&mut self, block_type: BlockType) { self.0.push(Event::Enter(block_type)) }
+
+    fn enter_else(&mut self) { self.0.push(Event::EnterElse) }
+
+    fn leave(&mut self) { self.0.push(Event::Leave) }
+}
+
+#[test]
+fn walk_nested_blocks() {
+    let i32_block = BlockType::ValueType(ValueType::I32);
+    let expr = Expression::from(vec![
+        OpCode::Block(BlockType::EmptyType),
+        OpCode::I32Const(1),
+        OpCode::If {
+            ty: i32_block,
+        },
+        OpCode::I32Const(2),
+        OpCode::Else,
+        OpCode::Loop(BlockType::EmptyType),
+        OpCode::End,
+        OpCode::I32Const(3),
+        OpCode::End,
+        OpCode::Drop,
+        OpCode::End,
+        OpCode::Nop,
+        OpCode::End,
+    ]);
+    let mut recorder = Recorder::default();
+    expr.walk(&mut recorder);
+    assert_eq!(recorder.0, vec![
+        Event::Visit(OpCode::Block(BlockType::EmptyType)),
+        Event::Enter(BlockType::EmptyType),
+        Event::Visit(OpCode::I32Const(1)),
+        Event::Visit(OpCode::If {
+            ty: i32_block,
+        }),
+        Event::Enter(i32_block),
+        Event::Visit(OpCode::I32Const(2)),
+        Event::Visit(OpCode::Else),
+        Event::EnterElse,
+        Event::Visit(OpCode::Loop(BlockType::EmptyType)),
+        Event::Enter(BlockType::EmptyType),
+        Event::Visit(OpCode::End),
+        Event::Leave,
+        Event::Visit(OpCode::I32Const(3)),
+        Event::Visit(OpCode::End),
+        Event::Leave,
+        Event::Visit(OpCode::Drop),
+        Event::Visit(OpCode::End),
+        Event::Leave,
+        Event::Visit(OpCode::Nop),
+        // The end of the outermost sequence is visited but does not close a
+        // nested sequence.
+        Event::Visit(OpCode::End),
+    ]);
+}
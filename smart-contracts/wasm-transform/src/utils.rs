@@ -3,12 +3,23 @@
 
 use crate::{
     artifact::{Artifact, CompiledFunction, CompiledFunctionBytes, TryFromImport},
-    parse::{parse_skeleton, GetParseable, Parseable, Skeleton},
+    artifact_input::{walk_artifact, ArtifactVisitor},
+    parse::{parse_custom, parse_skeleton, GetParseable, ParseResult, Parseable, Skeleton},
+    types::{FuncIndex, FunctionType, GlobalInit, Name},
     validate::{validate_module, ValidateImportExport},
 };
 
 /// Strip the custom sections from the module Wasm module.
-pub fn strip(skeleton: &mut Skeleton<'_>) { skeleton.custom = Vec::new(); }
+pub fn strip(skeleton: &mut Skeleton<'_>) { strip_except(skeleton, &[]) }
+
+/// Strip the custom sections from the Wasm module, except those whose name is
+/// in `keep`, e.g., the `name` section. Custom sections whose name cannot be
+/// parsed are always removed.
+pub fn strip_except(skeleton: &mut Skeleton<'_>, keep: &[&str]) {
+    skeleton.custom.retain(|sec| {
+        parse_custom(sec).map_or(false, |custom| keep.iter().any(|k| *k == custom.name.as_ref()))
+    });
+}
 
 /// Parse a Wasm module, validate, and compile to a runnable artifact.
 pub fn instantiate<I: TryFromImport, VI: ValidateImportExport>(
@@ -41,3 +52,59 @@ pub fn parse_artifact<'a, I: Parseable<'a, ()>>(
 ) -> anyhow::Result<Artifact<I, CompiledFunctionBytes<'a>>> {
     (&mut std::io::Cursor::new(bytes)).next(())
 }
+
+/// Sizes of the different parts of an artifact, as computed by
+/// [`estimate_artifact_size`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ArtifactStats {
+    /// Number of imported functions.
+    pub num_imports:   u32,
+    /// Number of function types.
+    pub num_types:     u32,
+    /// Number of entries in the table.
+    pub table_size:    u32,
+    /// Total number of bytes in the data segments of all the memories.
+    pub data_bytes:    u64,
+    /// Number of globals.
+    pub num_globals:   u32,
+    /// Number of exported functions.
+    pub num_exports:   u32,
+    /// Number of functions defined in the artifact.
+    pub num_functions: u32,
+    /// Total number of bytes of compiled code of all the functions.
+    pub code_bytes:    u64,
+}
+
+impl<'a, I> ArtifactVisitor<'a, I> for ArtifactStats {
+    fn import(&mut self, _import: I) { self.num_imports += 1 }
+
+    fn ty(&mut self, _ty: FunctionType) { self.num_types += 1 }
+
+    fn table_entry(&mut self, _entry: Option<FuncIndex>) { self.table_size += 1 }
+
+    fn memory(&mut self, _init_size: u32, _max_size: u32) {}
+
+    fn data(&mut self, _offset: i32, init: &'a [u8]) { self.data_bytes += init.len() as u64 }
+
+    fn global(&mut self, _init: GlobalInit) { self.num_globals += 1 }
+
+    fn export(&mut self, _name: Name, _idx: FuncIndex) { self.num_exports += 1 }
+
+    fn function(&mut self, code: CompiledFunctionBytes<'a>) {
+        self.num_functions += 1;
+        self.code_bytes += code.code.len() as u64;
+    }
+}
+
+/// Walk over a serialized artifact and compute the sizes of its parts without
+/// constructing the artifact. In contrast to [`parse_artifact`] this does not
+/// allocate memory proportional to the size of the artifact, so it can be used
+/// to reject artifacts that are too big before parsing them. It accepts the
+/// same artifacts as [`parse_artifact`].
+pub fn estimate_artifact_size<'a, I: Parseable<'a, ()>>(
+    bytes: &'a [u8],
+) -> ParseResult<ArtifactStats> {
+    let mut stats = ArtifactStats::default();
+    walk_artifact::<_, I, _>((), &mut std::io::Cursor::new(bytes), &mut stats)?;
+    Ok(stats)
+}
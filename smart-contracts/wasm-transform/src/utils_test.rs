@@ -0,0 +1,75 @@
+//! Tests for the utilities for working with modules and artifacts.
+use crate::{
+    artifact::{Artifact, ArtifactNamedImport, CompiledFunction, RunnableCode},
+    output::Output,
+    output_test::{parse_module, MODULE},
+    parse::parse_skeleton,
+    utils::{estimate_artifact_size, parse_artifact, strip, strip_except, ArtifactStats},
+};
+
+#[test]
+fn artifact_size_estimate() {
+    let artifact: Artifact<ArtifactNamedImport, CompiledFunction> =
+        parse_module(MODULE).compile().expect("Module should compile.");
+    let mut out = Vec::new();
+    artifact.output(&mut out).expect("Outputting to a vector should succeed.");
+    let stats = estimate_artifact_size::<ArtifactNamedImport>(&out)
+        .expect("Estimating the size of a valid artifact should succeed.");
+    assert_eq!(stats, ArtifactStats {
+        num_imports:   1,
+        num_types:     2,
+        table_size:    1,
+        data_bytes:    2,
+        num_globals:   1,
+        num_exports:   1,
+        num_functions: 1,
+        code_bytes:    artifact.code[0].code().len() as u64,
+    });
+    assert!(
+        estimate_artifact_size::<ArtifactNamedImport>(&out[..out.len() - 1]).is_err(),
+        "Estimating the size of a truncated artifact should fail."
+    );
+}
+
+#[test]
+fn artifact_duplicate_exports() {
+    let mut artifact: Artifact<ArtifactNamedImport, CompiledFunction> =
+        parse_module(MODULE).compile().expect("Module should compile.");
+    artifact.export.insert("zz1".into(), 1);
+    artifact.export.insert("zz2".into(), 1);
+    let mut out = Vec::new();
+    artifact.output(&mut out).expect("Outputting to a vector should succeed.");
+    parse_artifact::<ArtifactNamedImport>(&out).expect("Artifact should parse.");
+    estimate_artifact_size::<ArtifactNamedImport>(&out).expect("Artifact size should estimate.");
+    // Rename the last export so that it duplicates the one before it.
+    let pos = out.windows(3).position(|w| w == b"zz2").expect("The export name is in the output.");
+    out[pos + 2] = b'1';
+    assert!(
+        parse_artifact::<ArtifactNamedImport>(&out).is_err(),
+        "Parsing an artifact with duplicate exports should fail."
+    );
+    assert!(
+        estimate_artifact_size::<ArtifactNamedImport>(&out).is_err(),
+        "Estimating the size of an artifact with duplicate exports should fail."
+    );
+}
+
+#[test]
+fn strip_keeps_requested_sections() {
+    // The module with a `name` section (with an empty payload) and another custom
+    // section appended.
+    let name_section: &[u8] = &[0x00, 0x05, 0x04, 0x6E, 0x61, 0x6D, 0x65];
+    let other_section: &[u8] = &[0x00, 0x03, 0x01, 0x78, 0x2A];
+    let input = [MODULE, name_section, other_section].concat();
+    let mut skeleton = parse_skeleton(&input).expect("Module should parse.");
+    assert_eq!(skeleton.custom.len(), 2, "Both custom sections should be parsed.");
+    strip_except(&mut skeleton, &["name"]);
+    let mut out = Vec::new();
+    skeleton.output(&mut out).expect("Outputting to a vector should succeed.");
+    assert_eq!(out, [MODULE, name_section].concat(), "Only the name section should be kept.");
+
+    strip(&mut skeleton);
+    let mut out = Vec::new();
+    skeleton.output(&mut out).expect("Outputting to a vector should succeed.");
+    assert_eq!(out, MODULE, "Stripping should remove all custom sections.");
+}
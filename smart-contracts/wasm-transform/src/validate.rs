@@ -12,8 +12,8 @@
 use crate::{
     constants::*,
     parse::{
-        parse_custom, parse_sec_with_default, CodeSkeletonSection, OpCodeIterator, ParseResult,
-        Skeleton, EMPTY_CTX,
+        parse_custom, parse_sec_with_default, Byte, CodeSkeletonSection, OpCodeIterator,
+        ParseError, ParseResult, Skeleton,
     },
     types::*,
 };
@@ -26,6 +26,20 @@ pub enum ValidationError {
         actual: u32,
         max:    u32,
     },
+    TooManyFunctions {
+        actual: usize,
+        max:    usize,
+    },
+    FunctionBodyTooLarge {
+        func_idx: FuncIndex,
+        size:     u32,
+        max:      u32,
+    },
+    TooManyInstructions {
+        func_idx: FuncIndex,
+        actual:   usize,
+        max:      usize,
+    },
 }
 
 impl std::fmt::Display for ValidationError {
@@ -35,13 +49,96 @@ impl std::fmt::Display for ValidationError {
                 actual,
                 max,
             } => write!(f, "The number of locals ({}) is more than allowed ({}).", actual, max),
+            ValidationError::TooManyFunctions {
+                actual,
+                max,
+            } => write!(f, "The number of functions ({}) is more than allowed ({}).", actual, max),
+            ValidationError::FunctionBodyTooLarge {
+                func_idx,
+                size,
+                max,
+            } => write!(
+                f,
+                "The size of the body of function {} ({} bytes) is more than allowed ({} bytes).",
+                func_idx, size, max
+            ),
+            ValidationError::TooManyInstructions {
+                func_idx,
+                actual,
+                max,
+            } => write!(
+                f,
+                "The number of instructions in function {} ({}) is more than allowed ({}).",
+                func_idx, actual, max
+            ),
         }
     }
 }
 
+impl std::error::Error for ValidationError {}
+
 /// Result type of validation.
 pub type ValidateResult<A> = anyhow::Result<A>;
 
+#[derive(Debug, Clone, Copy)]
+/// Limits on the size of modules, and optional features, that are checked by
+/// [`validate_module_with`].
+///
+/// The [`Default`] instance accepts exactly the modules accepted by
+/// [`validate_module`]. In particular it imposes no limit on the number of
+/// functions or the size of their bodies, since that would change which
+/// modules are valid on the chain. [`ValidationConfig::with_limits`] enables
+/// the limits in the [`constants`](crate::constants) module.
+pub struct ValidationConfig {
+    /// Maximum number of functions, imported and declared, if any.
+    pub max_functions:    Option<usize>,
+    /// Maximum number of locals, including parameters, of a function. Values
+    /// larger than [`ALLOWED_LOCALS`] have no effect since compilation relies
+    /// on that bound.
+    pub max_locals:       u32,
+    /// Maximum number of instructions in a function body, if any.
+    pub max_instructions: Option<usize>,
+    /// Maximum declared size in bytes of a function body, if any.
+    pub max_body_size:    Option<u32>,
+    /// Whether the sign-extension instructions `i32.extend8_s`,
+    /// `i32.extend16_s`, `i64.extend8_s`, `i64.extend16_s`, and
+    /// `i64.extend32_s` are allowed. They are not part of the instruction set
+    /// accepted on the chain, so this is `false` by default.
+    pub sign_extension:   bool,
+    /// Whether import and export names must consist of printable ASCII
+    /// characters other than space. Names with control characters are
+    /// accepted on the chain, so this is `false` by default.
+    pub strict_names:     bool,
+}
+
+impl Default for ValidationConfig {
+    fn default() -> Self {
+        Self {
+            max_functions:    None,
+            max_locals:       ALLOWED_LOCALS,
+            max_instructions: None,
+            max_body_size:    None,
+            sign_extension:   false,
+            strict_names:     false,
+        }
+    }
+}
+
+impl ValidationConfig {
+    /// The default configuration, but additionally limiting the number of
+    /// functions to [`MAX_NUM_FUNCTIONS`], the size of function bodies to
+    /// [`MAX_FUNCTION_BODY_SIZE`], and the number of instructions in them to
+    /// [`MAX_NUM_INSTRUCTIONS`].
+    pub fn with_limits() -> Self {
+        Self {
+            max_functions: Some(MAX_NUM_FUNCTIONS),
+            max_instructions: Some(MAX_NUM_INSTRUCTIONS),
+            max_body_size: Some(MAX_FUNCTION_BODY_SIZE),
+            ..Self::default()
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 /// The operand stack containing either known or unknown types.
 /// Unknown types appear on the stack by the use of parametric instructions
@@ -272,17 +369,21 @@ pub(crate) struct FunctionContext<'a> {
     pub(crate) funcs:       &'a [TypeIndex],
     pub(crate) types:       &'a [Rc<FunctionType>],
     pub(crate) locals:      Vec<LocalsRange>,
-    // Whether memory exists or not.
-    pub(crate) memory:      bool,
+    // Number of declared memories.
+    pub(crate) memories:    usize,
     // Whether the table exists or not.
     pub(crate) table:       bool,
 }
 
 /// Make a locals structure used to validate a function body.
 /// This function additionally ensures that there are no more than
-/// ALLOWED_LOCALS local variables. Note that function parameters are included
+/// `max_locals` local variables. Note that function parameters are included
 /// in locals.
-fn make_locals(ty: &FunctionType, locals: &[Local]) -> ValidateResult<(Vec<LocalsRange>, u32)> {
+fn make_locals(
+    ty: &FunctionType,
+    locals: &[Local],
+    max_locals: u32,
+) -> ValidateResult<(Vec<LocalsRange>, u32)> {
     let mut out = Vec::with_capacity(ty.parameters.len() + locals.len());
     let mut start = 0;
     for &ty in ty.parameters.iter() {
@@ -305,9 +406,9 @@ fn make_locals(ty: &FunctionType, locals: &[Local]) -> ValidateResult<(Vec<Local
         start = end;
     }
     let num_locals = start;
-    ensure!(num_locals <= ALLOWED_LOCALS, ValidationError::TooManyLocals {
+    ensure!(num_locals <= max_locals, ValidationError::TooManyLocals {
         actual: num_locals,
-        max:    ALLOWED_LOCALS,
+        max:    max_locals,
     });
     Ok((out, num_locals))
 }
@@ -328,6 +429,10 @@ pub trait HasValidationContext {
     /// Return whether the module has memory.
     fn memory_exists(&self) -> bool;
 
+    /// Return whether the module has a memory with the given index. Unless the
+    /// `multi-memory` feature is enabled this is only memory 0.
+    fn memory_index_exists(&self, index: MemIndex) -> bool { index == 0 && self.memory_exists() }
+
     /// Return whether the module has the table.
     fn table_exists(&self) -> bool;
 
@@ -367,7 +472,9 @@ impl<'a> HasValidationContext for FunctionContext<'a> {
         }
     }
 
-    fn memory_exists(&self) -> bool { self.memory }
+    fn memory_exists(&self) -> bool { self.memories > 0 }
+
+    fn memory_index_exists(&self, index: MemIndex) -> bool { (index as usize) < self.memories }
 
     fn table_exists(&self) -> bool { self.table }
 
@@ -394,22 +501,23 @@ enum Type {
     I64,
 }
 
-/// Ensure that the alignment is valid for the given type.
-fn ensure_alignment(num: u32, align: Type) -> ValidateResult<()> {
-    match align {
-        Type::I8 => {
-            ensure!(num == 0, "Type I8 alignment must be less than 0, but is {}.", num);
-        }
-        Type::I16 => {
-            ensure!(num <= 1, "Type I16 alignment must be less than 1, but is {}.", num);
-        }
-        Type::I32 => {
-            ensure!(num <= 2, "Type I32 alignment must be less than 2, but is {}", num);
-        }
-        Type::I64 => {
-            ensure!(num <= 3, "Type I64 alignment must be less than 3, but is {}.", num);
-        }
-    }
+/// Ensure that the alignment exponent of a memory access is no larger than the
+/// natural alignment of the accessed type. The name of the instruction is only
+/// used in the error message.
+fn ensure_alignment(num: u32, align: Type, instr: &str) -> ValidateResult<()> {
+    let natural = match align {
+        Type::I8 => 0,
+        Type::I16 => 1,
+        Type::I32 => 2,
+        Type::I64 => 3,
+    };
+    ensure!(
+        num <= natural,
+        "Alignment exponent {} of {} exceeds the natural alignment exponent {}.",
+        num,
+        instr,
+        natural
+    );
     Ok(())
 }
 
@@ -612,117 +720,116 @@ pub fn validate<O: Borrow<OpCode>, H: Handler<O>>(
                 state.pop_expect_opd(Known(ty))?;
             }
             OpCode::I32Load(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I32)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I32, "i32.load")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I32));
             }
             OpCode::I64Load(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I64)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I64, "i64.load")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I64));
             }
             OpCode::I32Load8S(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I8)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I8, "i32.load8_s")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I32));
             }
             OpCode::I32Load8U(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I8)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I8, "i32.load8_u")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I32));
             }
             OpCode::I32Load16S(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I16)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I16, "i32.load16_s")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I32));
             }
             OpCode::I32Load16U(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I16)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I16, "i32.load16_u")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I32));
             }
             OpCode::I64Load8S(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I8)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I8, "i64.load8_s")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I64));
             }
             OpCode::I64Load8U(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I8)?;
-                ensure!(memarg.align == 0, "Alignment out of range");
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I8, "i64.load8_u")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I64));
             }
             OpCode::I64Load16S(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I16)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I16, "i64.load16_s")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I64));
             }
             OpCode::I64Load16U(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I16)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I16, "i64.load16_u")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I64));
             }
             OpCode::I64Load32S(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I32)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I32, "i64.load32_s")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I64));
             }
             OpCode::I64Load32U(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I32)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I32, "i64.load32_u")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I64));
             }
             OpCode::I32Store(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I32)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I32, "i32.store")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
             }
             OpCode::I64Store(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I64)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I64, "i64.store")?;
                 state.pop_expect_opd(Known(ValueType::I64))?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
             }
             OpCode::I32Store8(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I8)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I8, "i32.store8")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
             }
             OpCode::I32Store16(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I16)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I16, "i32.store16")?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
             }
             OpCode::I64Store8(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I8)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I8, "i64.store8")?;
                 state.pop_expect_opd(Known(ValueType::I64))?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
             }
             OpCode::I64Store16(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I16)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I16, "i64.store16")?;
                 state.pop_expect_opd(Known(ValueType::I64))?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
             }
             OpCode::I64Store32(memarg) => {
-                ensure!(context.memory_exists(), "Memory should exist.");
-                ensure_alignment(memarg.align, Type::I32)?;
+                ensure!(context.memory_index_exists(memarg.memory), "Memory should exist.");
+                ensure_alignment(memarg.align, Type::I32, "i64.store32")?;
                 state.pop_expect_opd(Known(ValueType::I64))?;
                 state.pop_expect_opd(Known(ValueType::I32))?;
             }
@@ -831,6 +938,14 @@ pub fn validate<O: Borrow<OpCode>, H: Handler<O>>(
                 state.pop_expect_opd(Known(ValueType::I32))?;
                 state.push_opd(Known(ValueType::I64));
             }
+            OpCode::I32Extend8S | OpCode::I32Extend16S => {
+                state.pop_expect_opd(Known(ValueType::I32))?;
+                state.push_opd(Known(ValueType::I32));
+            }
+            OpCode::I64Extend8S | OpCode::I64Extend16S | OpCode::I64Extend32S => {
+                state.pop_expect_opd(Known(ValueType::I64))?;
+                state.push_opd(Known(ValueType::I64));
+            }
         }
         handler.handle_opcode(&state, old_stack_height, next_opcode)?;
     }
@@ -858,11 +973,43 @@ pub trait ValidateImportExport {
     fn validate_export_function(&self, item_name: &Name, ty: &FunctionType) -> bool;
 }
 
+/// Check that a name of an import or export only consists of printable ASCII
+/// characters, excluding space. The parser already ensures that names are
+/// ASCII, this additionally excludes control characters.
+fn is_valid_import_export_name(name: &Name) -> bool {
+    name.as_ref().bytes().all(|b| b.is_ascii_graphic())
+}
+
+/// The opcode of the instruction if it is one of the sign-extension
+/// instructions, and [`None`] otherwise.
+fn sign_extension_opcode(opcode: &OpCode) -> Option<Byte> {
+    match opcode {
+        OpCode::I32Extend8S => Some(0xC0),
+        OpCode::I32Extend16S => Some(0xC1),
+        OpCode::I64Extend8S => Some(0xC2),
+        OpCode::I64Extend16S => Some(0xC3),
+        OpCode::I64Extend32S => Some(0xC4),
+        _ => None,
+    }
+}
+
 /// Validate the module. This function parses and validates the module at the
 /// same time, failing at the first encountered error.
+///
+/// This is [`validate_module_with`] with the default [`ValidationConfig`].
 pub fn validate_module<'a>(
     imp: &impl ValidateImportExport,
     skeleton: &Skeleton<'a>,
+) -> ValidateResult<Module> {
+    validate_module_with(imp, skeleton, &ValidationConfig::default())
+}
+
+/// Same as [`validate_module`], but with the limits and features given by the
+/// configuration.
+pub fn validate_module_with<'a>(
+    imp: &impl ValidateImportExport,
+    skeleton: &Skeleton<'a>,
+    config: &ValidationConfig,
 ) -> ValidateResult<Module> {
     // This is a technicality, but we need to parse the custom sections to ensure
     // that they are valid. Validity consists only of checking that the name part
@@ -872,9 +1019,9 @@ pub fn validate_module<'a>(
     }
 
     // The type section is valid as long as it's well-formed.
-    let ty: TypeSection = parse_sec_with_default(EMPTY_CTX, &skeleton.ty)?;
+    let ty: TypeSection = parse_sec_with_default(skeleton.config(), &skeleton.ty)?;
     // Imports are valid as long as they parse, and all the indices exist.
-    let import: ImportSection = parse_sec_with_default(EMPTY_CTX, &skeleton.import)?;
+    let import: ImportSection = parse_sec_with_default(skeleton.config(), &skeleton.import)?;
     {
         let mut seen_imports = BTreeSet::new();
         for i in import.imports.iter() {
@@ -882,6 +1029,14 @@ pub fn validate_module<'a>(
                 ImportDescription::Func {
                     type_idx,
                 } => {
+                    ensure!(
+                        !config.strict_names
+                            || (is_valid_import_export_name(&i.mod_name)
+                                && is_valid_import_export_name(&i.item_name)),
+                        "Import name {:?}.{:?} contains disallowed characters.",
+                        i.mod_name.as_ref(),
+                        i.item_name.as_ref()
+                    );
                     if let Some(ty) = ty.get(type_idx) {
                         let is_new = seen_imports.insert((&i.mod_name, &i.item_name));
                         ensure!(
@@ -897,16 +1052,16 @@ pub fn validate_module<'a>(
     }
     // The table section is valid as long as it's well-formed.
     // We already check the limits at parse time.
-    let table: TableSection = parse_sec_with_default(EMPTY_CTX, &skeleton.table)?;
+    let table: TableSection = parse_sec_with_default(skeleton.config(), &skeleton.table)?;
 
     // The memory section is valid as long as it's well-formed.
     // We already check the limits at parse time.
-    let memory: MemorySection = parse_sec_with_default(EMPTY_CTX, &skeleton.memory)?;
+    let memory: MemorySection = parse_sec_with_default(skeleton.config(), &skeleton.memory)?;
 
     // The global section is valid as long as it's well-formed.
     // We already check that all the globals are initialized with
     // correct expressions.
-    let global: GlobalSection = parse_sec_with_default(EMPTY_CTX, &skeleton.global)?;
+    let global: GlobalSection = parse_sec_with_default(skeleton.config(), &skeleton.global)?;
     ensure!(
         global.globals.len() <= MAX_NUM_GLOBALS,
         "The number of globals must not exceed {}.",
@@ -915,12 +1070,12 @@ pub fn validate_module<'a>(
 
     // The start section is valid as long as it parses correctly.
     // We make sure that there is no content in the start section during parsing.
-    let start = parse_sec_with_default(EMPTY_CTX, &skeleton.start)?;
+    let start = parse_sec_with_default(skeleton.config(), &skeleton.start)?;
 
     // The function type section is valid if it parses properly, and all the indices
     // of types are valid.
     // The code section then needs to match.
-    let func: FunctionSection = parse_sec_with_default(EMPTY_CTX, &skeleton.func)?;
+    let func: FunctionSection = parse_sec_with_default(skeleton.config(), &skeleton.func)?;
     for &type_idx in func.types.iter() {
         ensure!(ty.get(type_idx).is_some(), "Function refers to a type that does not exist.")
     }
@@ -929,13 +1084,22 @@ pub fn validate_module<'a>(
     // Since all imports must be functions we could just use length, but
     // in the interest of being more robust to changes we count imported functions
     // instead.
-    let total_funcs =
-        import.imports.iter().filter(|&x| Import::is_func(x)).count() + func.types.len();
+    let num_imported_funcs = import.imports.iter().filter(|&x| Import::is_func(x)).count();
+    let total_funcs = num_imported_funcs + func.types.len();
+    if let Some(max) = config.max_functions {
+        ensure!(total_funcs <= max, ValidationError::TooManyFunctions {
+            actual: total_funcs,
+            max,
+        });
+    }
 
-    let code: CodeSkeletonSection = parse_sec_with_default(EMPTY_CTX, &skeleton.code)?;
+    let code: CodeSkeletonSection = parse_sec_with_default(skeleton.config(), &skeleton.code)?;
     ensure!(
         func.types.len() == code.impls.len(),
-        "The number of functions in the function and code sections must match."
+        "The number of functions in the function and code sections must match ({} declared, {} \
+         bodies).",
+        func.types.len(),
+        code.impls.len()
     );
     // an index of function types, merging imported and declared functions.
     let funcs = import
@@ -949,22 +1113,52 @@ pub fn validate_module<'a>(
         .chain(func.types.iter().copied())
         .collect::<Vec<TypeIndex>>();
 
+    let max_locals = std::cmp::min(config.max_locals, ALLOWED_LOCALS);
     let mut parsed_code = Vec::with_capacity(code.impls.len());
-    for (&f, c) in func.types.iter().zip(code.impls) {
+    for (i, (&f, c)) in func.types.iter().zip(code.impls).enumerate() {
+        // Index of the function in the function index space, which starts with
+        // imported functions. It is only used for reporting errors.
+        let func_idx = (num_imported_funcs + i) as FuncIndex;
+        if let Some(max) = config.max_body_size {
+            ensure!(c.size <= max, ValidationError::FunctionBodyTooLarge {
+                func_idx,
+                size: c.size,
+                max,
+            });
+        }
         match ty.get(f) {
             Some(func_ty) => {
-                let (locals, num_locals) = make_locals(func_ty, &c.locals)?;
+                let (locals, num_locals) = make_locals(func_ty, &c.locals, max_locals)?;
                 let ctx = FunctionContext {
                     return_type: BlockType::from(func_ty.result),
                     globals: &global.globals,
                     funcs: &funcs,
                     types: &ty.types,
                     locals,
-                    memory: memory.memory_type.is_some(),
+                    memories: memory.num_memories(),
                     table: table.table_type.is_some(),
                 };
-                let (opcodes, max_height) =
-                    validate(&ctx, &mut OpCodeIterator::new(c.expr_bytes), Vec::new())?;
+                // Unless enabled, sign-extension instructions are rejected in the
+                // same way as other unsupported instructions.
+                let opcodes = OpCodeIterator::new(c.expr_bytes).map(|opcode| {
+                    let opcode = opcode?;
+                    if !config.sign_extension {
+                        if let Some(opcode) = sign_extension_opcode(&opcode) {
+                            bail!(ParseError::UnsupportedInstruction {
+                                opcode
+                            })
+                        }
+                    }
+                    Ok(opcode)
+                });
+                let (opcodes, max_height) = validate(&ctx, opcodes, Vec::new())?;
+                if let Some(max) = config.max_instructions {
+                    ensure!(opcodes.len() <= max, ValidationError::TooManyInstructions {
+                        func_idx,
+                        actual: opcodes.len(),
+                        max,
+                    });
+                }
                 ensure!(
                     num_locals as usize + max_height <= MAX_ALLOWED_STACK_HEIGHT,
                     "Stack height would exceed allowed limits."
@@ -986,12 +1180,17 @@ pub fn validate_module<'a>(
     }
     // Exports are mostly valid by parsing, but we need to make sure that
     // they are all distinct.
-    let export: ExportSection = parse_sec_with_default(EMPTY_CTX, &skeleton.export)?;
+    let export: ExportSection = parse_sec_with_default(skeleton.config(), &skeleton.export)?;
     let mut export_names = BTreeSet::new();
     ensure!(export.exports.len() <= MAX_NUM_EXPORTS, "Module exceeds maximum number of exports.");
     for e in export.exports.iter() {
         // ensure the name is unique.
         ensure!(export_names.insert(&e.name), "Duplicate exports {}.", e.name);
+        ensure!(
+            !config.strict_names || is_valid_import_export_name(&e.name),
+            "Export name {:?} contains disallowed characters.",
+            e.name.as_ref()
+        );
 
         match e.description {
             ExportDescription::Func {
@@ -1031,7 +1230,8 @@ pub fn validate_module<'a>(
     // the offset expression is of the correct type and constant.
     // We additionally need to check that all the functions referred
     // to in the table are defined.
-    let element: ElementSection = parse_sec_with_default(&global, &skeleton.element)?;
+    let element: ElementSection =
+        parse_sec_with_default((&global, skeleton.config()), &skeleton.element)?;
     ensure!(
         element.elements.is_empty() || table.table_type.is_some(),
         "There is an elements section, but no table."
@@ -1043,11 +1243,12 @@ pub fn validate_module<'a>(
             "Number of initial elements is more than the table size."
         );
         if let Some(table_type) = table.table_type.as_ref() {
+            // The offset is interpreted as unsigned, so a negative offset is a very
+            // large one, which is then rejected by the bounds check below.
             let offset = elem.offset as u32;
             // since we provide no way to grow the table the initial minimum size
             // is the size of the table, as specified in the allocation section of the
             // Wasm semantics.
-            // The as u32 is safe beca
             let end = offset
                 .checked_add(inits_len)
                 .ok_or_else(|| anyhow!("The end of the table exceeds u32 max bound."))?;
@@ -1071,36 +1272,36 @@ pub fn validate_module<'a>(
     // the offset expression is of the correct type and constant.
     // We additionally need to check that all the locations referred
     // to in the table are defined.
-    let data: DataSection = parse_sec_with_default(&global, &skeleton.data)?;
-    // Make sure that if there are any data segments then a memory exists.
-    // By parsing we already ensure that all the references are to a single memory
-    // and that the initial memory is limited by MAX_INIT_MEMORY_SIZE.
-    if let Some(memory_type) = memory.memory_type.as_ref() {
-        for data in data.sections.iter() {
-            let inits_len: u32 = data.init.len().try_into()?;
-            ensure!(
-                // this cannot overflow because we've already ensured limits.min <
-                // MAX_INIT_MEMORY_SIZE
-                inits_len <= memory_type.limits.min * PAGE_SIZE,
-                "Number of initial elements is more than the initial memory size."
-            );
-            let offset: u32 = data.offset.try_into()?;
-            let end = offset
-                .checked_add(inits_len)
-                .ok_or_else(|| anyhow!("The end of the memory exceeds u32 max bound."))?;
-            ensure!(
-                // by validation we have that memory_type.limits.min <= MAX_INIT_MEMORY_SIZE <
-                // 2^16, so this cannot overflow but we're still being safe
-                memory_type.limits.min.checked_mul(PAGE_SIZE).map_or(false, |l| end <= l),
-                "Initialization expression for the data segment exceeds initial memory size {} > \
-                 {}.",
-                end,
-                memory_type.limits.min * PAGE_SIZE
-            );
-        }
-    } else {
-        // There is no memory, so there should be no data section.
-        ensure!(data.sections.is_empty(), "There are data sections, but no declared memory.");
+    let data: DataSection = parse_sec_with_default((&global, skeleton.config()), &skeleton.data)?;
+    // Make sure that the memory of each data segment exists.
+    // By parsing we already ensure that, unless the `multi-memory` feature is
+    // enabled, all the references are to a single memory, and that the initial
+    // memory is limited by MAX_INIT_MEMORY_SIZE.
+    for data in data.sections.iter() {
+        let memory_type = match memory.get(data.memory) {
+            Some(memory_type) => memory_type,
+            None if data.memory == 0 => bail!("There are data sections, but no declared memory."),
+            None => bail!("Data segment refers to memory {}, which is not declared.", data.memory),
+        };
+        let inits_len: u32 = data.init.len().try_into()?;
+        ensure!(
+            // this cannot overflow because we've already ensured limits.min <
+            // MAX_INIT_MEMORY_SIZE
+            inits_len <= memory_type.limits.min * PAGE_SIZE,
+            "Number of initial elements is more than the initial memory size."
+        );
+        let offset: u32 = data.offset.try_into()?;
+        let end = offset
+            .checked_add(inits_len)
+            .ok_or_else(|| anyhow!("The end of the memory exceeds u32 max bound."))?;
+        ensure!(
+            // by validation we have that memory_type.limits.min <= MAX_INIT_MEMORY_SIZE <
+            // 2^16, so this cannot overflow but we're still being safe
+            memory_type.limits.min.checked_mul(PAGE_SIZE).map_or(false, |l| end <= l),
+            "Initialization expression for the data segment exceeds initial memory size {} > {}.",
+            end,
+            memory_type.limits.min * PAGE_SIZE
+        );
     }
     Ok(Module {
         ty,
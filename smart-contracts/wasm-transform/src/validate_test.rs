@@ -0,0 +1,178 @@
+//! Tests for validation of modules.
+use crate::{
+    output_test::{AllowAll, MODULE},
+    parse::{parse_skeleton, ParseError},
+    types::Module,
+    validate::{validate_module, validate_module_with, ValidationConfig, ValidationError},
+};
+
+/// The opcode and operand type of each of the sign-extension instructions.
+pub(crate) const SIGN_EXTENSION_OPCODES: [(u8, u8); 5] =
+    [(0xC0, 0x7F), (0xC1, 0x7F), (0xC2, 0x7E), (0xC3, 0x7E), (0xC4, 0x7E)];
+
+/// A module that exports a single function `f` with a parameter of the given
+/// type and a result of the given type. The body applies the unary
+/// instruction with the given opcode to the parameter.
+pub(crate) fn unop_module(param: u8, result: u8, opcode: u8) -> Vec<u8> {
+    vec![
+        0x00, 0x61, 0x73, 0x6D, 0x01, 0x00, 0x00, 0x00, // magic hash and version
+        0x01, 0x06, 0x01, 0x60, 0x01, param, 0x01, result, // types
+        0x03, 0x02, 0x01, 0x00, // functions
+        0x07, 0x05, 0x01, 0x01, 0x66, 0x00, 0x00, // exports
+        0x0A, 0x07, 0x01, 0x05, 0x00, 0x20, 0x00, opcode, 0x0B, // code
+    ]
+}
+
+/// Validate the module with the sign-extension instructions enabled.
+pub(crate) fn validate_with_sign_extension(bytes: &[u8]) -> anyhow::Result<Module> {
+    let config = ValidationConfig {
+        sign_extension: true,
+        ..ValidationConfig::default()
+    };
+    validate_module_with(&AllowAll, &parse_skeleton(bytes)?, &config)
+}
+
+#[test]
+fn sign_extension_rejected_by_default() {
+    for &(opcode, ty) in SIGN_EXTENSION_OPCODES.iter() {
+        let bytes = unop_module(ty, ty, opcode);
+        let skeleton = parse_skeleton(&bytes).expect("Module should parse.");
+        let err = validate_module(&AllowAll, &skeleton)
+            .expect_err("Sign-extension instructions should be rejected by default.");
+        assert!(
+            matches!(
+                err.downcast_ref::<ParseError>(),
+                Some(ParseError::UnsupportedInstruction { opcode: found }) if *found == opcode
+            ),
+            "Unexpected error {} for opcode {:#04x}.",
+            err,
+            opcode
+        );
+    }
+}
+
+#[test]
+fn sign_extension_allowed() {
+    for &(opcode, ty) in SIGN_EXTENSION_OPCODES.iter() {
+        let bytes = unop_module(ty, ty, opcode);
+        if let Err(e) = validate_with_sign_extension(&bytes) {
+            panic!("Opcode {:#04x} should validate when enabled: {}", opcode, e);
+        }
+    }
+}
+
+#[test]
+fn sign_extension_type_mismatch() {
+    for &(opcode, ty) in SIGN_EXTENSION_OPCODES.iter() {
+        // The other integer type is used for both the operand and the result.
+        let other = if ty == 0x7F {
+            0x7E
+        } else {
+            0x7F
+        };
+        let bytes = unop_module(other, other, opcode);
+        assert!(
+            validate_with_sign_extension(&bytes).is_err(),
+            "Opcode {:#04x} should not accept operands of the wrong type.",
+            opcode
+        );
+    }
+}
+
+/// Validate [`MODULE`], which has one imported and one declared function, with
+/// the given configuration.
+fn validate_limited(config: ValidationConfig) -> anyhow::Result<Module> {
+    validate_module_with(&AllowAll, &parse_skeleton(MODULE)?, &config)
+}
+
+#[test]
+fn limits_disabled_by_default() {
+    let skeleton = parse_skeleton(MODULE).expect("Module should parse.");
+    validate_module(&AllowAll, &skeleton).expect("Module should validate.");
+    let config = ValidationConfig {
+        max_functions: Some(1),
+        max_instructions: Some(1),
+        max_body_size: Some(1),
+        ..ValidationConfig::default()
+    };
+    assert!(validate_limited(config).is_err(), "Limits should be enforced when set.");
+    validate_limited(ValidationConfig::with_limits())
+        .expect("Module should be within the recommended limits.");
+}
+
+#[test]
+fn too_many_functions() {
+    let err = validate_limited(ValidationConfig {
+        max_functions: Some(1),
+        ..ValidationConfig::default()
+    })
+    .expect_err("Module has too many functions.");
+    assert!(
+        matches!(
+            err.downcast_ref::<ValidationError>(),
+            Some(ValidationError::TooManyFunctions {
+                actual: 2,
+                max:    1,
+            })
+        ),
+        "Unexpected error {}.",
+        err
+    );
+}
+
+#[test]
+fn function_body_too_large() {
+    let err = validate_limited(ValidationConfig {
+        max_body_size: Some(8),
+        ..ValidationConfig::default()
+    })
+    .expect_err("Function body is too large.");
+    assert!(
+        matches!(
+            err.downcast_ref::<ValidationError>(),
+            Some(ValidationError::FunctionBodyTooLarge {
+                func_idx: 1,
+                size:     9,
+                max:      8,
+            })
+        ),
+        "Unexpected error {}.",
+        err
+    );
+}
+
+#[test]
+fn too_many_instructions() {
+    let err = validate_limited(ValidationConfig {
+        max_instructions: Some(3),
+        ..ValidationConfig::default()
+    })
+    .expect_err("Function has too many instructions.");
+    assert!(
+        matches!(err.downcast_ref::<ValidationError>(), Some(ValidationError::TooManyInstructions {
+            func_idx: 1,
+            actual,
+            max: 3
+        }) if *actual > 3),
+        "Unexpected error {}.",
+        err
+    );
+}
+
+#[test]
+#[cfg(feature = "multi-memory")]
+fn memory_index_out_of_range() {
+    // Change the memory index of the first load in the module to 2, which does
+    // not exist.
+    let mut bytes = crate::parse_test::TWO_MEMORIES.to_vec();
+    let load = bytes
+        .windows(3)
+        .position(|w| w == [0x28, 0x42, 0x01])
+        .expect("The module should contain a load from memory 1.");
+    bytes[load + 2] = 0x02;
+    let skeleton = parse_skeleton(&bytes).expect("Module should parse.");
+    assert!(
+        validate_module(&AllowAll, &skeleton).is_err(),
+        "Accessing a memory that is not declared should be rejected."
+    );
+}
@@ -0,0 +1,351 @@
+//! An approximate printer of modules in the WebAssembly text format. This is
+//! intended for debugging and is not guaranteed to produce output that can be
+//! parsed back by other tools.
+
+use crate::types::*;
+use std::fmt::Write;
+
+/// Indentation used per nesting level.
+const INDENT: &str = "  ";
+
+impl Module {
+    /// Render the module in (approximately) the WebAssembly text format.
+    /// Nested blocks in function bodies are indented.
+    pub fn to_wat(&self) -> String {
+        let mut out = String::new();
+        // Writing to a string cannot fail.
+        let _ = self.write_wat(&mut out);
+        out
+    }
+
+    fn write_wat(&self, out: &mut String) -> std::fmt::Result {
+        writeln!(out, "(module")?;
+        for (i, ty) in self.ty.types.iter().enumerate() {
+            writeln!(out, "{}(type (;{};) (func{}))", INDENT, i, func_type_wat(ty))?;
+        }
+        for (i, import) in self.import.imports.iter().enumerate() {
+            match import.description {
+                ImportDescription::Func {
+                    type_idx,
+                } => writeln!(
+                    out,
+                    "{}(import {} {} (func (;{};) (type {})))",
+                    INDENT,
+                    string_wat(import.mod_name.as_ref().as_bytes()),
+                    string_wat(import.item_name.as_ref().as_bytes()),
+                    i,
+                    type_idx
+                )?,
+            }
+        }
+        if let Some(table_type) = self.table.table_type {
+            writeln!(out, "{}(table (;0;) {} funcref)", INDENT, limits_wat(table_type.limits))?;
+        }
+        if let Some(memory_type) = self.memory.memory_type {
+            writeln!(out, "{}(memory (;0;) {})", INDENT, limits_wat(memory_type.limits))?;
+        }
+        for (i, memory_type) in self.memory.additional_memory_types.iter().enumerate() {
+            writeln!(out, "{}(memory (;{};) {})", INDENT, i + 1, limits_wat(memory_type.limits))?;
+        }
+        for (i, global) in self.global.globals.iter().enumerate() {
+            let ty = ValueType::from(global);
+            let ty = if global.mutable {
+                format!("(mut {})", value_type_wat(ty))
+            } else {
+                value_type_wat(ty).to_owned()
+            };
+            writeln!(out, "{}(global (;{};) {} ({}))", INDENT, i, ty, const_wat(global.init))?;
+        }
+        for export in self.export.exports.iter() {
+            let description = match export.description {
+                ExportDescription::Func {
+                    index,
+                } => format!("func {}", index),
+                ExportDescription::Table => "table 0".to_owned(),
+                ExportDescription::Memory => "memory 0".to_owned(),
+                ExportDescription::Global {
+                    index,
+                } => format!("global {}", index),
+            };
+            writeln!(
+                out,
+                "{}(export {} ({}))",
+                INDENT,
+                string_wat(export.name.as_ref().as_bytes()),
+                description
+            )?;
+        }
+        for (i, element) in self.element.elements.iter().enumerate() {
+            write!(out, "{}(elem (;{};) (i32.const {}) func", INDENT, i, element.offset)?;
+            for init in element.inits.iter() {
+                write!(out, " {}", init)?;
+            }
+            writeln!(out, ")")?;
+        }
+        let num_imports = self.import.imports.len();
+        for (i, code) in self.code.impls.iter().enumerate() {
+            write!(
+                out,
+                "{}(func (;{};) (type {}){}",
+                INDENT,
+                num_imports + i,
+                code.ty_idx,
+                func_type_wat(&code.ty)
+            )?;
+            for local in code.locals.iter() {
+                write!(out, "\n{}{}(local", INDENT, INDENT)?;
+                for _ in 0..local.multiplicity {
+                    write!(out, " {}", value_type_wat(local.ty))?;
+                }
+                write!(out, ")")?;
+            }
+            write_instrs_wat(out, &code.expr.instrs)?;
+            writeln!(out, ")")?;
+        }
+        for (i, data) in self.data.sections.iter().enumerate() {
+            let memory = if data.memory == 0 {
+                String::new()
+            } else {
+                format!(" (memory {})", data.memory)
+            };
+            writeln!(
+                out,
+                "{}(data (;{};){} (i32.const {}) {})",
+                INDENT,
+                i,
+                memory,
+                data.offset,
+                string_wat(&data.init)
+            )?;
+        }
+        writeln!(out, ")")
+    }
+}
+
+fn value_type_wat(ty: ValueType) -> &'static str {
+    match ty {
+        ValueType::I32 => "i32",
+        ValueType::I64 => "i64",
+    }
+}
+
+fn func_type_wat(ty: &FunctionType) -> String {
+    let mut out = String::new();
+    if !ty.parameters.is_empty() {
+        out.push_str(" (param");
+        for &param in ty.parameters.iter() {
+            out.push(' ');
+            out.push_str(value_type_wat(param));
+        }
+        out.push(')');
+    }
+    if let Some(result) = ty.result {
+        out.push_str(" (result ");
+        out.push_str(value_type_wat(result));
+        out.push(')');
+    }
+    out
+}
+
+fn limits_wat(limits: Limits) -> String {
+    match limits.max {
+        None => format!("{}", limits.min),
+        Some(max) => format!("{} {}", limits.min, max),
+    }
+}
+
+fn const_wat(init: GlobalInit) -> String {
+    match init {
+        GlobalInit::I32(x) => format!("i32.const {}", x),
+        GlobalInit::I64(x) => format!("i64.const {}", x),
+    }
+}
+
+/// Render bytes as a string literal. Printable ASCII characters are output
+/// as is, and all other bytes are escaped.
+fn string_wat(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len() + 2);
+    out.push('"');
+    for &b in bytes {
+        if (0x20..0x7F).contains(&b) && b != b'"' && b != b'\\' {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("\\{:02x}", b));
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn block_type_wat(bt: BlockType) -> String {
+    match bt {
+        BlockType::EmptyType => String::new(),
+        BlockType::ValueType(ty) => format!(" (result {})", value_type_wat(ty)),
+    }
+}
+
+fn memarg_wat(memarg: &MemArg) -> String {
+    let mut out = String::new();
+    if memarg.memory != 0 {
+        out.push_str(&format!(" {}", memarg.memory));
+    }
+    if memarg.offset != 0 {
+        out.push_str(&format!(" offset={}", memarg.offset));
+    }
+    // The alignment is stored as an exponent, but written in bytes.
+    out.push_str(&format!(" align={}", 1u64 << memarg.align.min(63)));
+    out
+}
+
+/// Write the instruction sequence of a function body, each instruction on its
+/// own line. The final `end` of the body is not written.
+fn write_instrs_wat(out: &mut String, instrs: &[OpCode]) -> std::fmt::Result {
+    // The body of the function is at nesting level 2 in the module.
+    let mut depth = 2usize;
+    let len = instrs.len();
+    for (i, instr) in instrs.iter().enumerate() {
+        if i + 1 == len && *instr == OpCode::End {
+            break;
+        }
+        if matches!(instr, OpCode::End | OpCode::Else) {
+            depth = depth.saturating_sub(1);
+        }
+        out.push('\n');
+        for _ in 0..depth {
+            out.push_str(INDENT);
+        }
+        out.push_str(&instr_wat(instr));
+        if matches!(instr, OpCode::Block(_) | OpCode::Loop(_) | OpCode::If { .. } | OpCode::Else) {
+            depth += 1;
+        }
+    }
+    Ok(())
+}
+
+fn instr_wat(instr: &OpCode) -> String {
+    use OpCode::*;
+    let name = match instr {
+        End => "end",
+        Nop => "nop",
+        Unreachable => "unreachable",
+        Block(bt) => return format!("block{}", block_type_wat(*bt)),
+        Loop(bt) => return format!("loop{}", block_type_wat(*bt)),
+        If {
+            ty,
+        } => return format!("if{}", block_type_wat(*ty)),
+        Else => "else",
+        Br(l) => return format!("br {}", l),
+        BrIf(l) => return format!("br_if {}", l),
+        BrTable {
+            labels,
+            default,
+        } => {
+            let mut out = "br_table".to_owned();
+            for l in labels.iter() {
+                out.push_str(&format!(" {}", l));
+            }
+            out.push_str(&format!(" {}", default));
+            return out;
+        }
+        Return => "return",
+        Call(idx) => return format!("call {}", idx),
+        CallIndirect(ty) => return format!("call_indirect (type {})", ty),
+        Drop => "drop",
+        Select => "select",
+        LocalGet(idx) => return format!("local.get {}", idx),
+        LocalSet(idx) => return format!("local.set {}", idx),
+        LocalTee(idx) => return format!("local.tee {}", idx),
+        GlobalGet(idx) => return format!("global.get {}", idx),
+        GlobalSet(idx) => return format!("global.set {}", idx),
+        I32Load(m) => return format!("i32.load{}", memarg_wat(m)),
+        I64Load(m) => return format!("i64.load{}", memarg_wat(m)),
+        I32Load8S(m) => return format!("i32.load8_s{}", memarg_wat(m)),
+        I32Load8U(m) => return format!("i32.load8_u{}", memarg_wat(m)),
+        I32Load16S(m) => return format!("i32.load16_s{}", memarg_wat(m)),
+        I32Load16U(m) => return format!("i32.load16_u{}", memarg_wat(m)),
+        I64Load8S(m) => return format!("i64.load8_s{}", memarg_wat(m)),
+        I64Load8U(m) => return format!("i64.load8_u{}", memarg_wat(m)),
+        I64Load16S(m) => return format!("i64.load16_s{}", memarg_wat(m)),
+        I64Load16U(m) => return format!("i64.load16_u{}", memarg_wat(m)),
+        I64Load32S(m) => return format!("i64.load32_s{}", memarg_wat(m)),
+        I64Load32U(m) => return format!("i64.load32_u{}", memarg_wat(m)),
+        I32Store(m) => return format!("i32.store{}", memarg_wat(m)),
+        I64Store(m) => return format!("i64.store{}", memarg_wat(m)),
+        I32Store8(m) => return format!("i32.store8{}", memarg_wat(m)),
+        I32Store16(m) => return format!("i32.store16{}", memarg_wat(m)),
+        I64Store8(m) => return format!("i64.store8{}", memarg_wat(m)),
+        I64Store16(m) => return format!("i64.store16{}", memarg_wat(m)),
+        I64Store32(m) => return format!("i64.store32{}", memarg_wat(m)),
+        MemorySize => "memory.size",
+        MemoryGrow => "memory.grow",
+        I32Const(n) => return format!("i32.const {}", n),
+        I64Const(n) => return format!("i64.const {}", n),
+        I32Eqz => "i32.eqz",
+        I32Eq => "i32.eq",
+        I32Ne => "i32.ne",
+        I32LtS => "i32.lt_s",
+        I32LtU => "i32.lt_u",
+        I32GtS => "i32.gt_s",
+        I32GtU => "i32.gt_u",
+        I32LeS => "i32.le_s",
+        I32LeU => "i32.le_u",
+        I32GeS => "i32.ge_s",
+        I32GeU => "i32.ge_u",
+        I64Eqz => "i64.eqz",
+        I64Eq => "i64.eq",
+        I64Ne => "i64.ne",
+        I64LtS => "i64.lt_s",
+        I64LtU => "i64.lt_u",
+        I64GtS => "i64.gt_s",
+        I64GtU => "i64.gt_u",
+        I64LeS => "i64.le_s",
+        I64LeU => "i64.le_u",
+        I64GeS => "i64.ge_s",
+        I64GeU => "i64.ge_u",
+        I32Clz => "i32.clz",
+        I32Ctz => "i32.ctz",
+        I32Popcnt => "i32.popcnt",
+        I32Add => "i32.add",
+        I32Sub => "i32.sub",
+        I32Mul => "i32.mul",
+        I32DivS => "i32.div_s",
+        I32DivU => "i32.div_u",
+        I32RemS => "i32.rem_s",
+        I32RemU => "i32.rem_u",
+        I32And => "i32.and",
+        I32Or => "i32.or",
+        I32Xor => "i32.xor",
+        I32Shl => "i32.shl",
+        I32ShrS => "i32.shr_s",
+        I32ShrU => "i32.shr_u",
+        I32Rotl => "i32.rotl",
+        I32Rotr => "i32.rotr",
+        I64Clz => "i64.clz",
+        I64Ctz => "i64.ctz",
+        I64Popcnt => "i64.popcnt",
+        I64Add => "i64.add",
+        I64Sub => "i64.sub",
+        I64Mul => "i64.mul",
+        I64DivS => "i64.div_s",
+        I64DivU => "i64.div_u",
+        I64RemS => "i64.rem_s",
+        I64RemU => "i64.rem_u",
+        I64And => "i64.and",
+        I64Or => "i64.or",
+        I64Xor => "i64.xor",
+        I64Shl => "i64.shl",
+        I64ShrS => "i64.shr_s",
+        I64ShrU => "i64.shr_u",
+        I64Rotl => "i64.rotl",
+        I64Rotr => "i64.rotr",
+        I32WrapI64 => "i32.wrap_i64",
+        I64ExtendI32S => "i64.extend_i32_s",
+        I64ExtendI32U => "i64.extend_i32_u",
+        I32Extend8S => "i32.extend8_s",
+        I32Extend16S => "i32.extend16_s",
+        I64Extend8S => "i64.extend8_s",
+        I64Extend16S => "i64.extend16_s",
+        I64Extend32S => "i64.extend32_s",
+    };
+    name.to_owned()
+}